@@ -2,7 +2,10 @@
 //!
 //! This also combines the textures into one big atlas.
 
-use std::fs;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
 use bevy::{
     prelude::*,
@@ -11,6 +14,7 @@ use bevy::{
 };
 use cosmos_core::{
     block::{Block, BlockFace},
+    content::{ContentPack, ContentPacks, BASE_NAMESPACE},
     loader::{AddLoadingEvent, DoneLoadingEvent, LoadingManager},
     registry::{self, identifiable::Identifiable, Registry},
 };
@@ -37,6 +41,21 @@ struct AssetsLoadingID(usize);
 #[derive(Resource, Debug)]
 struct AssetsLoading(Vec<LoadingAsset>);
 
+/// Where block textures are, relative to the assets directory or a content pack's directory
+const BLOCK_IMAGES_DIRECTORY: &str = "images/blocks";
+
+/// The number of textures used to show how damaged a block is, from least to most damaged.
+///
+/// Each one is registered in the `Registry<BlockTextureIndex>` as `damage_stage_{stage}`.
+pub const DAMAGE_STAGES: usize = 4;
+
+/// The path the asset server uses for a content pack's directory.
+///
+/// Asset paths are relative to the client's assets directory, while a pack's directory is relative to where the game is run from.
+fn pack_asset_path(pack: &ContentPack) -> PathBuf {
+    Path::new("..").join(pack.directory())
+}
+
 #[derive(Resource, Reflect, FromReflect, Debug)]
 /// This stores the texture atlas for all blocks in the game.
 ///
@@ -88,14 +107,29 @@ fn setup(
     mut loading: ResMut<AssetsLoading>,
     mut loader: ResMut<LoadingManager>,
     mut start_writer: EventWriter<AddLoadingEvent>,
+    packs: Res<ContentPacks>,
 ) {
+    let mut handles = server
+        .load_folder("images/blocks/")
+        .expect("error loading blocks textures");
+
+    for pack in packs
+        .iter()
+        .filter(|pack| pack.namespace() != BASE_NAMESPACE)
+    {
+        if pack.directory().join(BLOCK_IMAGES_DIRECTORY).is_dir() {
+            handles.append(
+                &mut server
+                    .load_folder(pack_asset_path(pack).join(BLOCK_IMAGES_DIRECTORY))
+                    .unwrap_or_else(|e| {
+                        panic!("error loading blocks textures for pack {} - {e}", pack.id())
+                    }),
+            );
+        }
+    }
+
     loading.0.push(LoadingAsset {
-        handles: server
-            .load_folder("images/blocks/")
-            .expect("error loading blocks textures")
-            .into_iter()
-            .map(|x| x.typed::<Image>())
-            .collect(),
+        handles: handles.into_iter().map(|x| x.typed::<Image>()).collect(),
         atlas_name: AtlasName::Main,
     });
 
@@ -215,7 +249,10 @@ fn check_assets_ready(
 
                         for handle in &asset.handles {
                             let Some(image) = images.get(handle) else {
-                                warn!("{:?} did not resolve to an `Image` asset.", server.get_handle_path(handle));
+                                warn!(
+                                    "{:?} did not resolve to an `Image` asset.",
+                                    server.get_handle_path(handle)
+                                );
                                 continue;
                             };
 
@@ -365,6 +402,7 @@ fn load_block_textxures(
     blocks: Res<Registry<Block>>,
     atlas: Res<MainAtlas>,
     server: Res<AssetServer>,
+    packs: Res<ContentPacks>,
     mut registry: ResMut<Registry<BlockTextureIndex>>,
 ) {
     if let Some(index) = atlas
@@ -378,6 +416,18 @@ fn load_block_textxures(
         });
     }
 
+    for stage in 0..DAMAGE_STAGES {
+        if let Some(index) = atlas.atlas.get_texture_index(&server.get_handle(&format!(
+            "{BLOCK_IMAGES_DIRECTORY}/damage_stage_{stage}.png"
+        ))) {
+            registry.register(BlockTextureIndex {
+                id: 0,
+                unlocalized_name: format!("damage_stage_{stage}"),
+                indices: BlockTextureIndicies::all(index),
+            });
+        }
+    }
+
     for block in blocks.iter() {
         let unlocalized_name = block.unlocalized_name();
         let block_name = unlocalized_name
@@ -385,7 +435,20 @@ fn load_block_textxures(
             .nth(1)
             .unwrap_or(unlocalized_name);
 
-        let json_path = format!("assets/blocks/{block_name}.json");
+        // Blocks from a content pack look in that pack first, then fall back to the game's own assets
+        let pack = packs
+            .from_unlocalized_name(unlocalized_name)
+            .filter(|pack| pack.namespace() != BASE_NAMESPACE);
+
+        let json_path = match pack {
+            Some(pack) => pack
+                .directory()
+                .join("blocks")
+                .join(format!("{block_name}.json"))
+                .display()
+                .to_string(),
+            None => format!("assets/blocks/{block_name}.json"),
+        };
 
         let block_info = if let Ok(block_info) = fs::read(&json_path) {
             serde_json::from_slice::<BlockInfo>(&block_info)
@@ -398,9 +461,25 @@ fn load_block_textxures(
 
         let mut map = HashMap::new();
         for (entry, texture_name) in block_info.texture.iter() {
-            if let Some(index) = atlas.atlas.get_texture_index(
-                &server.get_handle(&format!("images/blocks/{texture_name}.png",)),
-            ) {
+            let file_name = format!("{texture_name}.png");
+
+            let index = pack
+                .and_then(|pack| {
+                    let path = pack_asset_path(pack)
+                        .join(BLOCK_IMAGES_DIRECTORY)
+                        .join(&file_name);
+
+                    atlas
+                        .atlas
+                        .get_texture_index(&server.get_handle(path.as_path()))
+                })
+                .or_else(|| {
+                    atlas.atlas.get_texture_index(
+                        &server.get_handle(&format!("{BLOCK_IMAGES_DIRECTORY}/{file_name}")),
+                    )
+                });
+
+            if let Some(index) = index {
                 map.insert(entry.to_owned(), index);
             }
         }
@@ -5,7 +5,7 @@ use bevy::{
     reflect::{FromReflect, Reflect},
 };
 use cosmos_core::{
-    block::Block,
+    block::{definitions::BlockDefinition, Block},
     registry::{self, identifiable::Identifiable, Registry},
 };
 use serde::{Deserialize, Serialize};
@@ -70,31 +70,26 @@ fn register_light(
 
 fn register_all_lights(
     blocks: Res<Registry<Block>>,
+    definitions: Res<Registry<BlockDefinition>>,
     mut registry: ResMut<Registry<BlockLighting>>,
 ) {
-    register_light(
-        BlockLightProperties {
-            color: Color::WHITE,
-            intensity: 500.0,
-            range: 12.0,
-            ..Default::default()
-        },
-        &mut registry,
-        &blocks,
-        "cosmos:light",
-    );
+    for definition in definitions.iter() {
+        if let Some(light) = definition.light {
+            let [r, g, b] = light.color;
 
-    register_light(
-        BlockLightProperties {
-            color: Color::rgb(81.0 / 255.0, 143.0 / 255.0, 225.0 / 255.0),
-            intensity: 100.0,
-            range: 6.0,
-            ..Default::default()
-        },
-        &mut registry,
-        &blocks,
-        "cosmos:ship_core",
-    );
+            register_light(
+                BlockLightProperties {
+                    color: Color::rgb_u8(r, g, b),
+                    intensity: light.intensity,
+                    range: light.range,
+                    ..Default::default()
+                },
+                &mut registry,
+                &blocks,
+                definition.unlocalized_name(),
+            );
+        }
+    }
 }
 
 pub(super) fn register(app: &mut App) {
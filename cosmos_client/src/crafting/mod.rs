@@ -0,0 +1,100 @@
+//! Asks the server for its recipes & to craft them
+
+use bevy::prelude::*;
+use bevy_renet::renet::RenetClient;
+use cosmos_core::{
+    crafting::{netty::CraftingClientMessages, recipes::Recipe},
+    inventory::Inventory,
+    netty::{cosmos_encoder, NettyChannel},
+    registry::identifiable::Identifiable,
+    structure::structure_block::StructureBlock,
+};
+
+use crate::{netty::mapping::NetworkMapping, state::game_state::GameState};
+
+mod netty;
+
+#[derive(Resource, Debug, Default)]
+/// Every recipe the server has, with their item & block ids translated into this client's.
+///
+/// Each recipe keeps the server's id for it, since that's what the server expects in a craft request.
+pub struct ServerRecipes {
+    recipes: Vec<Recipe>,
+}
+
+impl ServerRecipes {
+    /// Iterates over every recipe
+    pub fn iter(&self) -> std::slice::Iter<'_, Recipe> {
+        self.recipes.iter()
+    }
+
+    /// Iterates over every recipe that has all its inputs in this inventory
+    pub fn craftable<'a>(&'a self, inventory: &'a Inventory) -> impl Iterator<Item = &'a Recipe> {
+        self.recipes
+            .iter()
+            .filter(|recipe| recipe.has_inputs(inventory))
+    }
+}
+
+/// Send this to ask the server to craft a recipe with the items in the player's inventory
+pub struct CraftEvent {
+    /// The server's id of the recipe, which is [`Identifiable::id`] of the recipes in [`ServerRecipes`]
+    pub recipe_id: u16,
+    /// The client's entity for the structure the station is on, and the station block.
+    ///
+    /// This must be the container the player has open. None for recipes crafted by hand.
+    pub station: Option<(Entity, StructureBlock)>,
+}
+
+impl CraftEvent {
+    /// Crafts this recipe, optionally at a station
+    pub fn new(recipe: &Recipe, station: Option<(Entity, StructureBlock)>) -> Self {
+        Self {
+            recipe_id: recipe.id(),
+            station,
+        }
+    }
+}
+
+fn request_recipes(mut client: ResMut<RenetClient>) {
+    client.send_message(
+        NettyChannel::Crafting.id(),
+        cosmos_encoder::serialize(&CraftingClientMessages::RequestRecipes),
+    );
+}
+
+fn send_craft_requests(
+    mut event_reader: EventReader<CraftEvent>,
+    network_mapping: Res<NetworkMapping>,
+    mut client: ResMut<RenetClient>,
+) {
+    for ev in event_reader.iter() {
+        let station = match ev.station {
+            Some((structure_entity, block)) => {
+                let Some(structure_entity) = network_mapping.server_from_client(&structure_entity) else {
+                    continue;
+                };
+
+                Some((structure_entity, block))
+            }
+            None => None,
+        };
+
+        client.send_message(
+            NettyChannel::Crafting.id(),
+            cosmos_encoder::serialize(&CraftingClientMessages::Craft {
+                recipe_id: ev.recipe_id,
+                station,
+            }),
+        );
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    netty::register(app);
+
+    app.init_resource::<ServerRecipes>()
+        .add_event::<CraftEvent>()
+        .add_system(request_recipes.in_schedule(OnEnter(GameState::Playing)))
+        .add_system(send_craft_requests.in_set(OnUpdate(GameState::Playing)));
+}
@@ -0,0 +1,47 @@
+//! Receives the crafting messages the server sends
+
+use bevy::prelude::*;
+use bevy_renet::renet::RenetClient;
+use cosmos_core::{
+    crafting::netty::CraftingServerMessages,
+    netty::{cosmos_encoder, NettyChannel},
+    registry::identifiable::Identifiable,
+};
+
+use crate::{netty::registry_sync::ServerRegistryIds, state::game_state::GameState};
+
+use super::ServerRecipes;
+
+fn receive_crafting_messages(
+    mut client: ResMut<RenetClient>,
+    server_registry_ids: Res<ServerRegistryIds>,
+    mut server_recipes: ResMut<ServerRecipes>,
+) {
+    while let Some(message) = client.receive_message(NettyChannel::Crafting.id()) {
+        let msg: CraftingServerMessages = cosmos_encoder::deserialize(&message).unwrap();
+
+        match msg {
+            CraftingServerMessages::Recipes { mut recipes } => {
+                for recipe in recipes.iter_mut() {
+                    server_registry_ids.remap_recipe(recipe);
+                }
+
+                server_recipes.recipes = recipes;
+            }
+            CraftingServerMessages::CraftAccepted { .. } => {}
+            CraftingServerMessages::CraftRejected { recipe_id, reason } => {
+                let name = server_recipes
+                    .iter()
+                    .find(|recipe| recipe.id() == recipe_id)
+                    .map(|recipe| recipe.unlocalized_name().to_owned())
+                    .unwrap_or_else(|| format!("#{recipe_id}"));
+
+                warn!("Unable to craft {name} - {reason}");
+            }
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_system(receive_crafting_messages.in_set(OnUpdate(GameState::Playing)));
+}
@@ -3,11 +3,15 @@
 use bevy::prelude::*;
 use bevy_renet::renet::RenetClient;
 use cosmos_core::{
-    block::BlockFace,
+    block::BlockRotation,
     netty::{client_reliable_messages::ClientReliableMessages, cosmos_encoder, NettyChannel},
+    structure::Structure,
 };
 
-use crate::{netty::mapping::NetworkMapping, state::game_state::GameState};
+use crate::{
+    netty::{mapping::NetworkMapping, registry_sync::ServerRegistryIds},
+    state::game_state::GameState,
+};
 
 #[derive(Debug)]
 /// Sent when this client breaks a block
@@ -37,8 +41,8 @@ pub struct BlockPlaceEvent {
     pub inventory_slot: usize,
     /// The block's id
     pub block_id: u16,
-    /// The block's top face
-    pub block_up: BlockFace,
+    /// The block's rotation
+    pub block_rotation: BlockRotation,
 }
 
 #[derive(Debug)]
@@ -54,10 +58,28 @@ pub struct BlockInteractEvent {
     pub z: usize,
 }
 
+/// The size in chunks this client thinks the structure is, so the server can tell if its coordinates are out of date
+fn structure_size(
+    structure_query: &Query<&Structure>,
+    structure_entity: Entity,
+) -> (u32, u32, u32) {
+    structure_query
+        .get(structure_entity)
+        .map(|structure| {
+            (
+                structure.chunks_width() as u32,
+                structure.chunks_height() as u32,
+                structure.chunks_length() as u32,
+            )
+        })
+        .unwrap_or_default()
+}
+
 fn handle_block_break(
     mut event_reader: EventReader<BlockBreakEvent>,
     mut client: ResMut<RenetClient>,
     network_mapping: Res<NetworkMapping>,
+    structure_query: Query<&Structure>,
 ) {
     for ev in event_reader.iter() {
         client.send_message(
@@ -69,6 +91,7 @@ fn handle_block_break(
                 x: ev.x as u32,
                 y: ev.y as u32,
                 z: ev.z as u32,
+                structure_size: structure_size(&structure_query, ev.structure_entity),
             }),
         );
     }
@@ -78,6 +101,8 @@ fn handle_block_place(
     mut event_reader: EventReader<BlockPlaceEvent>,
     mut client: ResMut<RenetClient>,
     network_mapping: Res<NetworkMapping>,
+    server_registry_ids: Res<ServerRegistryIds>,
+    structure_query: Query<&Structure>,
 ) {
     for ev in event_reader.iter() {
         client.send_message(
@@ -89,8 +114,9 @@ fn handle_block_place(
                 x: ev.x as u32,
                 y: ev.y as u32,
                 z: ev.z as u32,
-                block_id: ev.block_id,
-                block_up: ev.block_up,
+                structure_size: structure_size(&structure_query, ev.structure_entity),
+                block_id: server_registry_ids.block_to_server(ev.block_id),
+                block_rotation: ev.block_rotation,
                 inventory_slot: ev.inventory_slot as u32,
             }),
         );
@@ -101,6 +127,7 @@ fn handle_block_interact(
     mut event_reader: EventReader<BlockInteractEvent>,
     mut client: ResMut<RenetClient>,
     network_mapping: Res<NetworkMapping>,
+    structure_query: Query<&Structure>,
 ) {
     for ev in event_reader.iter() {
         client.send_message(
@@ -112,6 +139,7 @@ fn handle_block_interact(
                 x: ev.x as u32,
                 y: ev.y as u32,
                 z: ev.z as u32,
+                structure_size: structure_size(&structure_query, ev.structure_entity),
             }),
         );
     }
@@ -50,6 +50,9 @@ pub enum CosmosInputs {
     /// Unlocks the mouse from the window
     UnlockMouse,
 
+    /// Opens or closes the player's inventory
+    ToggleInventory,
+
     /// Change the selected block system while piloting ship
     SelectSystem1,
     /// Change the selected block system while piloting ship
@@ -116,6 +119,8 @@ fn init_input(mut input_handler: ResMut<CosmosInputHandler>) {
 
     input_handler.set_keycode(CosmosInputs::UnlockMouse, KeyCode::Escape);
 
+    input_handler.set_keycode(CosmosInputs::ToggleInventory, KeyCode::I);
+
     input_handler.set_keycode(CosmosInputs::HotbarSlot1, KeyCode::Key1);
     input_handler.set_keycode(CosmosInputs::HotbarSlot2, KeyCode::Key2);
     input_handler.set_keycode(CosmosInputs::HotbarSlot3, KeyCode::Key3);
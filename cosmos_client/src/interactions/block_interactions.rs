@@ -3,7 +3,7 @@
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::{QueryFilter, RapierContext};
 use cosmos_core::{
-    block::BlockFace,
+    block::{BlockFace, BlockRotation},
     blockitems::BlockItems,
     inventory::Inventory,
     item::Item,
@@ -16,7 +16,7 @@ use crate::{
     input::inputs::{CosmosInputHandler, CosmosInputs},
     rendering::MainCamera,
     state::game_state::GameState,
-    ui::hotbar::Hotbar,
+    ui::{hotbar::Hotbar, inventory::inventory_screen_closed},
     LocalPlayer,
 };
 
@@ -32,6 +32,23 @@ pub enum InteractionType {
 //     interaction_type: InteractionType,
 // }
 
+/// Points the front of a placed block the way the player is looking, with its top as close to the player's up as possible
+fn placement_rotation(
+    camera_transform: &GlobalTransform,
+    structure_transform: &GlobalTransform,
+) -> BlockRotation {
+    let inverse_rotation = structure_transform.compute_transform().rotation.inverse();
+
+    let front = BlockFace::from_direction_vec3(inverse_rotation * camera_transform.forward());
+    let front_direction = front.direction_vec3();
+
+    // Ignore the part of up that's along the front so they can't end up being the same axis
+    let up = inverse_rotation * camera_transform.up();
+    let block_up = BlockFace::from_direction_vec3(up - front_direction * up.dot(front_direction));
+
+    BlockRotation::facing(block_up, front).unwrap_or(BlockRotation::IDENTITY)
+}
+
 // make this not horrible at some point please
 fn process_player_interaction(
     keys: Res<Input<KeyCode>>,
@@ -53,7 +70,7 @@ fn process_player_interaction(
 ) {
     let trans = camera.get_single().unwrap();
     if let Ok(player_body) = player_body.get_single() {
-        if let Ok(Some((entity, intersection))) = rapier_context.cast_ray_and_get_normal(
+        if let Ok(Some((entity, _))) = rapier_context.cast_ray_and_get_normal(
             0,
             trans.translation(),
             trans.forward(),
@@ -63,23 +80,16 @@ fn process_player_interaction(
         ) {
             if let Ok(parent) = parent_query.get(entity) {
                 if let Ok((structure, transform, is_planet)) = structure_query.get(parent.get()) {
-                    if input_handler.check_just_pressed(CosmosInputs::BreakBlock, &keys, &mouse) {
-                        let moved_point = intersection.point - intersection.normal * 0.3;
-
-                        let point = transform
-                            .compute_matrix()
-                            .inverse()
-                            .transform_point3(moved_point);
-
-                        let (x, y, z) = structure
-                            .relative_coords_to_local_coords_checked(point.x, point.y, point.z)
-                            .expect("Tried to break block outside of structure?");
+                    let Some(hit) = structure.raycast(trans.translation(), trans.forward(), 10.0, transform) else {
+                        return;
+                    };
 
+                    if input_handler.check_just_pressed(CosmosInputs::BreakBlock, &keys, &mouse) {
                         break_writer.send(BlockBreakEvent {
                             structure_entity: structure.get_entity().unwrap(),
-                            x,
-                            y,
-                            z,
+                            x: hit.block.x,
+                            y: hit.block.y,
+                            z: hit.block.z,
                         });
                     }
 
@@ -93,40 +103,40 @@ fn process_player_interaction(
                                     let item = items.from_numeric_id(is.item_id());
 
                                     if let Some(block_id) = block_items.block_from_item(item) {
-                                        let moved_point =
-                                            intersection.point + intersection.normal * 0.95;
-
-                                        let point = transform
-                                            .compute_matrix()
-                                            .inverse()
-                                            .transform_point3(moved_point);
-
-                                        if let Ok((x, y, z)) = structure
-                                            .relative_coords_to_local_coords_checked(
-                                                point.x, point.y, point.z,
+                                        let (dx, dy, dz) = hit.face.direction();
+
+                                        let (x, y, z) = (
+                                            hit.block.x as i32 + dx,
+                                            hit.block.y as i32 + dy,
+                                            hit.block.z as i32 + dz,
+                                        );
+
+                                        if x >= 0
+                                            && y >= 0
+                                            && z >= 0
+                                            && structure.is_within_blocks(
+                                                x as usize, y as usize, z as usize,
                                             )
                                         {
-                                            if structure.is_within_blocks(x, y, z) {
-                                                inventory.decrease_quantity_at(inventory_slot, 1);
-
-                                                let block_up = if is_planet.is_some() {
-                                                    Planet::planet_face(structure, x, y, z)
-                                                } else {
-                                                    BlockFace::Top
-                                                };
-
-                                                place_writer.send(BlockPlaceEvent {
-                                                    structure_entity: structure
-                                                        .get_entity()
-                                                        .unwrap(),
-                                                    x,
-                                                    y,
-                                                    z,
-                                                    inventory_slot,
-                                                    block_id,
-                                                    block_up,
-                                                });
-                                            }
+                                            let (x, y, z) = (x as usize, y as usize, z as usize);
+
+                                            inventory.decrease_quantity_at(inventory_slot, 1);
+
+                                            let block_rotation = if is_planet.is_some() {
+                                                Planet::planet_face(structure, x, y, z).into()
+                                            } else {
+                                                placement_rotation(trans, transform)
+                                            };
+
+                                            place_writer.send(BlockPlaceEvent {
+                                                structure_entity: structure.get_entity().unwrap(),
+                                                x,
+                                                y,
+                                                z,
+                                                inventory_slot,
+                                                block_id,
+                                                block_rotation,
+                                            });
                                         }
                                     }
                                 }
@@ -135,22 +145,11 @@ fn process_player_interaction(
                     }
 
                     if input_handler.check_just_pressed(CosmosInputs::Interact, &keys, &mouse) {
-                        let moved_point = intersection.point - intersection.normal * 0.3;
-
-                        let point = transform
-                            .compute_matrix()
-                            .inverse()
-                            .transform_point3(moved_point);
-
-                        let (x, y, z) = structure
-                            .relative_coords_to_local_coords_checked(point.x, point.y, point.z)
-                            .unwrap();
-
                         interact_writer.send(BlockInteractEvent {
                             structure_entity: structure.get_entity().unwrap(),
-                            x,
-                            y,
-                            z,
+                            x: hit.block.x,
+                            y: hit.block.y,
+                            z: hit.block.z,
                         });
                     }
                 }
@@ -162,5 +161,9 @@ fn process_player_interaction(
 pub(super) fn register(app: &mut App) {
     app
         // .add_event::<BlockInteractionEvent>()
-        .add_system(process_player_interaction.in_set(OnUpdate(GameState::Playing)));
+        .add_system(
+            process_player_interaction
+                .run_if(inventory_screen_closed)
+                .in_set(OnUpdate(GameState::Playing)),
+        );
 }
@@ -0,0 +1,37 @@
+//! Keeps track of the container block the player has open, if any
+
+use bevy::prelude::*;
+use bevy_renet::renet::RenetClient;
+use cosmos_core::{
+    inventory::{netty::InventoryClientMessages, Inventory},
+    netty::{cosmos_encoder, NettyChannel},
+    structure::structure_block::StructureBlock,
+};
+
+#[derive(Resource, Debug)]
+/// The container the player has open. This only exists while a container is open.
+pub struct OpenContainer {
+    /// The client's entity for the structure the container is on
+    pub structure_entity: Entity,
+    /// The container block
+    pub block: StructureBlock,
+    /// The client's copy of what's in the container
+    pub inventory: Inventory,
+}
+
+impl OpenContainer {
+    /// Checks if this is the container on that block
+    pub fn is(&self, structure_entity: Entity, block: StructureBlock) -> bool {
+        self.structure_entity == structure_entity && self.block == block
+    }
+}
+
+/// Tells the server the player closed the container they have open
+pub fn close_container(client: &mut RenetClient, commands: &mut Commands) {
+    client.send_message(
+        NettyChannel::Inventory.id(),
+        cosmos_encoder::serialize(&InventoryClientMessages::CloseContainer),
+    );
+
+    commands.remove_resource::<OpenContainer>();
+}
@@ -23,6 +23,10 @@ use crate::{
     state::game_state::GameState,
 };
 
+pub mod container;
+mod netty;
+pub mod transactions;
+
 const INVENTORY_SLOT_LAYER: u8 = 10;
 
 #[derive(Component)]
@@ -155,6 +159,9 @@ fn render_hotbar(
 struct HotbarLocation;
 
 pub(super) fn register(app: &mut App) {
+    netty::register(app);
+    transactions::register(app);
+
     app.add_system(
         |query: Query<&Window, With<PrimaryWindow>>,
          mut cam: Query<&mut Transform, With<HotbarLocation>>| {
@@ -0,0 +1,121 @@
+//! Receives the inventory messages the server sends
+
+use bevy::prelude::*;
+use bevy_renet::renet::RenetClient;
+use cosmos_core::{
+    inventory::{netty::InventoryServerMessages, transaction::apply_slot_change, Inventory},
+    netty::{cosmos_encoder, NettyChannel},
+};
+
+use crate::{
+    netty::{mapping::NetworkMapping, registry_sync::ServerRegistryIds},
+    state::game_state::GameState,
+};
+
+use super::{
+    container::OpenContainer,
+    transactions::{client_inventory, PendingTransactions},
+};
+
+fn receive_inventory_messages(
+    mut client: ResMut<RenetClient>,
+    network_mapping: Res<NetworkMapping>,
+    server_registry_ids: Res<ServerRegistryIds>,
+    mut open_container: Option<ResMut<OpenContainer>>,
+    mut inventory_query: Query<&mut Inventory>,
+    mut pending: ResMut<PendingTransactions>,
+    mut commands: Commands,
+) {
+    while let Some(message) = client.receive_message(NettyChannel::Inventory.id()) {
+        let msg: InventoryServerMessages = cosmos_encoder::deserialize(&message).unwrap();
+
+        match msg {
+            InventoryServerMessages::ContainerInventory {
+                structure_entity,
+                block,
+                serialized_inventory,
+            } => {
+                let Some(structure_entity) = network_mapping.client_from_server(&structure_entity) else {
+                    continue;
+                };
+
+                let mut inventory: Inventory =
+                    cosmos_encoder::deserialize(&serialized_inventory).unwrap();
+                server_registry_ids.remap_inventory(&mut inventory);
+
+                commands.insert_resource(OpenContainer {
+                    structure_entity,
+                    block,
+                    inventory,
+                });
+            }
+            InventoryServerMessages::ContainerClosed {
+                structure_entity,
+                block,
+            } => {
+                let is_open = open_container
+                    .as_ref()
+                    .zip(network_mapping.client_from_server(&structure_entity))
+                    .map(|(open, structure_entity)| open.is(structure_entity, block))
+                    .unwrap_or(false);
+
+                if is_open {
+                    commands.remove_resource::<OpenContainer>();
+                }
+            }
+            InventoryServerMessages::SlotsChanged { inventory, slots } => {
+                let Some(inventory) = inventory.map_entities(|entity| network_mapping.client_from_server(&entity)) else {
+                    continue;
+                };
+
+                let Some(inventory) = client_inventory(
+                    inventory,
+                    &mut inventory_query,
+                    open_container.as_deref_mut(),
+                ) else {
+                    continue;
+                };
+
+                for (slot, mut item_stack) in slots {
+                    if let Some(item_stack) = item_stack.as_mut() {
+                        server_registry_ids.remap_item_stack(item_stack);
+                    }
+
+                    apply_slot_change(inventory, slot, item_stack);
+                }
+            }
+            InventoryServerMessages::TransactionAccepted { id } => {
+                pending.accept(id);
+            }
+            InventoryServerMessages::TransactionRejected {
+                id,
+                reason,
+                inventories,
+            } => {
+                warn!("Inventory transaction rejected - {reason}");
+
+                pending.reject(id);
+
+                for (identifier, mut current) in inventories {
+                    let Some(identifier) = identifier.map_entities(|entity| network_mapping.client_from_server(&entity)) else {
+                        continue;
+                    };
+
+                    server_registry_ids.remap_inventory(&mut current);
+
+                    if let Some(inventory) = client_inventory(
+                        identifier,
+                        &mut inventory_query,
+                        open_container.as_deref_mut(),
+                    ) {
+                        *inventory = current;
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_system(receive_inventory_messages.in_set(OnUpdate(GameState::Playing)));
+}
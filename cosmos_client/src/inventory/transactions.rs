@@ -0,0 +1,134 @@
+//! Makes inventory transactions, applying them right away & replacing the inventories with the server's copies if it rejects them
+
+use bevy::prelude::*;
+use bevy_renet::renet::RenetClient;
+use cosmos_core::{
+    inventory::{
+        netty::InventoryClientMessages,
+        transaction::{InventoryIdentifier, InventoryTransaction},
+        Inventory,
+    },
+    netty::{cosmos_encoder, NettyChannel},
+};
+
+use crate::{netty::mapping::NetworkMapping, state::game_state::GameState};
+
+use super::container::OpenContainer;
+
+/// Send this to rearrange the items in the player's inventory or the container they have open.
+///
+/// The transaction should use the client's entities. It is applied right away, and undone if the server rejects it.
+pub struct InventoryTransactionEvent(pub InventoryTransaction);
+
+#[derive(Resource, Debug, Default)]
+/// The ids of every transaction the server hasn't replied to yet, oldest first
+pub(super) struct PendingTransactions {
+    next_id: u32,
+    pending: Vec<u32>,
+}
+
+impl PendingTransactions {
+    /// The server applied this transaction. It already sent the slots it changed.
+    pub(super) fn accept(&mut self, id: u32) {
+        self.pending.retain(|pending| *pending != id);
+    }
+
+    /// The server rejected this transaction, and sent the current contents of the inventories it used to replace the client's copies.
+    ///
+    /// Any transactions made after it are still pending. The server replies to each of those on its own,
+    /// so their changes are either sent as changed slots or replaced the same way.
+    pub(super) fn reject(&mut self, id: u32) {
+        self.pending.retain(|pending| *pending != id);
+    }
+}
+
+/// Gets the client's copy of this inventory, if the client has it
+pub(super) fn client_inventory<'a>(
+    identifier: InventoryIdentifier,
+    inventory_query: &'a mut Query<&mut Inventory>,
+    open_container: Option<&'a mut OpenContainer>,
+) -> Option<&'a mut Inventory> {
+    match identifier {
+        InventoryIdentifier::Entity(entity) => inventory_query
+            .get_mut(entity)
+            .ok()
+            .map(|inventory| inventory.into_inner()),
+        InventoryIdentifier::Container {
+            structure_entity,
+            block,
+        } => open_container
+            .filter(|open| open.is(structure_entity, block))
+            .map(|open| &mut open.inventory),
+    }
+}
+
+fn send_transactions(
+    mut event_reader: EventReader<InventoryTransactionEvent>,
+    mut inventory_query: Query<&mut Inventory>,
+    mut open_container: Option<ResMut<OpenContainer>>,
+    mut pending: ResMut<PendingTransactions>,
+    network_mapping: Res<NetworkMapping>,
+    mut client: ResMut<RenetClient>,
+) {
+    for InventoryTransactionEvent(transaction) in event_reader.iter() {
+        let Some(server_transaction) = transaction.map_entities(|entity| network_mapping.server_from_client(&entity)) else {
+            continue;
+        };
+
+        let from = transaction.from().inventory;
+        let to = transaction.to_inventory();
+
+        let Some(mut from_after) = client_inventory(from, &mut inventory_query, open_container.as_deref_mut()).cloned() else {
+            continue;
+        };
+
+        let mut to_after = if to != from {
+            let Some(to_after) = client_inventory(to, &mut inventory_query, open_container.as_deref_mut()).cloned() else {
+                continue;
+            };
+
+            Some(to_after)
+        } else {
+            None
+        };
+
+        // The server would reject this anyway
+        if let Err(e) = transaction.apply(&mut from_after, to_after.as_mut()) {
+            warn!("Invalid inventory transaction - {e}");
+            continue;
+        }
+
+        if let Some(inventory) =
+            client_inventory(from, &mut inventory_query, open_container.as_deref_mut())
+        {
+            *inventory = from_after;
+        }
+
+        if let Some(to_after) = to_after {
+            if let Some(inventory) =
+                client_inventory(to, &mut inventory_query, open_container.as_deref_mut())
+            {
+                *inventory = to_after;
+            }
+        }
+
+        let id = pending.next_id;
+        pending.next_id = pending.next_id.wrapping_add(1);
+
+        pending.pending.push(id);
+
+        client.send_message(
+            NettyChannel::Inventory.id(),
+            cosmos_encoder::serialize(&InventoryClientMessages::Transaction {
+                id,
+                transaction: server_transaction,
+            }),
+        );
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_event::<InventoryTransactionEvent>()
+        .init_resource::<PendingTransactions>()
+        .add_system(send_transactions.in_set(OnUpdate(GameState::Playing)));
+}
@@ -1,5 +1,5 @@
 use bevy::prelude::{App, Commands, IntoSystemAppConfig, OnEnter, OnExit, Res, ResMut};
-use cosmos_core::{item::Item, registry::Registry};
+use cosmos_core::{content::ContentPacks, item::Item, registry::Registry};
 
 use crate::state::game_state::GameState;
 
@@ -11,11 +11,12 @@ fn insert_langs(mut item_langs: ResMut<Lang<Item>>, items: Res<Registry<Item>>)
     }
 }
 
-fn insert_resource(mut commands: Commands) {
-    commands.insert_resource(Lang::<Item>::new("en_us", vec!["items", "blocks"]));
+fn insert_resource(mut commands: Commands, packs: Res<ContentPacks>) {
+    commands.insert_resource(Lang::<Item>::new("en_us", vec!["items", "blocks"], &packs));
 }
 
 pub(super) fn register(app: &mut App) {
-    app.add_system(insert_resource.in_schedule(OnEnter(GameState::PreLoading)))
+    // Content packs are found during pre-loading
+    app.add_system(insert_resource.in_schedule(OnEnter(GameState::Loading)))
         .add_system(insert_langs.in_schedule(OnExit(GameState::PostLoading)));
 }
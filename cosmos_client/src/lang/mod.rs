@@ -2,13 +2,16 @@
 
 mod load_langs;
 
-use std::{fs, marker::PhantomData};
+use std::{fs, marker::PhantomData, path::Path};
 
 use bevy::{
     prelude::{App, Resource},
     utils::HashMap,
 };
-use cosmos_core::registry::identifiable::Identifiable;
+use cosmos_core::{
+    content::{ContentPacks, BASE_NAMESPACE},
+    registry::identifiable::Identifiable,
+};
 
 #[derive(Resource)]
 /// Used to get the human-readable + localized text to display for identifiable types
@@ -19,9 +22,9 @@ pub struct Lang<T: Identifiable + Send + Sync> {
     _phantom: PhantomData<T>,
 }
 
-fn load_data(lang_type: &str, lang_folder: &str, map: &mut HashMap<String, String>) {
-    let path = format!("assets/lang/{lang_folder}/{lang_type}.lang");
-    let str = fs::read_to_string(path.clone())
+fn load_data(path: &Path, map: &mut HashMap<String, String>) {
+    let path = path.display();
+    let str = fs::read_to_string(path.to_string())
         .unwrap_or_else(|_| panic!("Error reading lang file @ '{path}'!"));
 
     for line in str
@@ -46,11 +49,34 @@ impl<T: Identifiable + Send + Sync> Lang<T> {
     ///
     /// * `lang_type` The language identifier, such as en_us
     /// * `read_from` These are the files that should be read from for the language data. These should be sorted in order of importance - data found in the file N will override data found files N + X.
-    pub fn new(lang_type: &str, read_from: Vec<&str>) -> Self {
+    /// * `packs` Any content pack that has these files will also be read from, after the game's own files
+    pub fn new(lang_type: &str, read_from: Vec<&str>, packs: &ContentPacks) -> Self {
         let mut lang_contents = HashMap::new();
 
-        for fallback in read_from {
-            load_data(lang_type, fallback, &mut lang_contents);
+        for fallback in read_from.iter() {
+            load_data(
+                &Path::new("assets/lang")
+                    .join(fallback)
+                    .join(format!("{lang_type}.lang")),
+                &mut lang_contents,
+            );
+        }
+
+        for pack in packs
+            .iter()
+            .filter(|pack| pack.namespace() != BASE_NAMESPACE)
+        {
+            for fallback in read_from.iter() {
+                let path = pack
+                    .directory()
+                    .join("lang")
+                    .join(fallback)
+                    .join(format!("{lang_type}.lang"));
+
+                if path.is_file() {
+                    load_data(&path, &mut lang_contents);
+                }
+            }
         }
 
         Self {
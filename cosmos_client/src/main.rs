@@ -5,6 +5,7 @@
 pub mod asset;
 pub mod block;
 pub mod camera;
+pub mod crafting;
 pub mod entities;
 pub mod events;
 pub mod input;
@@ -342,6 +343,7 @@ fn main() {
     loading::register(&mut app);
     entities::register(&mut app);
     inventory::register(&mut app);
+    crafting::register(&mut app);
     rendering::register(&mut app);
     universe::register(&mut app);
     skybox::register(&mut app);
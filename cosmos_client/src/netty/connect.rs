@@ -18,6 +18,7 @@ use crate::{
     netty::{
         lobby::{ClientLobby, MostRecentTick},
         mapping::NetworkMapping,
+        registry_sync::ServerRegistryIds,
     },
     state::game_state::GameState,
 };
@@ -76,6 +77,7 @@ pub fn establish_connection(mut commands: Commands, connection_config: Res<Conne
     commands.insert_resource(MostRecentTick(None));
     commands.insert_resource(new_renet_client(connection_config.host_name.as_str()));
     commands.insert_resource(NetworkMapping::default());
+    commands.insert_resource(ServerRegistryIds::default());
 }
 
 /// Waits for a connection to be made, then changes the game state to `GameState::LoadingWorld`.
@@ -9,11 +9,20 @@ use bevy::{
 use bevy_rapier3d::prelude::*;
 use bevy_renet::renet::RenetClient;
 use cosmos_core::{
-    block::Block,
+    block::{hardness::BlockHardness, Block},
+    content::ContentPacks,
     ecs::NeedsDespawned,
-    entities::player::{render_distance::RenderDistance, Player},
-    events::{block_events::BlockChangedEvent, structure::change_pilot_event::ChangePilotEvent},
+    entities::{
+        dropped_item::{DroppedItem, DROPPED_ITEM_SIZE},
+        player::{render_distance::RenderDistance, Player},
+    },
+    events::{
+        block_events::{BlockChangedBatchEvent, BlockChangedEvent, BlockDataChangedEvent},
+        structure::change_pilot_event::ChangePilotEvent,
+        wrappers::cancellable_event::{CancellableEvent, CancellableEventSet},
+    },
     inventory::Inventory,
+    item::Item,
     netty::{
         client_reliable_messages::ClientReliableMessages, cosmos_encoder,
         netty_rigidbody::NettyRigidBody, server_reliable_messages::ServerReliableMessages,
@@ -24,9 +33,12 @@ use cosmos_core::{
         location::{add_previous_location, handle_child_syncing, Location, SYSTEM_SECTORS},
         player_world::PlayerWorld,
     },
-    registry::Registry,
+    registry::{identifiable::Identifiable, Registry},
     structure::{
-        chunk::Chunk,
+        block_edit_batch::BlockEditBatch,
+        block_health::block_health_changed_event::BlockHealthChangedEvent,
+        chunk::codec,
+        events::StructureResizedEvent,
         planet::{biosphere::BiosphereMarker, planet_builder::TPlanetBuilder},
         ship::{pilot::Pilot, ship_builder::TShipBuilder, Ship},
         ChunkInitEvent, Structure,
@@ -40,6 +52,7 @@ use crate::{
         flags::LocalPlayer,
         lobby::{ClientLobby, PlayerInfo},
         mapping::NetworkMapping,
+        registry_sync::ServerRegistryIds,
     },
     rendering::MainCamera,
     state::game_state::GameState,
@@ -136,6 +149,18 @@ fn client_sync_players(
     mut network_mapping: ResMut<NetworkMapping>,
     mut set_chunk_event_writer: EventWriter<ChunkInitEvent>,
     mut block_change_event_writer: EventWriter<BlockChangedEvent>,
+    // Grouped together to stay within bevy's system parameter limit
+    (
+        mut block_change_batch_event_writer,
+        mut structure_resized_event_writer,
+        mut block_data_changed_event_writer,
+        mut block_health_changed_event_writer,
+    ): (
+        EventWriter<BlockChangedBatchEvent>,
+        EventWriter<StructureResizedEvent>,
+        EventWriter<BlockDataChangedEvent>,
+        EventWriter<BlockHealthChangedEvent>,
+    ),
     query_player: Query<&Player>,
     mut query_body: Query<
         (
@@ -147,8 +172,15 @@ fn client_sync_players(
         Without<LocalPlayer>,
     >,
     mut query_structure: Query<&mut Structure>,
-    blocks: Res<Registry<Block>>,
-    mut pilot_change_event_writer: EventWriter<ChangePilotEvent>,
+    // Grouped together to stay within bevy's system parameter limit
+    (blocks, hardness_registry, items, packs, mut server_registry_ids): (
+        Res<Registry<Block>>,
+        Res<Registry<BlockHardness>>,
+        Res<Registry<Item>>,
+        Res<ContentPacks>,
+        ResMut<ServerRegistryIds>,
+    ),
+    mut pilot_change_event_writer: EventWriter<CancellableEvent<ChangePilotEvent>>,
     mut set_ship_movement_event: EventWriter<SetShipMovementEvent>,
     mut requested_entities: ResMut<RequestedEntities>,
     time: Res<Time>,
@@ -229,6 +261,26 @@ fn client_sync_players(
         let msg: ServerReliableMessages = cosmos_encoder::deserialize(&message).unwrap();
 
         match msg {
+            ServerReliableMessages::RegistrySync {
+                packs: server_packs,
+                blocks: server_blocks,
+                items: server_items,
+            } => match ServerRegistryIds::new(
+                &server_packs,
+                &server_blocks,
+                &server_items,
+                &packs,
+                &blocks,
+                &items,
+            ) {
+                Ok(registry_ids) => *server_registry_ids = registry_ids,
+                Err(e) => {
+                    eprintln!("Disconnecting from server - {e}");
+                    client.disconnect();
+
+                    return;
+                }
+            },
             ServerReliableMessages::PlayerCreate {
                 mut body,
                 id,
@@ -247,8 +299,9 @@ fn client_sync_players(
 
                 let mut entity_cmds = commands.spawn_empty();
 
-                let inventory: Inventory =
+                let mut inventory: Inventory =
                     cosmos_encoder::deserialize(&inventory_serialized).unwrap();
+                server_registry_ids.remap_inventory(&mut inventory);
 
                 // This should be set via the server, but just in case,
                 // this will avoid any position mismatching
@@ -416,8 +469,9 @@ fn client_sync_players(
                 if let Some(s_entity) = network_mapping.client_from_server(&server_structure_entity)
                 {
                     if let Ok(mut structure) = query_structure.get_mut(s_entity) {
-                        let chunk: Chunk = cosmos_encoder::deserialize(&serialized_chunk)
+                        let mut chunk = codec::decode(&serialized_chunk)
                             .expect("Unable to deserialize chunk from server");
+                        server_registry_ids.remap_chunk(&mut chunk);
 
                         let (x, y, z) = (
                             chunk.structure_x(),
@@ -465,13 +519,16 @@ fn client_sync_players(
             ServerReliableMessages::MOTD { motd } => {
                 println!("Server MOTD: {motd}");
             }
+            ServerReliableMessages::BlockPlaceRejected { reason, .. } => {
+                println!("Unable to place block - {reason}");
+            }
             ServerReliableMessages::BlockChange {
                 x,
                 y,
                 z,
                 structure_entity,
                 block_id,
-                block_up,
+                block_rotation,
             } => {
                 // Sometimes you'll get block updates for structures that don't exist
                 if let Some(client_ent) = network_mapping.client_from_server(&structure_entity) {
@@ -480,14 +537,92 @@ fn client_sync_players(
                             x as usize,
                             y as usize,
                             z as usize,
-                            blocks.from_numeric_id(block_id),
-                            block_up,
+                            blocks.from_numeric_id(server_registry_ids.block_from_server(block_id)),
+                            block_rotation,
                             &blocks,
                             Some(&mut block_change_event_writer),
                         );
                     }
                 }
             }
+            ServerReliableMessages::StructureResized {
+                structure_entity,
+                negative: (nx, ny, nz),
+                positive: (px, py, pz),
+            } => {
+                if let Some(client_ent) = network_mapping.client_from_server(&structure_entity) {
+                    if let Ok(mut structure) = query_structure.get_mut(client_ent) {
+                        structure.grow(
+                            (nx as usize, ny as usize, nz as usize),
+                            (px as usize, py as usize, pz as usize),
+                            Some(&mut structure_resized_event_writer),
+                        );
+                    }
+                }
+            }
+            ServerReliableMessages::BlockChangeBatch {
+                structure_entity,
+                changes,
+            } => {
+                if let Some(client_ent) = network_mapping.client_from_server(&structure_entity) {
+                    if let Ok(mut structure) = query_structure.get_mut(client_ent) {
+                        let changes = changes.into_iter().map(|mut edit| {
+                            edit.block_id = server_registry_ids.block_from_server(edit.block_id);
+                            edit
+                        });
+
+                        structure.apply_block_edits(
+                            BlockEditBatch::from_iter(changes),
+                            &blocks,
+                            Some(&mut block_change_batch_event_writer),
+                        );
+                    }
+                }
+            }
+            ServerReliableMessages::BlockDataChanged {
+                structure_entity,
+                block,
+                data_id,
+                data,
+            } => {
+                if let Some(client_ent) = network_mapping.client_from_server(&structure_entity) {
+                    if let Ok(mut structure) = query_structure.get_mut(client_ent) {
+                        structure.set_raw_block_data(
+                            block.x,
+                            block.y,
+                            block.z,
+                            &data_id,
+                            data,
+                            Some(&mut block_data_changed_event_writer),
+                        );
+                    }
+                }
+            }
+            ServerReliableMessages::BlockHealthChanged {
+                structure_entity,
+                changes,
+            } => {
+                if let Some(client_ent) = network_mapping.client_from_server(&structure_entity) {
+                    if let Ok(mut structure) = query_structure.get_mut(client_ent) {
+                        for (block, health) in changes {
+                            let block_type = structure.block_at(block.x, block.y, block.z, &blocks);
+
+                            let Some(hardness) = hardness_registry.from_id(block_type.unlocalized_name()) else {
+                                continue;
+                            };
+
+                            structure.set_block_health(
+                                block.x,
+                                block.y,
+                                block.z,
+                                hardness,
+                                health,
+                                Some(&mut block_health_changed_event_writer),
+                            );
+                        }
+                    }
+                }
+            }
             ServerReliableMessages::PilotChange {
                 structure_entity,
                 pilot_entity,
@@ -507,18 +642,19 @@ fn client_sync_players(
                     continue;
                 };
 
-                pilot_change_event_writer.send(ChangePilotEvent {
+                pilot_change_event_writer.send(CancellableEvent::new(ChangePilotEvent {
                     structure_entity,
                     pilot_entity,
-                });
+                }));
             }
             ServerReliableMessages::EntityInventory {
                 serialized_inventory,
                 owner,
             } => {
                 if let Some(client_entity) = network_mapping.client_from_server(&owner) {
-                    let inventory: Inventory =
+                    let mut inventory: Inventory =
                         cosmos_encoder::deserialize(&serialized_inventory).unwrap();
+                    server_registry_ids.remap_inventory(&mut inventory);
 
                     commands.entity(client_entity).insert(inventory);
                 } else {
@@ -531,6 +667,53 @@ fn client_sync_players(
             ServerReliableMessages::LaserCannonFire {} => {
                 println!("A laser cannon was fired")
             }
+            ServerReliableMessages::DroppedItem {
+                entity: server_entity,
+                mut body,
+                mut item_stack,
+            } => {
+                server_registry_ids.remap_item_stack(&mut item_stack);
+
+                // The server sends this again whenever some of its items are picked up
+                if let Some(entity) = network_mapping.client_from_server(&server_entity) {
+                    commands.entity(entity).insert(DroppedItem::new(item_stack));
+
+                    continue;
+                }
+
+                body.location.last_transform_loc = Some(body.location.local);
+
+                let half_size = DROPPED_ITEM_SIZE / 2.0;
+
+                let entity = commands
+                    .spawn((
+                        PbrBundle {
+                            transform: Transform::with_rotation(
+                                Transform::from_translation(body.location.local),
+                                body.rotation,
+                            ),
+                            mesh: meshes.add(shape::Cube::new(DROPPED_ITEM_SIZE).into()),
+                            ..default()
+                        },
+                        body.location,
+                        Collider::cuboid(half_size, half_size, half_size),
+                        RigidBody::Dynamic,
+                        body.create_velocity(),
+                        ReadMassProperties::default(),
+                        DroppedItem::new(item_stack),
+                    ))
+                    .id();
+
+                network_mapping.add_mapping(entity, server_entity);
+            }
+            ServerReliableMessages::DroppedItemRemove {
+                entity: server_entity,
+            } => {
+                if let Some(entity) = network_mapping.client_from_server(&server_entity) {
+                    commands.entity(entity).insert(NeedsDespawned);
+                    network_mapping.remove_mapping_from_server_entity(&server_entity);
+                }
+            }
             ServerReliableMessages::Star { entity, star } => {
                 if let Some(client_entity) = network_mapping.client_from_server(&entity) {
                     commands.entity(client_entity).insert((
@@ -640,7 +823,8 @@ pub(super) fn register(app: &mut App) {
         .add_systems((update_crosshair, insert_last_rotation))
         .add_system(
             client_sync_players
-                .run_if(in_state(GameState::Playing).or_else(in_state(GameState::LoadingWorld))),
+                .run_if(in_state(GameState::Playing).or_else(in_state(GameState::LoadingWorld)))
+                .in_set(CancellableEventSet::Send),
         )
         .add_systems(
             (
@@ -7,6 +7,7 @@ pub mod flags;
 mod gameplay;
 pub mod lobby;
 pub mod mapping;
+pub mod registry_sync;
 
 pub(super) fn register(app: &mut App) {
     gameplay::register(app);
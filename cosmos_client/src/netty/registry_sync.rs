@@ -0,0 +1,206 @@
+//! The client builds its registries itself, so their numeric ids may not match the server's.
+//!
+//! When connecting, the server sends its content packs & the unlocalized name of every id in its registries.
+//! Any ids received from or sent to the server are then translated between the server's ids and this client's.
+//! If the client doesn't have the same content packs as the server, or the server has any blocks or items
+//! this client doesn't, the client can't properly display the world and disconnects.
+
+use std::fmt;
+
+use bevy::prelude::Resource;
+use cosmos_core::{
+    block::{blocks::MISSING_BLOCK_NAME, Block},
+    content::{ContentPackId, ContentPacks},
+    crafting::recipes::Recipe,
+    inventory::{itemstack::ItemStack, Inventory},
+    item::Item,
+    registry::{
+        id_map::{IdMap, IdRemap},
+        Registry,
+    },
+    structure::chunk::Chunk,
+};
+
+#[derive(Debug)]
+/// The reason this client can't play on a server
+pub enum RegistrySyncError {
+    /// The server & this client don't have the same content packs
+    PackMismatch {
+        /// The packs the server has that this client doesn't
+        missing: Vec<ContentPackId>,
+        /// The packs this client has that the server doesn't
+        extra: Vec<ContentPackId>,
+    },
+    /// The server has content this client doesn't
+    MissingContent {
+        /// The unlocalized names of the blocks this client is missing
+        blocks: Vec<String>,
+        /// The unlocalized names of the items this client is missing
+        items: Vec<String>,
+    },
+}
+
+impl fmt::Display for RegistrySyncError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PackMismatch { missing, extra } => {
+                write!(
+                    f,
+                    "The server uses different content packs than this client."
+                )?;
+
+                if !missing.is_empty() {
+                    write!(f, " Missing packs: {}.", join(missing))?;
+                }
+                if !extra.is_empty() {
+                    write!(f, " Packs the server doesn't use: {}.", join(extra))?;
+                }
+
+                Ok(())
+            }
+            Self::MissingContent { blocks, items } => {
+                write!(f, "The server requires content this client does not have.")?;
+
+                if !blocks.is_empty() {
+                    write!(f, " Missing blocks: {}.", blocks.join(", "))?;
+                }
+                if !items.is_empty() {
+                    write!(f, " Missing items: {}.", items.join(", "))?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for RegistrySyncError {}
+
+fn join(packs: &[ContentPackId]) -> String {
+    packs
+        .iter()
+        .map(|pack| pack.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+#[derive(Debug, Default, Resource)]
+/// Translates ids between the server's registries and this client's.
+///
+/// Each remap is None if the server's ids are the same as this client's.
+pub struct ServerRegistryIds {
+    blocks_from_server: Option<IdRemap>,
+    blocks_to_server: Option<IdRemap>,
+    items_from_server: Option<IdRemap>,
+}
+
+/// None if nothing needs to be remapped
+fn non_identity(remap: IdRemap) -> Option<IdRemap> {
+    if remap.is_identity() {
+        None
+    } else {
+        Some(remap)
+    }
+}
+
+impl ServerRegistryIds {
+    /// Checks the server's content packs & registries against this client's & creates the remaps between them.
+    ///
+    /// * `server_packs` The server's content packs
+    /// * `server_blocks` The server's block ids
+    /// * `server_items` The server's item ids
+    pub fn new(
+        server_packs: &[ContentPackId],
+        server_blocks: &IdMap,
+        server_items: &IdMap,
+        packs: &ContentPacks,
+        blocks: &Registry<Block>,
+        items: &Registry<Item>,
+    ) -> Result<Self, RegistrySyncError> {
+        let client_packs = packs.ids();
+
+        let missing = server_packs
+            .iter()
+            .filter(|pack| !client_packs.contains(pack))
+            .cloned()
+            .collect::<Vec<ContentPackId>>();
+
+        let extra = client_packs
+            .iter()
+            .filter(|pack| !server_packs.contains(pack))
+            .cloned()
+            .collect::<Vec<ContentPackId>>();
+
+        if !missing.is_empty() || !extra.is_empty() {
+            return Err(RegistrySyncError::PackMismatch { missing, extra });
+        }
+
+        let blocks_from_server = server_blocks.remap_for(blocks, MISSING_BLOCK_NAME);
+        // Every block has an item with the same name, so this is also used for items
+        let items_from_server = server_items.remap_for(items, MISSING_BLOCK_NAME);
+
+        if !blocks_from_server.missing().is_empty() || !items_from_server.missing().is_empty() {
+            return Err(RegistrySyncError::MissingContent {
+                blocks: blocks_from_server.missing().to_vec(),
+                items: items_from_server.missing().to_vec(),
+            });
+        }
+
+        let blocks_to_server =
+            IdMap::from_registry(blocks).remap_to(server_blocks, MISSING_BLOCK_NAME);
+
+        Ok(Self {
+            blocks_from_server: non_identity(blocks_from_server),
+            blocks_to_server: non_identity(blocks_to_server),
+            items_from_server: non_identity(items_from_server),
+        })
+    }
+
+    /// Translates a block id sent by the server into this client's id
+    pub fn block_from_server(&self, block_id: u16) -> u16 {
+        self.blocks_from_server
+            .as_ref()
+            .map(|remap| remap.get(block_id))
+            .unwrap_or(block_id)
+    }
+
+    /// Translates one of this client's block ids into the server's id
+    pub fn block_to_server(&self, block_id: u16) -> u16 {
+        self.blocks_to_server
+            .as_ref()
+            .map(|remap| remap.get(block_id))
+            .unwrap_or(block_id)
+    }
+
+    /// Translates every block id in a chunk sent by the server into this client's ids
+    pub fn remap_chunk(&self, chunk: &mut Chunk) {
+        if let Some(remap) = &self.blocks_from_server {
+            chunk.remap_block_ids(remap);
+        }
+    }
+
+    /// Translates every item id in an inventory sent by the server into this client's ids
+    pub fn remap_inventory(&self, inventory: &mut Inventory) {
+        if let Some(remap) = &self.items_from_server {
+            inventory.remap_item_ids(remap);
+        }
+    }
+
+    /// Translates the item id of an item stack sent by the server into this client's ids
+    pub fn remap_item_stack(&self, item_stack: &mut ItemStack) {
+        if let Some(remap) = &self.items_from_server {
+            item_stack.remap_item_id(remap);
+        }
+    }
+
+    /// Translates every item & block id in a recipe sent by the server into this client's ids
+    pub fn remap_recipe(&self, recipe: &mut Recipe) {
+        if let Some(remap) = &self.items_from_server {
+            recipe.remap_item_ids(remap);
+        }
+
+        if let Some(remap) = &self.blocks_from_server {
+            recipe.remap_block_ids(remap);
+        }
+    }
+}
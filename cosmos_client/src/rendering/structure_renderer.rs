@@ -5,18 +5,20 @@ use crate::state::game_state::GameState;
 use crate::structure::planet::unload_chunks_far_from_players;
 use bevy::prelude::{
     warn, App, BuildChildren, Component, DespawnRecursiveExt, EventReader, GlobalTransform,
-    IntoSystemConfigs, Mesh, OnUpdate, PbrBundle, PointLight, PointLightBundle, Quat, Rect,
+    IntoSystemConfigs, Mesh, OnUpdate, PbrBundle, PointLight, PointLightBundle, Rect,
     StandardMaterial, Transform, Vec3, With,
 };
 use bevy::reflect::{FromReflect, Reflect};
 use bevy::render::primitives::Aabb;
 use bevy::utils::hashbrown::HashMap;
+use cosmos_core::block::hardness::BlockHardness;
 use cosmos_core::block::{Block, BlockFace};
-use cosmos_core::events::block_events::BlockChangedEvent;
+use cosmos_core::events::block_events::{BlockChangedBatchEvent, BlockChangedEvent};
 use cosmos_core::physics::location::SECTOR_DIMENSIONS;
 use cosmos_core::registry::identifiable::Identifiable;
 use cosmos_core::registry::many_to_one::ManyToOneRegistry;
 use cosmos_core::registry::Registry;
+use cosmos_core::structure::block_health::block_health_changed_event::BlockHealthChangedEvent;
 use cosmos_core::structure::chunk::{Chunk, ChunkEntity, CHUNK_DIMENSIONS, CHUNK_DIMENSIONSF};
 use cosmos_core::structure::events::ChunkSetEvent;
 use cosmos_core::structure::structure_block::StructureBlock;
@@ -25,10 +27,9 @@ use cosmos_core::utils::array_utils::expand;
 use cosmos_core::utils::timer::UtilsTimer;
 use rayon::prelude::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 use std::collections::HashSet;
-use std::f32::consts::PI;
 use std::sync::Mutex;
 
-use crate::asset::asset_loading::{BlockTextureIndex, MainAtlas};
+use crate::asset::asset_loading::{BlockTextureIndex, MainAtlas, DAMAGE_STAGES};
 use crate::{Assets, Commands, Entity, Handle, Query, Res, ResMut};
 
 use super::{BlockMeshRegistry, CosmosMeshBuilder, MeshBuilder, MeshInformation};
@@ -47,80 +48,91 @@ struct ChunkMesh {
 
 fn monitor_block_updates_system(
     mut event: EventReader<BlockChangedEvent>,
+    mut batch_event: EventReader<BlockChangedBatchEvent>,
+    mut health_event: EventReader<BlockHealthChangedEvent>,
     mut chunk_set_event: EventReader<ChunkSetEvent>,
     structure_query: Query<&Structure>,
     mut commands: Commands,
 ) {
     let mut chunks_todo = HashMap::<Entity, HashSet<(usize, usize, usize)>>::default();
 
-    for ev in event.iter() {
-        let structure: &Structure = structure_query.get(ev.structure_entity).unwrap();
-        if !chunks_todo.contains_key(&ev.structure_entity) {
-            chunks_todo.insert(ev.structure_entity, HashSet::default());
+    let changed_blocks = event
+        .iter()
+        .map(|ev| (ev.structure_entity, ev.block))
+        .chain(batch_event.iter().flat_map(|ev| {
+            ev.changes
+                .iter()
+                .map(move |change| (ev.structure_entity, change.block))
+        }))
+        // Damaged blocks have an overlay, so their chunk needs rendered again
+        .chain(
+            health_event
+                .iter()
+                .map(|ev| (ev.structure_entity, ev.block)),
+        );
+
+    for (structure_entity, block) in changed_blocks {
+        let structure: &Structure = structure_query.get(structure_entity).unwrap();
+        if !chunks_todo.contains_key(&structure_entity) {
+            chunks_todo.insert(structure_entity, HashSet::default());
         }
 
         let chunks = chunks_todo
-            .get_mut(&ev.structure_entity)
+            .get_mut(&structure_entity)
             .expect("This was just added");
 
-        if ev.block.x() != 0 && ev.block.x() % CHUNK_DIMENSIONS == 0 {
+        if block.x() != 0 && block.x() % CHUNK_DIMENSIONS == 0 {
             chunks.insert((
-                ev.block.chunk_coord_x() - 1,
-                ev.block.chunk_coord_y(),
-                ev.block.chunk_coord_z(),
+                block.chunk_coord_x() - 1,
+                block.chunk_coord_y(),
+                block.chunk_coord_z(),
             ));
         }
 
-        if ev.block.x() != structure.blocks_width() - 1
-            && (ev.block.x() + 1) % CHUNK_DIMENSIONS == 0
-        {
+        if block.x() != structure.blocks_width() - 1 && (block.x() + 1) % CHUNK_DIMENSIONS == 0 {
             chunks.insert((
-                ev.block.chunk_coord_x() + 1,
-                ev.block.chunk_coord_y(),
-                ev.block.chunk_coord_z(),
+                block.chunk_coord_x() + 1,
+                block.chunk_coord_y(),
+                block.chunk_coord_z(),
             ));
         }
 
-        if ev.block.y() != 0 && ev.block.y() % CHUNK_DIMENSIONS == 0 {
+        if block.y() != 0 && block.y() % CHUNK_DIMENSIONS == 0 {
             chunks.insert((
-                ev.block.chunk_coord_x(),
-                ev.block.chunk_coord_y() - 1,
-                ev.block.chunk_coord_z(),
+                block.chunk_coord_x(),
+                block.chunk_coord_y() - 1,
+                block.chunk_coord_z(),
             ));
         }
 
-        if ev.block.y() != structure.blocks_height() - 1
-            && (ev.block.y() + 1) % CHUNK_DIMENSIONS == 0
-        {
+        if block.y() != structure.blocks_height() - 1 && (block.y() + 1) % CHUNK_DIMENSIONS == 0 {
             chunks.insert((
-                ev.block.chunk_coord_x(),
-                ev.block.chunk_coord_y() + 1,
-                ev.block.chunk_coord_z(),
+                block.chunk_coord_x(),
+                block.chunk_coord_y() + 1,
+                block.chunk_coord_z(),
             ));
         }
 
-        if ev.block.z() != 0 && ev.block.z() % CHUNK_DIMENSIONS == 0 {
+        if block.z() != 0 && block.z() % CHUNK_DIMENSIONS == 0 {
             chunks.insert((
-                ev.block.chunk_coord_x(),
-                ev.block.chunk_coord_y(),
-                ev.block.chunk_coord_z() - 1,
+                block.chunk_coord_x(),
+                block.chunk_coord_y(),
+                block.chunk_coord_z() - 1,
             ));
         }
 
-        if ev.block.z() != structure.blocks_length() - 1
-            && (ev.block.z() + 1) % CHUNK_DIMENSIONS == 0
-        {
+        if block.z() != structure.blocks_length() - 1 && (block.z() + 1) % CHUNK_DIMENSIONS == 0 {
             chunks.insert((
-                ev.block.chunk_coord_x(),
-                ev.block.chunk_coord_y(),
-                ev.block.chunk_coord_z() + 1,
+                block.chunk_coord_x(),
+                block.chunk_coord_y(),
+                block.chunk_coord_z() + 1,
             ));
         }
 
         chunks.insert((
-            ev.block.chunk_coord_x(),
-            ev.block.chunk_coord_y(),
-            ev.block.chunk_coord_z(),
+            block.chunk_coord_x(),
+            block.chunk_coord_y(),
+            block.chunk_coord_z(),
         ));
     }
 
@@ -207,6 +219,7 @@ fn monitor_needs_rendered_system(
     lights_query: Query<&LightsHolder>,
     chunk_meshes_query: Query<&ChunkMeshes>,
     block_textures: Res<Registry<BlockTextureIndex>>,
+    hardness: Res<Registry<BlockHardness>>,
 
     local_player: Query<&GlobalTransform, With<LocalPlayer>>,
 
@@ -297,6 +310,7 @@ fn monitor_needs_rendered_system(
                 &blocks,
                 &meshes_registry,
                 &block_textures,
+                &hardness,
             );
 
             let mut mutex = to_process.lock().expect("Error locking to_process vec!");
@@ -482,6 +496,31 @@ fn monitor_needs_rendered_system(
     }
 }
 
+/// How much bigger the damage overlay is than the block it covers, so it's drawn on top of the block's faces
+const DAMAGE_OVERLAY_SCALE: f32 = 1.002;
+
+/// Gets which damage texture to show for a block with this much health, or None if it isn't damaged.
+///
+/// Less health shows a higher stage, up to `DAMAGE_STAGES - 1`.
+fn damage_stage(health: f32, max_health: f32) -> Option<usize> {
+    if max_health <= 0.0 || health >= max_health {
+        return None;
+    }
+
+    let damage = 1.0 - health / max_health;
+
+    Some(((damage * DAMAGE_STAGES as f32) as usize).min(DAMAGE_STAGES - 1))
+}
+
+/// Block models have their front at -Z and their back at +Z, which is flipped compared to [`BlockFace::direction`]
+fn model_face(face: BlockFace) -> BlockFace {
+    match face {
+        BlockFace::Front => BlockFace::Back,
+        BlockFace::Back => BlockFace::Front,
+        _ => face,
+    }
+}
+
 #[derive(Default, Debug, Reflect, FromReflect)]
 struct ChunkRendererInstance {
     indices: Vec<u32>,
@@ -536,15 +575,24 @@ impl ChunkRenderer {
         blocks: &Registry<Block>,
         meshes: &BlockMeshRegistry,
         block_textures: &Registry<BlockTextureIndex>,
+        hardness: &Registry<BlockHardness>,
     ) {
         let cd2 = CHUNK_DIMENSIONSF / 2.0;
 
+        let damage_uvs = (0..DAMAGE_STAGES)
+            .map(|stage| {
+                block_textures
+                    .from_id(&format!("damage_stage_{stage}"))
+                    .and_then(|index| index.atlas_index("all"))
+                    .map(|index| atlas.uvs_for_index(index))
+            })
+            .collect::<Vec<Option<Rect>>>();
+
         let mut faces = Vec::with_capacity(6);
 
         for ((x, y, z), (block, block_info)) in chunk
             .blocks()
-            .copied()
-            .zip(chunk.block_info_iterator().copied())
+            .zip(chunk.block_info_iterator())
             .enumerate()
             .map(|(i, block)| (expand(i, CHUNK_DIMENSIONS, CHUNK_DIMENSIONS), block))
             .filter(|((x, y, z), _)| chunk.has_block_at(*x, *y, *z))
@@ -592,23 +640,23 @@ impl ChunkRenderer {
                 faces.push(BlockFace::Bottom);
             }
 
-            // back
+            // front
             if (z != CHUNK_DIMENSIONS - 1 && chunk.has_see_through_block_at(x, y, z + 1, blocks))
                 || (z == CHUNK_DIMENSIONS - 1
                     && (front
                         .map(|c| c.has_see_through_block_at(x, y, 0, blocks))
                         .unwrap_or(true)))
             {
-                faces.push(BlockFace::Back);
+                faces.push(BlockFace::Front);
             }
-            // front
+            // back
             if (z != 0 && chunk.has_see_through_block_at(x, y, z - 1, blocks))
                 || (z == 0
                     && (back
                         .map(|c| c.has_see_through_block_at(x, y, CHUNK_DIMENSIONS - 1, blocks))
                         .unwrap_or(true)))
             {
-                faces.push(BlockFace::Front);
+                faces.push(BlockFace::Back);
             }
 
             if !faces.is_empty() {
@@ -630,8 +678,22 @@ impl ChunkRenderer {
                 let mesh_builder = self.meshes.get_mut(&material.handle).unwrap();
 
                 let rotation = block_info.get_rotation();
-
-                for face in faces.iter().map(|x| BlockFace::rotate_face(*x, rotation)) {
+                let quat = rotation.as_quat();
+
+                let damage_uvs = hardness
+                    .from_id(block.unlocalized_name())
+                    .and_then(|hardness| {
+                        damage_stage(
+                            chunk.get_block_health(x, y, z, hardness),
+                            hardness.hardness(),
+                        )
+                    })
+                    .and_then(|stage| damage_uvs[stage]);
+
+                for face in faces
+                    .iter()
+                    .map(|x| model_face(BlockFace::rotate_face(*x, rotation)))
+                {
                     let index = block_textures
                         .from_id(block.unlocalized_name())
                         .unwrap_or_else(|| {
@@ -649,21 +711,12 @@ impl ChunkRenderer {
 
                     let mut mesh_info = mesh.info_for_face(face).clone();
 
-                    let rotation = match rotation {
-                        BlockFace::Top => Quat::IDENTITY,
-                        BlockFace::Front => Quat::from_axis_angle(Vec3::X, PI / 2.0),
-                        BlockFace::Back => Quat::from_axis_angle(Vec3::X, -PI / 2.0),
-                        BlockFace::Left => Quat::from_axis_angle(Vec3::Z, PI / 2.0),
-                        BlockFace::Right => Quat::from_axis_angle(Vec3::Z, -PI / 2.0),
-                        BlockFace::Bottom => Quat::from_axis_angle(Vec3::X, PI),
-                    };
-
                     for pos in mesh_info.positions.iter_mut() {
-                        *pos = rotation.mul_vec3((*pos).into()).into();
+                        *pos = quat.mul_vec3((*pos).into()).into();
                     }
 
                     for norm in mesh_info.normals.iter_mut() {
-                        *norm = rotation.mul_vec3((*norm).into()).into();
+                        *norm = quat.mul_vec3((*norm).into()).into();
                     }
 
                     mesh_builder.add_mesh_information(
@@ -671,6 +724,18 @@ impl ChunkRenderer {
                         Vec3::new(center_offset_x, center_offset_y, center_offset_z),
                         uvs,
                     );
+
+                    if let Some(damage_uvs) = damage_uvs {
+                        for pos in mesh_info.positions.iter_mut() {
+                            *pos = (Vec3::from(*pos) * DAMAGE_OVERLAY_SCALE).into();
+                        }
+
+                        mesh_builder.add_mesh_information(
+                            &mesh_info,
+                            Vec3::new(center_offset_x, center_offset_y, center_offset_z),
+                            damage_uvs,
+                        );
+                    }
                 }
 
                 faces.clear();
@@ -1,7 +1,11 @@
 //! Displays the player's hotbar
 
 use bevy::prelude::*;
-use cosmos_core::{inventory::Inventory, item::Item};
+use cosmos_core::{
+    inventory::{itemstack::ItemStack, Inventory},
+    item::Item,
+    registry::Registry,
+};
 
 use crate::{
     input::inputs::{CosmosInputHandler, CosmosInputs},
@@ -10,6 +14,8 @@ use crate::{
     state::game_state::GameState,
 };
 
+use super::inventory::item_name;
+
 const ITEM_NAME_FADE_DURATION_SEC: f32 = 5.0;
 
 #[derive(Component)]
@@ -129,6 +135,33 @@ fn tick_text_alpha_down(mut query: Query<&mut Text, With<ItemNameDisplay>>, time
     }
 }
 
+/// The stack's name, followed by a line for each piece of data it has
+fn item_tooltip(names: &Lang<Item>, items: &Registry<Item>, is: &ItemStack) -> String {
+    let mut tooltip = item_name(names, is);
+
+    let Some(data) = is.data() else {
+        return tooltip;
+    };
+
+    let item = items.try_from_numeric_id(is.item_id());
+
+    if let Some(durability) = data.durability {
+        match item.and_then(|item| item.durability()) {
+            Some(max) => tooltip.push_str(&format!("\nDurability: {durability} / {max}")),
+            None => tooltip.push_str(&format!("\nDurability: {durability}")),
+        }
+    }
+
+    if let Some(energy) = data.stored_energy {
+        match item.and_then(|item| item.energy_capacity()) {
+            Some(max) => tooltip.push_str(&format!("\nEnergy: {energy:.0} / {max:.0}")),
+            None => tooltip.push_str(&format!("\nEnergy: {energy:.0}")),
+        }
+    }
+
+    tooltip
+}
+
 fn listen_for_change_events(
     mut query_hb: Query<&mut Hotbar>,
     query_inventory: Query<&Inventory, (Changed<Inventory>, With<LocalPlayer>)>,
@@ -138,6 +171,7 @@ fn listen_for_change_events(
     item_name_query: Query<Entity, With<ItemNameDisplay>>,
     mut commands: Commands,
     names: Res<Lang<Item>>,
+    items: Res<Registry<Item>>,
 ) {
     if let Ok(mut hb) = query_hb.get_single_mut() {
         if hb.selected_slot != hb.prev_slot {
@@ -156,10 +190,7 @@ fn listen_for_change_events(
                     if let Ok(mut name_text) = text_query.get_mut(ent) {
                         if let Some(is) = inv.itemstack_at(hb.item_at_selected_inventory_slot(inv))
                         {
-                            name_text.sections[0].value = names
-                                .get_name_from_numeric_id(is.item_id())
-                                .unwrap_or(&format!("[missing name] ID #{}", is.item_id()))
-                                .to_owned();
+                            name_text.sections[0].value = item_tooltip(&names, &items, is);
 
                             name_text.sections[0].style.color = Color::WHITE;
                         } else {
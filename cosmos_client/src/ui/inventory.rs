@@ -0,0 +1,598 @@
+//! The full inventory screen, which shows every slot of the player's inventory & the container they have open side by side.
+//!
+//! Dragging an item with the left mouse button moves it, dragging with the right mouse button moves half of it,
+//! and shift-clicking an item sends it to the other inventory.
+
+use bevy::{prelude::*, window::PrimaryWindow};
+use bevy_renet::renet::RenetClient;
+use cosmos_core::{
+    inventory::{
+        itemstack::ItemStack,
+        transaction::{InventoryIdentifier, InventorySlot, InventoryTransaction},
+        Inventory,
+    },
+    item::Item,
+    structure::structure_block::StructureBlock,
+};
+
+use crate::{
+    input::inputs::{CosmosInputHandler, CosmosInputs},
+    inventory::{
+        container::{close_container, OpenContainer},
+        transactions::InventoryTransactionEvent,
+    },
+    lang::Lang,
+    netty::flags::LocalPlayer,
+    state::game_state::GameState,
+    window::setup::CursorFlags,
+};
+
+const SLOTS_PER_ROW: usize = 9;
+const SLOT_SIZE: f32 = 64.0;
+
+#[derive(Component, Debug)]
+/// The inventory screen. This only exists while the screen is open.
+pub struct InventoryScreen {
+    /// The container being shown next to the player's inventory
+    container: Option<(Entity, StructureBlock)>,
+}
+
+#[derive(Component, Debug)]
+struct SlotDisplay {
+    slot: InventorySlot,
+    name_text: Entity,
+    quantity_text: Entity,
+}
+
+#[derive(Component)]
+struct HeldItemDisplay;
+
+#[derive(Resource, Debug, Clone, Copy)]
+/// The slot the player is dragging an item out of
+struct Dragging {
+    from: InventorySlot,
+    /// If true, only half of the stack is being dragged
+    split: bool,
+}
+
+/// Use this as a run condition for anything that shouldn't happen while the inventory screen is open, such as clicking on blocks
+pub fn inventory_screen_closed(query: Query<(), With<InventoryScreen>>) -> bool {
+    query.is_empty()
+}
+
+/// The name shown for this stack, which is the name it was given if it has one
+pub fn item_name(names: &Lang<Item>, item_stack: &ItemStack) -> String {
+    if let Some(custom_name) = item_stack.custom_name() {
+        return custom_name.to_owned();
+    }
+
+    names
+        .get_name_from_numeric_id(item_stack.item_id())
+        .cloned()
+        .unwrap_or_else(|| format!("[missing name] ID #{}", item_stack.item_id()))
+}
+
+/// Half of the stack, rounded up so there's always something to split off
+fn split_quantity(item_stack: &ItemStack) -> u16 {
+    (item_stack.quantity() + 1) / 2
+}
+
+fn item_stack_at<'a>(
+    slot: InventorySlot,
+    player_inventory: &'a Inventory,
+    open_container: Option<&'a OpenContainer>,
+) -> Option<&'a ItemStack> {
+    let inventory = match slot.inventory {
+        InventoryIdentifier::Entity(_) => player_inventory,
+        InventoryIdentifier::Container {
+            structure_entity,
+            block,
+        } => {
+            &open_container
+                .filter(|open| open.is(structure_entity, block))?
+                .inventory
+        }
+    };
+
+    inventory.itemstack_at(slot.slot as usize)
+}
+
+/// Gets where the cursor is in UI coordinates, which start at the top left of the window
+fn cursor_ui_position(window: &Window) -> Option<Vec2> {
+    window
+        .cursor_position()
+        .map(|position| Vec2::new(position.x, window.height() - position.y))
+}
+
+fn spawn_slot(
+    grid: &mut ChildBuilder,
+    slot: InventorySlot,
+    font: &Handle<Font>,
+    slot_image: &Handle<Image>,
+) {
+    let mut slot_entity = grid.spawn(ImageBundle {
+        image: slot_image.clone().into(),
+        style: Style {
+            size: Size::new(Val::Px(SLOT_SIZE), Val::Px(SLOT_SIZE)),
+            ..default()
+        },
+        ..default()
+    });
+
+    let mut name_text = None;
+    let mut quantity_text = None;
+
+    slot_entity.with_children(|parent| {
+        name_text = Some(
+            parent
+                .spawn(TextBundle {
+                    style: Style {
+                        position: UiRect {
+                            top: Val::Px(5.0),
+                            left: Val::Px(5.0),
+                            ..default()
+                        },
+                        position_type: PositionType::Absolute,
+                        max_size: Size::new(Val::Px(SLOT_SIZE - 10.0), Val::Auto),
+                        ..default()
+                    },
+                    text: Text::from_section(
+                        "",
+                        TextStyle {
+                            color: Color::WHITE,
+                            font_size: 12.0,
+                            font: font.clone(),
+                        },
+                    ),
+                    ..default()
+                })
+                .id(),
+        );
+
+        quantity_text = Some(
+            parent
+                .spawn(TextBundle {
+                    style: Style {
+                        position: UiRect {
+                            bottom: Val::Px(5.0),
+                            right: Val::Px(5.0),
+                            ..default()
+                        },
+                        position_type: PositionType::Absolute,
+                        ..default()
+                    },
+                    text: Text::from_section(
+                        "",
+                        TextStyle {
+                            color: Color::WHITE,
+                            font_size: 20.0,
+                            font: font.clone(),
+                        },
+                    )
+                    .with_alignment(TextAlignment::Right),
+                    ..default()
+                })
+                .id(),
+        );
+    });
+
+    slot_entity.insert(SlotDisplay {
+        slot,
+        name_text: name_text.expect("This should have been set in the closure above"),
+        quantity_text: quantity_text.expect("This should have been set in the closure above"),
+    });
+}
+
+fn spawn_inventory_panel(
+    parent: &mut ChildBuilder,
+    title: &str,
+    inventory: InventoryIdentifier,
+    n_slots: usize,
+    font: &Handle<Font>,
+    slot_image: &Handle<Image>,
+) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(10.0)),
+                margin: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.8).into(),
+            ..default()
+        })
+        .with_children(|panel| {
+            panel.spawn(TextBundle::from_section(
+                title,
+                TextStyle {
+                    color: Color::WHITE,
+                    font_size: 24.0,
+                    font: font.clone(),
+                },
+            ));
+
+            panel
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        flex_wrap: FlexWrap::Wrap,
+                        size: Size::new(
+                            Val::Px(SLOT_SIZE * SLOTS_PER_ROW.min(n_slots.max(1)) as f32),
+                            Val::Auto,
+                        ),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|grid| {
+                    for slot in 0..n_slots {
+                        spawn_slot(
+                            grid,
+                            InventorySlot::new(inventory, slot as u32),
+                            font,
+                            slot_image,
+                        );
+                    }
+                });
+        });
+}
+
+fn spawn_inventory_screen(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    player_entity: Entity,
+    player_inventory: &Inventory,
+    open_container: Option<&OpenContainer>,
+) {
+    let font = asset_server.load("fonts/PixeloidSans.ttf");
+    let slot_image = asset_server.load("images/ui/hotbar-slot.png");
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                    ..default()
+                },
+                ..default()
+            },
+            InventoryScreen {
+                container: open_container.map(|open| (open.structure_entity, open.block)),
+            },
+        ))
+        .with_children(|parent| {
+            spawn_inventory_panel(
+                parent,
+                "Inventory",
+                InventoryIdentifier::Entity(player_entity),
+                player_inventory.len(),
+                &font,
+                &slot_image,
+            );
+
+            if let Some(open) = open_container {
+                spawn_inventory_panel(
+                    parent,
+                    "Container",
+                    InventoryIdentifier::Container {
+                        structure_entity: open.structure_entity,
+                        block: open.block,
+                    },
+                    open.inventory.len(),
+                    &font,
+                    &slot_image,
+                );
+            }
+
+            parent.spawn((
+                TextBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        ..default()
+                    },
+                    text: Text::from_section(
+                        "",
+                        TextStyle {
+                            color: Color::WHITE,
+                            font_size: 20.0,
+                            font: font.clone(),
+                        },
+                    ),
+                    visibility: Visibility::Hidden,
+                    ..default()
+                },
+                HeldItemDisplay,
+            ));
+        });
+}
+
+/// Opens the screen when the player presses the inventory key or opens a container, and closes it again.
+///
+/// If the open container changes while the screen is open, the screen is rebuilt to show it.
+fn toggle_inventory_screen(
+    input_handler: Res<CosmosInputHandler>,
+    keys: Res<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+    screen_query: Query<(Entity, &InventoryScreen)>,
+    player_query: Query<(Entity, &Inventory), With<LocalPlayer>>,
+    open_container: Option<Res<OpenContainer>>,
+    mut cursor_flags: ResMut<CursorFlags>,
+    mut client: ResMut<RenetClient>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    let Ok((player_entity, player_inventory)) = player_query.get_single() else {
+        return;
+    };
+
+    let toggle_pressed =
+        input_handler.check_just_pressed(CosmosInputs::ToggleInventory, &keys, &mouse);
+
+    if let Ok((screen_entity, screen)) = screen_query.get_single() {
+        // Escape unlocks the mouse, so it also closes the screen. The mouse will lock itself again.
+        let escape_pressed =
+            input_handler.check_just_pressed(CosmosInputs::UnlockMouse, &keys, &mouse);
+
+        if toggle_pressed || escape_pressed {
+            commands.entity(screen_entity).despawn_recursive();
+            commands.remove_resource::<Dragging>();
+
+            if open_container.is_some() {
+                close_container(&mut client, &mut commands);
+            }
+
+            if toggle_pressed {
+                cursor_flags.lock();
+            }
+
+            return;
+        }
+
+        let container = open_container
+            .as_ref()
+            .map(|open| (open.structure_entity, open.block));
+
+        if screen.container == container {
+            return;
+        }
+
+        commands.entity(screen_entity).despawn_recursive();
+        commands.remove_resource::<Dragging>();
+    } else {
+        let container_opened = open_container
+            .as_ref()
+            .map(|open| open.is_added())
+            .unwrap_or(false);
+
+        if !toggle_pressed && !container_opened {
+            return;
+        }
+
+        cursor_flags.unlock();
+    }
+
+    spawn_inventory_screen(
+        &mut commands,
+        &asset_server,
+        player_entity,
+        player_inventory,
+        open_container.as_deref(),
+    );
+}
+
+fn update_slot_displays(
+    slot_query: Query<&SlotDisplay>,
+    player_inventory: Query<&Inventory, With<LocalPlayer>>,
+    open_container: Option<Res<OpenContainer>>,
+    names: Res<Lang<Item>>,
+    mut text_query: Query<&mut Text>,
+) {
+    let Ok(player_inventory) = player_inventory.get_single() else {
+        return;
+    };
+
+    for slot_display in slot_query.iter() {
+        let item_stack = item_stack_at(
+            slot_display.slot,
+            player_inventory,
+            open_container.as_deref(),
+        );
+
+        let (name, quantity) = item_stack
+            .map(|item_stack| {
+                (
+                    item_name(&names, item_stack),
+                    format!("{}", item_stack.quantity()),
+                )
+            })
+            .unwrap_or_default();
+
+        for (text_entity, value) in [
+            (slot_display.name_text, name),
+            (slot_display.quantity_text, quantity),
+        ] {
+            if let Ok(mut text) = text_query.get_mut(text_entity) {
+                if text.sections[0].value != value {
+                    text.sections[0].value = value;
+                }
+            }
+        }
+    }
+}
+
+/// Turns the player's clicks on slots into inventory transactions
+fn handle_slot_clicks(
+    mouse: Res<Input<MouseButton>>,
+    keys: Res<Input<KeyCode>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    slot_query: Query<(&SlotDisplay, &Node, &GlobalTransform)>,
+    player_query: Query<(Entity, &Inventory), With<LocalPlayer>>,
+    open_container: Option<Res<OpenContainer>>,
+    dragging: Option<Res<Dragging>>,
+    mut event_writer: EventWriter<InventoryTransactionEvent>,
+    mut commands: Commands,
+) {
+    let Ok((player_entity, player_inventory)) = player_query.get_single() else {
+        return;
+    };
+
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+
+    let hovered = cursor_ui_position(window).and_then(|cursor| {
+        slot_query
+            .iter()
+            .find(|(_, node, transform)| {
+                (cursor - transform.translation().truncate())
+                    .abs()
+                    .cmple(node.size() / 2.0)
+                    .all()
+            })
+            .map(|(slot_display, _, _)| slot_display.slot)
+    });
+
+    let open_container = open_container.as_deref();
+
+    if let Some(dragging) = dragging {
+        let button = if dragging.split {
+            MouseButton::Right
+        } else {
+            MouseButton::Left
+        };
+
+        if !mouse.just_released(button) {
+            return;
+        }
+
+        commands.remove_resource::<Dragging>();
+
+        let Some(to) = hovered.filter(|to| *to != dragging.from) else {
+            return;
+        };
+
+        let transaction = if dragging.split {
+            let Some(item_stack) = item_stack_at(dragging.from, player_inventory, open_container) else {
+                return;
+            };
+
+            InventoryTransaction::Split {
+                from: dragging.from,
+                to,
+                quantity: split_quantity(item_stack),
+            }
+        } else {
+            InventoryTransaction::Move {
+                from: dragging.from,
+                to,
+            }
+        };
+
+        event_writer.send(InventoryTransactionEvent(transaction));
+
+        return;
+    }
+
+    let Some(hovered) = hovered else {
+        return;
+    };
+
+    if item_stack_at(hovered, player_inventory, open_container).is_none() {
+        return;
+    }
+
+    if mouse.just_pressed(MouseButton::Left) {
+        if keys.any_pressed([KeyCode::LShift, KeyCode::RShift]) {
+            let player = InventoryIdentifier::Entity(player_entity);
+
+            // Shift-clicking sends the item to whichever inventory it isn't in
+            let to = if hovered.inventory == player {
+                open_container.map(|open| InventoryIdentifier::Container {
+                    structure_entity: open.structure_entity,
+                    block: open.block,
+                })
+            } else {
+                Some(player)
+            };
+
+            if let Some(to) = to {
+                event_writer.send(InventoryTransactionEvent(InventoryTransaction::Transfer {
+                    from: hovered,
+                    to,
+                }));
+            }
+        } else {
+            commands.insert_resource(Dragging {
+                from: hovered,
+                split: false,
+            });
+        }
+    } else if mouse.just_pressed(MouseButton::Right) {
+        commands.insert_resource(Dragging {
+            from: hovered,
+            split: true,
+        });
+    }
+}
+
+/// Shows what the player is dragging next to their cursor
+fn update_held_item_display(
+    mut display_query: Query<(&mut Text, &mut Style, &mut Visibility), With<HeldItemDisplay>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    player_inventory: Query<&Inventory, With<LocalPlayer>>,
+    open_container: Option<Res<OpenContainer>>,
+    dragging: Option<Res<Dragging>>,
+    names: Res<Lang<Item>>,
+) {
+    let Ok((mut text, mut style, mut visibility)) = display_query.get_single_mut() else {
+        return;
+    };
+
+    let held = dragging.zip(player_inventory.get_single().ok()).and_then(
+        |(dragging, player_inventory)| {
+            item_stack_at(dragging.from, player_inventory, open_container.as_deref()).map(
+                |item_stack| {
+                    let quantity = if dragging.split {
+                        split_quantity(item_stack)
+                    } else {
+                        item_stack.quantity()
+                    };
+
+                    format!("{} x{quantity}", item_name(&names, item_stack))
+                },
+            )
+        },
+    );
+
+    let cursor = window_query.get_single().ok().and_then(cursor_ui_position);
+
+    let (Some(held), Some(cursor)) = (held, cursor) else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+
+    text.sections[0].value = held;
+    style.position = UiRect {
+        left: Val::Px(cursor.x + 12.0),
+        top: Val::Px(cursor.y + 12.0),
+        ..default()
+    };
+    *visibility = Visibility::Inherited;
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        (
+            toggle_inventory_screen,
+            handle_slot_clicks,
+            update_slot_displays,
+            update_held_item_display,
+        )
+            .chain()
+            .in_set(OnUpdate(GameState::Playing)),
+    );
+}
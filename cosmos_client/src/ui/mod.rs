@@ -5,9 +5,11 @@ use bevy::prelude::App;
 pub mod crosshair;
 pub mod debug_info_display;
 pub mod hotbar;
+pub mod inventory;
 
 pub(super) fn register(app: &mut App) {
     crosshair::register(app);
     hotbar::register(app);
+    inventory::register(app);
     debug_info_display::register(app);
 }
@@ -9,7 +9,8 @@ use bevy::{
 use crate::input::inputs::{CosmosInputHandler, CosmosInputs};
 
 #[derive(Resource, Copy, Clone)]
-struct CursorFlags {
+/// Whether the cursor is locked to the window & hidden, or free to click on things
+pub struct CursorFlags {
     locked: bool,
     visible: bool,
 }
@@ -19,6 +20,18 @@ impl CursorFlags {
         self.locked = !self.locked;
         self.visible = !self.visible;
     }
+
+    /// Locks & hides the cursor so it can be used to look around
+    pub fn lock(&mut self) {
+        self.locked = true;
+        self.visible = false;
+    }
+
+    /// Frees & shows the cursor so it can be used to click on things
+    pub fn unlock(&mut self) {
+        self.locked = false;
+        self.visible = true;
+    }
 }
 
 #[derive(Resource)]
@@ -68,14 +81,21 @@ fn toggle_mouse_freeze(
     inputs: Res<Input<KeyCode>>,
     mouse: Res<Input<MouseButton>>,
     mut cursor_flags: ResMut<CursorFlags>,
-    mut primary_query: Query<&mut Window, With<PrimaryWindow>>,
 ) {
     if input_handler.check_just_pressed(CosmosInputs::UnlockMouse, &inputs, &mouse) {
+        cursor_flags.toggle();
+    }
+}
+
+fn update_cursor(
+    cursor_flags: Res<CursorFlags>,
+    mut primary_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if cursor_flags.is_changed() {
         let mut window = primary_query
             .get_single_mut()
             .expect("Missing primary window.");
 
-        cursor_flags.toggle();
         apply_cursor_flags(&mut window, *cursor_flags);
     }
 }
@@ -117,5 +137,6 @@ pub(super) fn register(app: &mut App) {
     .add_startup_system(setup_window)
     .add_system(update_mouse_deltas)
     .add_system(toggle_mouse_freeze)
+    .add_system(update_cursor.after(toggle_mouse_freeze))
     .add_system(window_focus_changed);
 }
@@ -2,116 +2,44 @@
 //!
 //! This list is dynamic, and may grow & shrink at any time.
 //!
-//! The only guarenteed block is air ("cosmos:air").
+//! The only guarenteed block is air ("cosmos:air"). Every other block is loaded from its [`BlockDefinition`].
 
 use crate::block::block_builder::BlockBuilder;
+use crate::content::{self, ContentPacks};
 use crate::loader::{AddLoadingEvent, DoneLoadingEvent, LoadingManager};
 use crate::registry::{self, Registry};
-use bevy::prelude::{App, EventWriter, IntoSystemAppConfig, OnEnter, ResMut, States};
+use bevy::prelude::{App, EventWriter, IntoSystemAppConfig, OnEnter, Res, ResMut, States};
 
-use super::{Block, BlockProperty};
+use super::{
+    definitions::{load_block_definitions, BlockDefinition},
+    Block, BlockProperty,
+};
 
 /// Air's ID - this block will always exist
 pub static AIR_BLOCK_ID: u16 = 0;
 
+/// The unlocalized name of the block that replaces any saved block that no longer exists
+pub static MISSING_BLOCK_NAME: &str = "cosmos:missing";
+
 fn add_cosmos_blocks(
     mut blocks: ResMut<Registry<Block>>,
+    mut definitions: ResMut<Registry<BlockDefinition>>,
+    packs: Res<ContentPacks>,
     mut loading: ResMut<LoadingManager>,
     mut end_writer: EventWriter<DoneLoadingEvent>,
     mut start_writer: EventWriter<AddLoadingEvent>,
 ) {
     let id = loading.register_loader(&mut start_writer);
 
-    blocks.register(
-        BlockBuilder::new("cosmos:stone".into(), 10.0)
-            .add_property(BlockProperty::Opaque)
-            .add_property(BlockProperty::Full)
-            .create(),
-    );
-
-    blocks.register(
-        BlockBuilder::new("cosmos:grass".into(), 3.0)
-            .add_property(BlockProperty::Opaque)
-            .add_property(BlockProperty::Full)
-            .create(),
-    );
-
-    blocks.register(
-        BlockBuilder::new("cosmos:dirt".into(), 3.0)
-            .add_property(BlockProperty::Opaque)
-            .add_property(BlockProperty::Full)
-            .create(),
-    );
-
-    blocks.register(
-        BlockBuilder::new("cosmos:cherry_leaf".into(), 0.1)
-            .add_property(BlockProperty::Transparent)
-            .create(),
-    );
-
-    blocks.register(
-        BlockBuilder::new("cosmos:cherry_log".into(), 3.0)
-            .add_property(BlockProperty::Opaque)
-            .add_property(BlockProperty::Full)
-            .create(),
-    );
-
-    blocks.register(
-        BlockBuilder::new("cosmos:ship_core".into(), 2.0)
-            .add_property(BlockProperty::Opaque)
-            .add_property(BlockProperty::Full)
-            .add_property(BlockProperty::ShipOnly)
-            .create(),
-    );
-
-    blocks.register(
-        BlockBuilder::new("cosmos:energy_cell".to_owned(), 2.0)
-            .add_property(BlockProperty::Opaque)
-            .add_property(BlockProperty::Full)
-            .create(),
-    );
-
-    blocks.register(
-        BlockBuilder::new("cosmos:reactor".to_owned(), 2.0)
-            .add_property(BlockProperty::Opaque)
-            .add_property(BlockProperty::Full)
-            .create(),
-    );
-
-    blocks.register(
-        BlockBuilder::new("cosmos:laser_cannon".to_owned(), 2.0)
-            .add_property(BlockProperty::Opaque)
-            .add_property(BlockProperty::Full)
-            .create(),
-    );
-
-    blocks.register(
-        BlockBuilder::new("cosmos:ship_hull".to_owned(), 6.0)
-            .add_property(BlockProperty::Opaque)
-            .add_property(BlockProperty::Full)
-            .create(),
-    );
-
-    blocks.register(
-        BlockBuilder::new("cosmos:thruster".to_owned(), 2.0)
-            .add_property(BlockProperty::Opaque)
-            .add_property(BlockProperty::Full)
-            .create(),
-    );
-
-    blocks.register(
-        BlockBuilder::new("cosmos:light".to_owned(), 0.1)
-            .add_property(BlockProperty::Opaque)
-            .add_property(BlockProperty::Full)
-            .create(),
-    );
-
-    blocks.register(
-        BlockBuilder::new("cosmos:glass".to_owned(), 6.0)
-            .add_property(BlockProperty::Transparent)
-            .add_property(BlockProperty::Full)
-            .create(),
-    );
+    match load_block_definitions(&packs, &blocks) {
+        Ok(loaded) => {
+            for definition in loaded {
+                blocks.register(definition.create_block());
+                definitions.register(definition);
+            }
+        }
+        Err(errors) => content::panic_with_errors("block definitions", &errors),
+    }
 
     loading.finish_loading(id, &mut end_writer);
 }
@@ -141,6 +69,7 @@ pub(super) fn register<T: States + Clone + Copy>(
     loading_state: T,
 ) {
     registry::create_registry::<Block>(app);
+    registry::create_registry::<BlockDefinition>(app);
 
     app.add_systems((
         // Game will break without air & needs this at ID 0, so load that first
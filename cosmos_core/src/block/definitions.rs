@@ -0,0 +1,374 @@
+//! Every block other than air is defined in the `content/blocks` files of a content pack.
+//!
+//! A definition contains everything about a block, such as its density, hardness & any light it gives off.
+//! These are loaded into the [`Registry<BlockDefinition>`], which the other registries are filled out from.
+//!
+//! An example definition:
+//!
+//! ```json
+//! {
+//!     "unlocalized_name": "cosmos:thruster",
+//!     "density": 2.0,
+//!     "hardness": 20.0,
+//!     "regeneration": 0.5,
+//!     "properties": ["Opaque", "Full"],
+//!     "thruster": { "strength": 5.0, "energy_consumption": 100.0 }
+//! }
+//! ```
+
+use serde::Deserialize;
+
+use crate::{
+    content::{self, ContentError, ContentFile, ContentObject, ContentPacks},
+    inventory::container::ContainerProperty,
+    registry::{identifiable::Identifiable, Registry},
+    structure::systems::{
+        energy_generation_system::EnergyGenerationProperty,
+        energy_storage_system::EnergyStorageProperty, laser_cannon_system::LaserCannonProperty,
+        repair_system::RepairProperty, thruster_system::ThrusterProperty,
+    },
+};
+
+use super::{block_builder::BlockBuilder, Block, BlockProperty};
+
+/// The kind of content block definitions are
+const BLOCKS_CONTENT: &str = "blocks";
+
+/// Every field a block definition can have
+const FIELDS: [&str; 12] = [
+    "unlocalized_name",
+    "density",
+    "hardness",
+    "regeneration",
+    "properties",
+    "light",
+    "thruster",
+    "energy_generation",
+    "energy_storage",
+    "laser_cannon",
+    "repair",
+    "container",
+];
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// The light a block gives off
+pub struct BlockLightDefinition {
+    /// The color of the light as [red, green, blue], each from 0 to 255
+    pub color: [u8; 3],
+    /// How intense the light is in lumens
+    pub intensity: f32,
+    /// How far this light will reach
+    pub range: f32,
+}
+
+#[derive(Debug)]
+/// Everything about a block, as read from its content file
+pub struct BlockDefinition {
+    id: u16,
+    unlocalized_name: String,
+
+    /// How dense the block is
+    pub density: f32,
+    /// How much damage the block can take before it breaks
+    pub hardness: f32,
+    /// How much health the block regains every second after being damaged - 0 if it doesn't regenerate
+    pub regeneration: f32,
+    /// The block's properties
+    pub properties: Vec<BlockProperty>,
+    /// The light this block gives off, if any
+    pub light: Option<BlockLightDefinition>,
+    /// Present if this block is a thruster
+    pub thruster: Option<ThrusterProperty>,
+    /// Present if this block generates energy
+    pub energy_generation: Option<EnergyGenerationProperty>,
+    /// Present if this block stores energy
+    pub energy_storage: Option<EnergyStorageProperty>,
+    /// Present if this block is a laser cannon
+    pub laser_cannon: Option<LaserCannonProperty>,
+    /// Present if this block repairs other blocks
+    pub repair: Option<RepairProperty>,
+    /// Present if this block stores items
+    pub container: Option<ContainerProperty>,
+}
+
+impl Identifiable for BlockDefinition {
+    fn id(&self) -> u16 {
+        self.id
+    }
+
+    fn set_numeric_id(&mut self, id: u16) {
+        self.id = id;
+    }
+
+    fn unlocalized_name(&self) -> &str {
+        &self.unlocalized_name
+    }
+}
+
+impl BlockDefinition {
+    /// Creates the block this defines
+    pub fn create_block(&self) -> Block {
+        let mut builder = BlockBuilder::new(self.unlocalized_name.clone(), self.density);
+
+        for property in self.properties.iter() {
+            builder.add_property(*property);
+        }
+
+        builder.create()
+    }
+}
+
+fn non_negative(object: &ContentObject, field: &str, value: f32) -> Result<f32, ContentError> {
+    if value.is_finite() && value >= 0.0 {
+        Ok(value)
+    } else {
+        Err(object.error(field, format!("Must be 0 or more, but was {value}")))
+    }
+}
+
+fn positive(object: &ContentObject, field: &str, value: f32) -> Result<f32, ContentError> {
+    if value.is_finite() && value > 0.0 {
+        Ok(value)
+    } else {
+        Err(object.error(field, format!("Must be more than 0, but was {value}")))
+    }
+}
+
+fn parse_block_definition(object: &ContentObject) -> Result<BlockDefinition, ContentError> {
+    object.deny_unknown_fields(&FIELDS)?;
+
+    let unlocalized_name = object.required::<String>("unlocalized_name")?;
+
+    let density = non_negative(object, "density", object.required("density")?)?;
+    let hardness = non_negative(object, "hardness", object.required("hardness")?)?;
+    let regeneration = non_negative(
+        object,
+        "regeneration",
+        object.optional("regeneration")?.unwrap_or(0.0),
+    )?;
+
+    let properties = object.required::<Vec<BlockProperty>>("properties")?;
+
+    let opaque = properties.contains(&BlockProperty::Opaque);
+    let transparent = properties.contains(&BlockProperty::Transparent);
+
+    if opaque == transparent {
+        return Err(object.error(
+            "properties",
+            "Must contain exactly one of Opaque or Transparent",
+        ));
+    }
+
+    let light = object.optional::<BlockLightDefinition>("light")?;
+
+    if let Some(light) = light {
+        positive(object, "light.intensity", light.intensity)?;
+        positive(object, "light.range", light.range)?;
+    }
+
+    let thruster = object.optional::<ThrusterProperty>("thruster")?;
+
+    if let Some(thruster) = thruster {
+        non_negative(object, "thruster.strength", thruster.strength)?;
+        non_negative(
+            object,
+            "thruster.energy_consumption",
+            thruster.energy_consupmtion,
+        )?;
+    }
+
+    let energy_generation = object.optional::<EnergyGenerationProperty>("energy_generation")?;
+
+    if let Some(generation) = energy_generation {
+        non_negative(
+            object,
+            "energy_generation.generation_rate",
+            generation.generation_rate,
+        )?;
+    }
+
+    let energy_storage = object.optional::<EnergyStorageProperty>("energy_storage")?;
+
+    if let Some(storage) = energy_storage {
+        non_negative(object, "energy_storage.capacity", storage.capacity)?;
+    }
+
+    let laser_cannon = object.optional::<LaserCannonProperty>("laser_cannon")?;
+
+    if let Some(cannon) = laser_cannon {
+        non_negative(
+            object,
+            "laser_cannon.energy_per_shot",
+            cannon.energy_per_shot,
+        )?;
+    }
+
+    let repair = object.optional::<RepairProperty>("repair")?;
+
+    if let Some(repair) = repair {
+        non_negative(object, "repair.repair_rate", repair.repair_rate)?;
+        non_negative(
+            object,
+            "repair.energy_consumption",
+            repair.energy_consumption,
+        )?;
+    }
+
+    let container = object.optional::<ContainerProperty>("container")?;
+
+    if container
+        .map(|container| container.slots == 0)
+        .unwrap_or(false)
+    {
+        return Err(object.error("container.slots", "Must be more than 0, but was 0"));
+    }
+
+    Ok(BlockDefinition {
+        id: 0,
+        unlocalized_name,
+        density,
+        hardness,
+        regeneration,
+        properties,
+        light,
+        thruster,
+        energy_generation,
+        energy_storage,
+        laser_cannon,
+        repair,
+        container,
+    })
+}
+
+/// Reads every block definition in a content file.
+///
+/// Every problem found is returned, rather than just the first.
+pub fn parse_block_definitions(
+    file: &ContentFile,
+) -> Result<Vec<BlockDefinition>, Vec<ContentError>> {
+    content::parse_definitions(file, parse_block_definition)
+}
+
+/// Reads every block definition from every content pack, in the order they should be registered.
+///
+/// Every problem found is returned, rather than just the first.
+///
+/// * `blocks` Any blocks already registered, which definitions can't reuse the names of
+pub fn load_block_definitions(
+    packs: &ContentPacks,
+    blocks: &Registry<Block>,
+) -> Result<Vec<BlockDefinition>, Vec<ContentError>> {
+    content::load_definitions(
+        packs,
+        BLOCKS_CONTENT,
+        |name| blocks.from_id(name).is_some(),
+        parse_block_definition,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use serde_json::Value;
+
+    use super::*;
+
+    fn file(path: &str, value: Value) -> ContentFile {
+        ContentFile {
+            namespace: "test".into(),
+            path: path.into(),
+            value,
+        }
+    }
+
+    #[test]
+    fn parses_definitions() {
+        let value = serde_json::json!([
+            {
+                "unlocalized_name": "test:core",
+                "density": 2.0,
+                "hardness": 100.0,
+                "regeneration": 0.5,
+                "properties": ["Opaque", "Full", "ShipOnly"],
+                "light": { "color": [81, 143, 225], "intensity": 100.0, "range": 6.0 },
+                "thruster": { "strength": 1.0, "energy_consumption": 100.0 },
+                "energy_storage": { "capacity": 1000.0 }
+            },
+            {
+                "unlocalized_name": "test:leaf",
+                "density": 0.1,
+                "hardness": 1.0,
+                "properties": ["Transparent"]
+            }
+        ]);
+
+        let definitions = parse_block_definitions(&file("test.json", value)).unwrap();
+
+        assert_eq!(definitions.len(), 2);
+        assert_eq!(definitions[0].unlocalized_name(), "test:core");
+        assert_eq!(definitions[0].light.unwrap().color, [81, 143, 225]);
+        assert_eq!(definitions[0].thruster.unwrap().energy_consupmtion, 100.0);
+        assert_eq!(definitions[0].energy_storage.unwrap().capacity, 1000.0);
+        assert!(definitions[0].laser_cannon.is_none());
+        assert_eq!(definitions[0].regeneration, 0.5);
+        assert_eq!(definitions[1].regeneration, 0.0);
+        assert!(definitions[1].light.is_none());
+        assert_eq!(definitions[1].properties, vec![BlockProperty::Transparent]);
+    }
+
+    #[test]
+    fn errors_name_file_and_field() {
+        let value = serde_json::json!([
+            {
+                "unlocalized_name": "test:stone",
+                "density": 10.0,
+                "hardness": "very",
+                "properties": ["Opaque", "Full"]
+            },
+            {
+                "unlocalized_name": "test:thruster",
+                "density": 2.0,
+                "hardness": 20.0,
+                "properties": ["Opaque", "Full"],
+                "thruster": { "strenght": 5.0, "energy_consumption": 100.0 }
+            },
+            {
+                "unlocalized_name": "test:glass",
+                "density": 6.0,
+                "hardness": 100.0,
+                "properties": ["Opaque", "Transparent"]
+            },
+            {
+                "unlocalized_name": "other:dirt",
+                "density": 3.0,
+                "hardness": 10.0,
+                "properties": ["Opaque", "Full"]
+            }
+        ]);
+
+        let errors = parse_block_definitions(&file("blocks/test.json", value))
+            .err()
+            .expect("Definitions should be invalid");
+
+        let fields = errors
+            .iter()
+            .map(|e| e.field.as_deref().unwrap())
+            .collect::<Vec<&str>>();
+
+        assert_eq!(
+            fields,
+            vec![
+                "[0].hardness",
+                "[1].thruster",
+                "[2].properties",
+                "[3].unlocalized_name"
+            ]
+        );
+        assert!(errors
+            .iter()
+            .all(|e| e.file == Path::new("blocks/test.json")));
+        assert!(errors[1].message.contains("strenght"));
+    }
+}
@@ -4,7 +4,7 @@ use bevy::prelude::{App, IntoSystemAppConfig, OnExit, Res, ResMut, States};
 
 use crate::registry::{self, identifiable::Identifiable, Registry};
 
-use super::Block;
+use super::{definitions::BlockDefinition, Block};
 
 #[derive(Debug)]
 /// Used to represent how much damage a block can take before it breaks
@@ -14,17 +14,21 @@ pub struct BlockHardness {
 
     // Air: 0, Leaves: 1, Grass/Dirt: 10, Stone: 50, Hull: 100,
     hardness: f32,
+    regeneration: f32,
 }
 
 impl BlockHardness {
     /// Creates a new hardness value for that block.
     ///
+    /// * `regeneration` How much health the block regains every second after being damaged
+    ///
     /// This still needs to be registered!
-    pub fn new(block: &Block, hardness: f32) -> BlockHardness {
+    pub fn new(block: &Block, hardness: f32, regeneration: f32) -> BlockHardness {
         Self {
             id: 0,
             unlocalized_name: block.unlocalized_name.to_owned(),
             hardness,
+            regeneration,
         }
     }
 
@@ -32,6 +36,13 @@ impl BlockHardness {
     pub fn hardness(&self) -> f32 {
         self.hardness
     }
+
+    /// Gets how much health the block regains every second after being damaged.
+    ///
+    /// 0 if the block doesn't regenerate.
+    pub fn regeneration(&self) -> f32 {
+        self.regeneration
+    }
 }
 
 impl Identifiable for BlockHardness {
@@ -51,11 +62,12 @@ impl Identifiable for BlockHardness {
 fn register_hardness(
     registry: &mut Registry<BlockHardness>,
     value: f32,
+    regeneration: f32,
     blocks: &Registry<Block>,
     name: &str,
 ) {
     if let Some(block) = blocks.from_id(name) {
-        registry.register(BlockHardness::new(block, value));
+        registry.register(BlockHardness::new(block, value, regeneration));
     } else {
         println!("[Block Hardness] Missing block {name}");
     }
@@ -63,25 +75,21 @@ fn register_hardness(
 
 fn register_block_hardness(
     blocks: Res<Registry<Block>>,
+    definitions: Res<Registry<BlockDefinition>>,
     mut registry: ResMut<Registry<BlockHardness>>,
 ) {
-    register_hardness(&mut registry, 0.0, &blocks, "cosmos:air");
-    register_hardness(&mut registry, 10.0, &blocks, "cosmos:grass");
-    register_hardness(&mut registry, 10.0, &blocks, "cosmos:dirt");
-    register_hardness(&mut registry, 50.0, &blocks, "cosmos:stone");
-
-    register_hardness(&mut registry, 30.0, &blocks, "cosmos:cherry_log");
-    register_hardness(&mut registry, 1.0, &blocks, "cosmos:cherry_leaf");
-
-    register_hardness(&mut registry, 100.0, &blocks, "cosmos:ship_core");
-    register_hardness(&mut registry, 20.0, &blocks, "cosmos:energy_cell");
-    register_hardness(&mut registry, 20.0, &blocks, "cosmos:reactor");
-    register_hardness(&mut registry, 20.0, &blocks, "cosmos:laser_cannon");
-    register_hardness(&mut registry, 20.0, &blocks, "cosmos:thruster");
-    register_hardness(&mut registry, 20.0, &blocks, "cosmos:light");
-
-    register_hardness(&mut registry, 100.0, &blocks, "cosmos:ship_hull");
-    register_hardness(&mut registry, 100.0, &blocks, "cosmos:glass");
+    // Air isn't defined in the content files
+    register_hardness(&mut registry, 0.0, 0.0, &blocks, "cosmos:air");
+
+    for definition in definitions.iter() {
+        register_hardness(
+            &mut registry,
+            definition.hardness,
+            definition.regeneration,
+            &blocks,
+            definition.unlocalized_name(),
+        );
+    }
 }
 
 fn sanity_check(blocks: Res<Registry<Block>>, hardness: Res<Registry<BlockHardness>>) {
@@ -1,9 +1,9 @@
 //! Blocks are the smallest thing found on any structure
 
-use std::fmt::Display;
+use std::{f32::consts::PI, fmt::Display};
 
 use bevy::{
-    prelude::{App, States, Vec3},
+    prelude::{App, Quat, States, Vec3},
     reflect::{FromReflect, Reflect},
 };
 use serde::{Deserialize, Serialize};
@@ -12,9 +12,10 @@ use crate::registry::identifiable::Identifiable;
 
 pub mod block_builder;
 pub mod blocks;
+pub mod definitions;
 pub mod hardness;
 
-#[derive(Reflect, FromReflect, Debug, Eq, PartialEq, Clone, Copy, Hash)]
+#[derive(Reflect, FromReflect, Debug, Eq, PartialEq, Clone, Copy, Hash, Serialize, Deserialize)]
 /// Represents different properties a block can has
 pub enum BlockProperty {
     /// Is this block non-see-through
@@ -118,47 +119,166 @@ impl BlockFace {
         }
     }
 
-    /// BlockFace::Top will result in no rotation being made
-    pub fn rotate_face(face: BlockFace, top_face: BlockFace) -> BlockFace {
-        match top_face {
-            Self::Top => face,
-            Self::Bottom => match face {
-                Self::Top => Self::Bottom,
-                Self::Bottom => Self::Top,
-                Self::Back => Self::Front,
-                Self::Front => Self::Back,
-                _ => face,
-            },
-            Self::Left => match face {
-                Self::Bottom => Self::Left,
-                Self::Right => Self::Bottom,
-                Self::Left => Self::Top,
-                Self::Top => Self::Right,
-                _ => face,
-            },
-            Self::Right => match face {
-                Self::Bottom => Self::Right,
-                Self::Right => Self::Top,
-                Self::Left => Self::Bottom,
-                Self::Top => Self::Left,
-                _ => face,
-            },
-            Self::Front => match face {
-                Self::Back => Self::Top,
-                Self::Bottom => Self::Back,
-                Self::Front => Self::Bottom,
-                Self::Top => Self::Front,
-                _ => face,
-            },
-            Self::Back => match face {
-                Self::Front => Self::Top,
-                Self::Back => Self::Bottom,
-                Self::Top => Self::Back,
-                Self::Bottom => Self::Front,
-                _ => face,
-            },
+    /// Gets the face whose direction is closest to this direction.
+    pub fn from_direction_vec3(direction: Vec3) -> Self {
+        let abs = direction.abs();
+
+        if abs.x >= abs.y && abs.x >= abs.z {
+            if direction.x >= 0.0 {
+                Self::Right
+            } else {
+                Self::Left
+            }
+        } else if abs.y >= abs.z {
+            if direction.y >= 0.0 {
+                Self::Top
+            } else {
+                Self::Bottom
+            }
+        } else if direction.z >= 0.0 {
+            Self::Front
+        } else {
+            Self::Back
+        }
+    }
+
+    /// Gets which face of the unrotated block ends up as this face once the block is rotated.
+    ///
+    /// [`BlockRotation::IDENTITY`] will result in no rotation being made
+    pub fn rotate_face(face: BlockFace, rotation: BlockRotation) -> BlockFace {
+        Self::from_direction_vec3(rotation.as_quat().inverse().mul_vec3(face.direction_vec3()))
+    }
+}
+
+#[derive(
+    Debug, PartialEq, Eq, Reflect, FromReflect, Default, Copy, Clone, Serialize, Deserialize, Hash,
+)]
+/// How many quarter turns a block is rotated around its up axis
+pub enum BlockSubRotation {
+    #[default]
+    /// No rotation
+    None,
+    /// Turned clockwise when looking down at the block's top
+    CW,
+    /// Turned halfway around
+    Flip,
+    /// Turned counter-clockwise when looking down at the block's top
+    CCW,
+}
+
+impl BlockSubRotation {
+    /// Returns the index for each sub rotation [0, 3].
+    pub fn index(&self) -> usize {
+        match *self {
+            Self::None => 0,
+            Self::CW => 1,
+            Self::Flip => 2,
+            Self::CCW => 3,
         }
     }
+
+    /// Get's this sub rotation from its index.
+    ///
+    /// Note this will panic if index is not <= 3.
+    pub fn from_index(index: usize) -> Self {
+        match index {
+            0 => Self::None,
+            1 => Self::CW,
+            2 => Self::Flip,
+            3 => Self::CCW,
+            _ => panic!("Index must be 0 <= index <= 3"),
+        }
+    }
+
+    fn angle(&self) -> f32 {
+        match *self {
+            Self::None => 0.0,
+            Self::CW => -PI / 2.0,
+            Self::Flip => PI,
+            Self::CCW => PI / 2.0,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Reflect, FromReflect, Copy, Clone, Serialize, Deserialize, Hash)]
+/// The orientation of a block - one of the 24 ways a cube can be rotated.
+///
+/// This is made of which face the block's top points towards, and how far the block is turned around that axis.
+pub struct BlockRotation {
+    /// The direction the block's top is pointing
+    pub block_up: BlockFace,
+    /// How far the block is turned around its up axis
+    pub sub_rotation: BlockSubRotation,
+}
+
+impl Default for BlockRotation {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl From<BlockFace> for BlockRotation {
+    fn from(block_up: BlockFace) -> Self {
+        Self::new(block_up, BlockSubRotation::None)
+    }
+}
+
+impl BlockRotation {
+    /// A block that hasn't been rotated at all
+    pub const IDENTITY: Self = Self {
+        block_up: BlockFace::Top,
+        sub_rotation: BlockSubRotation::None,
+    };
+
+    /// Creates a new block rotation
+    pub fn new(block_up: BlockFace, sub_rotation: BlockSubRotation) -> Self {
+        Self {
+            block_up,
+            sub_rotation,
+        }
+    }
+
+    /// Iterates over all 24 possible rotations
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..6).flat_map(|up| {
+            (0..4).map(move |sub| {
+                Self::new(BlockFace::from_index(up), BlockSubRotation::from_index(sub))
+            })
+        })
+    }
+
+    /// Finds the rotation that points the block's top + front in these directions.
+    ///
+    /// Returns None if `block_up` and `front` aren't perpendicular
+    pub fn facing(block_up: BlockFace, front: BlockFace) -> Option<Self> {
+        Self::all().find(|rotation| rotation.block_up == block_up && rotation.front() == front)
+    }
+
+    /// Gets the rotation to apply to the block's model
+    pub fn as_quat(&self) -> Quat {
+        let up = match self.block_up {
+            BlockFace::Top => Quat::IDENTITY,
+            BlockFace::Front => Quat::from_axis_angle(Vec3::X, PI / 2.0),
+            BlockFace::Back => Quat::from_axis_angle(Vec3::X, -PI / 2.0),
+            BlockFace::Left => Quat::from_axis_angle(Vec3::Z, PI / 2.0),
+            BlockFace::Right => Quat::from_axis_angle(Vec3::Z, -PI / 2.0),
+            BlockFace::Bottom => Quat::from_axis_angle(Vec3::X, PI),
+        };
+
+        up * Quat::from_axis_angle(Vec3::Y, self.sub_rotation.angle())
+    }
+
+    /// Gets the direction this face of the unrotated block points once the block is rotated
+    pub fn direction_of(&self, face: BlockFace) -> BlockFace {
+        BlockFace::from_direction_vec3(self.as_quat().mul_vec3(face.direction_vec3()))
+    }
+
+    /// Gets the direction the front of the block's model points.
+    ///
+    /// Models face -Z (the same as bevy's forward), so an unrotated block's front is [`BlockFace::Back`].
+    pub fn front(&self) -> BlockFace {
+        self.direction_of(BlockFace::Back)
+    }
 }
 
 impl Display for BlockFace {
@@ -261,6 +381,12 @@ impl Block {
         self.visibility & BlockProperty::Empty.id() != 0
     }
 
+    /// Returns true if this block can only be placed on ships
+    #[inline]
+    pub fn is_ship_only(&self) -> bool {
+        self.visibility & BlockProperty::ShipOnly.id() != 0
+    }
+
     /// Returns the density of this block
     #[inline]
     pub fn density(&self) -> f32 {
@@ -283,5 +409,40 @@ pub(super) fn register<T: States + Clone + Copy>(
     blocks::register(app, pre_loading_state, loading_state);
     hardness::register(app, loading_state, post_loading_state);
 
-    app.register_type::<BlockFace>();
+    app.register_type::<BlockFace>()
+        .register_type::<BlockRotation>();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn every_rotation_is_unique() {
+        let mut seen = Vec::new();
+
+        for rotation in BlockRotation::all() {
+            let faces = (rotation.direction_of(BlockFace::Top), rotation.front());
+
+            assert_eq!(faces.0, rotation.block_up);
+            assert!(!seen.contains(&faces));
+            assert_eq!(BlockRotation::facing(faces.0, faces.1), Some(rotation));
+
+            seen.push(faces);
+        }
+
+        assert_eq!(seen.len(), 24);
+    }
+
+    #[test]
+    fn rotate_face_undoes_direction_of() {
+        for rotation in BlockRotation::all() {
+            for face in (0..6).map(BlockFace::from_index) {
+                assert_eq!(
+                    BlockFace::rotate_face(rotation.direction_of(face), rotation),
+                    face
+                );
+            }
+        }
+    }
 }
@@ -9,7 +9,7 @@ use bevy::{
 
 use crate::{
     block::Block,
-    item::{Item, DEFAULT_MAX_STACK_SIZE},
+    item::{Item, ItemCategory, DEFAULT_MAX_STACK_SIZE},
     loader::{AddLoadingEvent, DoneLoadingEvent, LoadingManager},
     registry::{identifiable::Identifiable, Registry},
 };
@@ -80,7 +80,10 @@ fn create_links(
         if let Some(item) = items.from_id(cosmos_id) {
             block_items.create_link(item, block);
         } else {
-            items.register(Item::new(cosmos_id.to_owned(), DEFAULT_MAX_STACK_SIZE));
+            items.register(
+                Item::new(cosmos_id.to_owned(), DEFAULT_MAX_STACK_SIZE)
+                    .with_category(ItemCategory::Block),
+            );
             block_items.create_link(items.from_id(cosmos_id).unwrap(), block);
         }
     }
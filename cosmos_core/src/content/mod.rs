@@ -0,0 +1,362 @@
+//! Content (such as blocks) is defined in data files rather than in code.
+//!
+//! Content comes from [`ContentPack`]s. The game's own content is the `cosmos` pack in the top-level `assets` directory,
+//! and any other packs are read from the `packs` directory. Each pack's content lives in its `content` directory,
+//! split into a directory for each kind of content (such as `content/blocks`). Each file is a JSON array of definitions.
+//!
+//! Any problems with a content file are reported as a [`ContentError`], which names the file and field at fault.
+
+pub mod packs;
+
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    prelude::{App, States},
+    utils::HashMap,
+};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+use crate::registry::identifiable::Identifiable;
+
+pub use self::packs::{ContentPack, ContentPackId, ContentPacks, BASE_NAMESPACE};
+
+/// The directory in each pack that contains its content files
+const CONTENT_DIRECTORY: &str = "content";
+
+#[derive(Debug)]
+/// Something is wrong with a content file
+pub struct ContentError {
+    /// The file that has the problem
+    pub file: PathBuf,
+    /// The field that has the problem, such as `[3].hardness`.
+    ///
+    /// None if the problem is with the file as a whole.
+    pub field: Option<String>,
+    /// What is wrong
+    pub message: String,
+}
+
+impl ContentError {
+    /// Creates an error that is about the file as a whole, rather than one of its fields
+    pub fn file(file: &Path, message: impl Into<String>) -> Self {
+        Self {
+            file: file.to_owned(),
+            field: None,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ContentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.field {
+            Some(field) => write!(f, "{}: {field} - {}", self.file.display(), self.message),
+            None => write!(f, "{}: {}", self.file.display(), self.message),
+        }
+    }
+}
+
+impl std::error::Error for ContentError {}
+
+/// Prints every error & stops the game, since it can't run with broken content.
+///
+/// * `what` What was being loaded, such as "block definitions"
+pub fn panic_with_errors(what: &str, errors: &[ContentError]) -> ! {
+    for error in errors.iter() {
+        eprintln!("{error}");
+    }
+
+    panic!(
+        "Unable to load the {what} - {} error(s) found",
+        errors.len()
+    );
+}
+
+/// A content file that has been read, but not yet parsed
+pub struct ContentFile {
+    /// The namespace of the pack this file is from.
+    ///
+    /// Everything defined in this file must be in this namespace.
+    pub namespace: String,
+    /// Where this file is
+    pub path: PathBuf,
+    /// The file's contents
+    pub value: Value,
+}
+
+/// Reads every content file for this kind of content (such as `"blocks"`) from every pack, in load order.
+///
+/// Within a pack, files are sorted by name so the order content is registered in doesn't depend on the file system.
+/// Packs that have no content of this kind are skipped.
+pub fn read_content_files(
+    packs: &ContentPacks,
+    kind: &str,
+) -> Result<Vec<ContentFile>, Vec<ContentError>> {
+    let mut files = Vec::new();
+    let mut errors = Vec::new();
+
+    for pack in packs.iter() {
+        let directory = pack.directory().join(CONTENT_DIRECTORY).join(kind);
+
+        if !directory.is_dir() {
+            continue;
+        }
+
+        let entries = match fs::read_dir(&directory) {
+            Ok(entries) => entries,
+            Err(e) => {
+                errors.push(ContentError::file(
+                    &directory,
+                    format!("Unable to read directory - {e}"),
+                ));
+                continue;
+            }
+        };
+
+        let mut paths = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().map(|ext| ext == "json").unwrap_or(false))
+            .collect::<Vec<PathBuf>>();
+
+        paths.sort();
+
+        for path in paths {
+            let value = fs::read(&path)
+                .map_err(|e| ContentError::file(&path, format!("Unable to read file - {e}")))
+                .and_then(|contents| {
+                    serde_json::from_slice::<Value>(&contents)
+                        .map_err(|e| ContentError::file(&path, format!("Invalid JSON - {e}")))
+                });
+
+            match value {
+                Ok(value) => files.push(ContentFile {
+                    namespace: pack.namespace().to_owned(),
+                    path,
+                    value,
+                }),
+                Err(e) => errors.push(e),
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(files)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Reads every definition in a content file, using `parse` to read each one.
+///
+/// Every definition's unlocalized name must be in the namespace of the pack the file is from.
+/// Every problem found is returned, rather than just the first.
+pub fn parse_definitions<T: Identifiable>(
+    file: &ContentFile,
+    parse: impl Fn(&ContentObject) -> Result<T, ContentError>,
+) -> Result<Vec<T>, Vec<ContentError>> {
+    let Value::Array(entries) = &file.value else {
+        return Err(vec![ContentError::file(
+            &file.path,
+            "Expected an array of definitions",
+        )]);
+    };
+
+    let mut definitions = Vec::with_capacity(entries.len());
+    let mut errors = Vec::new();
+
+    for (i, entry) in entries.iter().enumerate() {
+        let definition = ContentObject::new(&file.path, format!("[{i}]"), entry).and_then(|object| {
+            let definition = parse(&object)?;
+
+            let name = definition.unlocalized_name();
+
+            let in_namespace = name
+                .split_once(':')
+                .map(|(namespace, identifier)| namespace == file.namespace && !identifier.is_empty())
+                .unwrap_or(false);
+
+            if in_namespace {
+                Ok(definition)
+            } else {
+                Err(object.error(
+                    "unlocalized_name",
+                    format!(
+                        "\"{name}\" should be formatted as {}:identifier, using the namespace of the pack it's in",
+                        file.namespace
+                    ),
+                ))
+            }
+        });
+
+        match definition {
+            Ok(definition) => definitions.push(definition),
+            Err(e) => errors.push(e),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(definitions)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Reads every definition of this kind of content (such as `"blocks"`) from every pack, in the order they should be registered.
+///
+/// Any unlocalized name that is defined more than once is reported as an error.
+/// Every problem found is returned, rather than just the first.
+///
+/// * `is_registered` Returns true if something with this unlocalized name was already registered without a content file
+/// * `parse` Reads a single definition
+pub fn load_definitions<T: Identifiable>(
+    packs: &ContentPacks,
+    kind: &str,
+    is_registered: impl Fn(&str) -> bool,
+    parse: impl Fn(&ContentObject) -> Result<T, ContentError>,
+) -> Result<Vec<T>, Vec<ContentError>> {
+    let files = read_content_files(packs, kind)?;
+
+    let mut definitions = Vec::new();
+    let mut errors = Vec::new();
+    let mut defined_in = HashMap::<String, PathBuf>::new();
+
+    for file in files.iter() {
+        match parse_definitions(file, &parse) {
+            Ok(file_definitions) => {
+                for (i, definition) in file_definitions.into_iter().enumerate() {
+                    let name = definition.unlocalized_name();
+
+                    let duplicate = if is_registered(name) {
+                        Some(format!("{name} is already registered"))
+                    } else {
+                        defined_in.get(name).map(|other| {
+                            format!("{name} is already defined in {}", other.display())
+                        })
+                    };
+
+                    if let Some(message) = duplicate {
+                        errors.push(ContentError {
+                            file: file.path.clone(),
+                            field: Some(format!("[{i}].unlocalized_name")),
+                            message,
+                        });
+                    } else {
+                        defined_in.insert(name.to_owned(), file.path.clone());
+                        definitions.push(definition);
+                    }
+                }
+            }
+            Err(mut file_errors) => errors.append(&mut file_errors),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(definitions)
+    } else {
+        Err(errors)
+    }
+}
+
+/// A JSON object read from a content file.
+///
+/// This keeps track of where the object is, so any errors can name the exact file and field at fault.
+pub struct ContentObject<'a> {
+    file: &'a Path,
+    path: String,
+    fields: &'a Map<String, Value>,
+}
+
+impl<'a> ContentObject<'a> {
+    /// Reads the value as an object
+    ///
+    /// * `file` The file this value is from
+    /// * `path` Where in the file this value is, such as `[3]`
+    pub fn new(file: &'a Path, path: String, value: &'a Value) -> Result<Self, ContentError> {
+        match value {
+            Value::Object(fields) => Ok(Self { file, path, fields }),
+            _ => Err(ContentError {
+                file: file.to_owned(),
+                field: Some(path),
+                message: "Expected an object".into(),
+            }),
+        }
+    }
+
+    /// Where in the file this object is, such as `[3]`.
+    ///
+    /// This is empty if the object is the whole file.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    fn field_path(&self, field: &str) -> String {
+        if self.path.is_empty() {
+            field.to_owned()
+        } else {
+            format!("{}.{field}", self.path)
+        }
+    }
+
+    /// Creates an error for one of this object's fields
+    pub fn error(&self, field: &str, message: impl Into<String>) -> ContentError {
+        ContentError {
+            file: self.file.to_owned(),
+            field: Some(self.field_path(field)),
+            message: message.into(),
+        }
+    }
+
+    /// Reads a field that must be an array of objects, such as `[{ ... }, { ... }]`
+    pub fn objects(&self, field: &str) -> Result<Vec<ContentObject<'a>>, ContentError> {
+        match self.fields.get(field) {
+            Some(Value::Array(values)) => {
+                let path = self.field_path(field);
+
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(i, value)| ContentObject::new(self.file, format!("{path}[{i}]"), value))
+                    .collect()
+            }
+            Some(_) => Err(self.error(field, "Expected an array of objects")),
+            None => Err(self.error(field, "Missing required field")),
+        }
+    }
+
+    /// Reads a field that must be present
+    pub fn required<T: DeserializeOwned>(&self, field: &str) -> Result<T, ContentError> {
+        self.optional(field)?
+            .ok_or_else(|| self.error(field, "Missing required field"))
+    }
+
+    /// Reads a field that may be left out
+    pub fn optional<T: DeserializeOwned>(&self, field: &str) -> Result<Option<T>, ContentError> {
+        self.fields
+            .get(field)
+            .map(|value| T::deserialize(value).map_err(|e| self.error(field, e.to_string())))
+            .transpose()
+    }
+
+    /// Makes sure this object has no fields other than the ones given, which catches any misspelled fields
+    pub fn deny_unknown_fields(&self, known: &[&str]) -> Result<(), ContentError> {
+        match self
+            .fields
+            .keys()
+            .find(|key| !known.contains(&key.as_str()))
+        {
+            Some(key) => Err(self.error(
+                key,
+                format!("Unknown field, expected one of: {}", known.join(", ")),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+pub(super) fn register<T: States + Clone + Copy>(app: &mut App, pre_loading_state: T) {
+    packs::register(app, pre_loading_state);
+}
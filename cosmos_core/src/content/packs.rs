@@ -0,0 +1,381 @@
+//! Content packs add content (such as blocks & items) under their own namespace, without needing to recompile the game.
+//!
+//! Each pack is a directory in `packs` that is laid out like an `assets` directory, with a `pack.json` manifest:
+//!
+//! ```json
+//! {
+//!     "namespace": "example",
+//!     "name": "Example Pack",
+//!     "version": "1.0.0",
+//!     "load_order": 10
+//! }
+//! ```
+//!
+//! The game's own content is always loaded first, then every pack in ascending `load_order`.
+//! Packs with the same load order are loaded in order of their namespace.
+
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::{App, EventWriter, IntoSystemAppConfig, OnEnter, ResMut, Resource, States};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::loader::{AddLoadingEvent, DoneLoadingEvent, LoadingManager};
+
+use super::{panic_with_errors, ContentError, ContentObject, CONTENT_DIRECTORY};
+
+/// The namespace of the game's own content
+pub const BASE_NAMESPACE: &str = "cosmos";
+
+/// The file in each pack's directory that describes the pack
+const MANIFEST_FILE: &str = "pack.json";
+
+/// Where the game's own assets directory can be, relative to where the game is run from.
+///
+/// The client & server are each run from their own directory, so they share the one at the top of the project.
+const BASE_DIRECTORIES: [&str; 2] = ["assets", "../assets"];
+
+/// Where the packs directory can be, relative to where the game is run from.
+const PACKS_DIRECTORIES: [&str; 2] = ["packs", "../packs"];
+
+/// Every field a pack manifest can have
+const MANIFEST_FIELDS: [&str; 4] = ["namespace", "name", "version", "load_order"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// Identifies a specific version of a content pack
+pub struct ContentPackId {
+    /// The namespace everything in this pack uses
+    pub namespace: String,
+    /// The pack's version
+    pub version: String,
+}
+
+impl fmt::Display for ContentPackId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.namespace, self.version)
+    }
+}
+
+#[derive(Debug, Clone)]
+/// A collection of content that all shares the same namespace
+pub struct ContentPack {
+    id: ContentPackId,
+    name: String,
+    load_order: i32,
+    directory: PathBuf,
+}
+
+impl ContentPack {
+    /// The namespace everything in this pack uses
+    pub fn namespace(&self) -> &str {
+        &self.id.namespace
+    }
+
+    /// Identifies this version of this pack
+    pub fn id(&self) -> &ContentPackId {
+        &self.id
+    }
+
+    /// The pack's human-readable name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Packs are loaded in ascending load order
+    pub fn load_order(&self) -> i32 {
+        self.load_order
+    }
+
+    /// The pack's directory, which is laid out like an `assets` directory
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    fn base(directory: PathBuf) -> Self {
+        Self {
+            id: ContentPackId {
+                namespace: BASE_NAMESPACE.to_owned(),
+                version: env!("CARGO_PKG_VERSION").to_owned(),
+            },
+            name: "Cosmos".to_owned(),
+            load_order: i32::MIN,
+            directory,
+        }
+    }
+}
+
+/// Reads a pack's manifest
+///
+/// * `directory` The pack's directory
+/// * `manifest_file` Where the manifest was read from
+/// * `value` The manifest's contents
+pub fn parse_manifest(
+    directory: &Path,
+    manifest_file: &Path,
+    value: &Value,
+) -> Result<ContentPack, ContentError> {
+    let object = ContentObject::new(manifest_file, String::new(), value)?;
+
+    object.deny_unknown_fields(&MANIFEST_FIELDS)?;
+
+    let namespace = object.required::<String>("namespace")?;
+
+    if namespace.is_empty()
+        || !namespace
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    {
+        return Err(object.error(
+            "namespace",
+            format!("\"{namespace}\" may only contain lowercase letters, digits & underscores"),
+        ));
+    }
+
+    if namespace == BASE_NAMESPACE {
+        return Err(object.error(
+            "namespace",
+            format!("\"{BASE_NAMESPACE}\" is reserved for the game's own content"),
+        ));
+    }
+
+    let name = object.required::<String>("name")?;
+    let version = object.required::<String>("version")?;
+
+    if version.is_empty() {
+        return Err(object.error("version", "Must not be empty"));
+    }
+
+    let load_order = object.required::<i32>("load_order")?;
+
+    Ok(ContentPack {
+        id: ContentPackId { namespace, version },
+        name,
+        load_order,
+        directory: directory.to_owned(),
+    })
+}
+
+#[derive(Debug, Default, Clone, Resource)]
+/// Every active content pack, in the order they are loaded.
+///
+/// The game's own content is always the first pack.
+pub struct ContentPacks {
+    packs: Vec<ContentPack>,
+}
+
+impl ContentPacks {
+    /// Puts the packs in load order, making sure no two packs use the same namespace
+    ///
+    /// * `base` The game's own content, which is always loaded first
+    /// * `packs` Every other pack
+    pub fn new(base: ContentPack, mut packs: Vec<ContentPack>) -> Result<Self, Vec<ContentError>> {
+        packs.sort_by(|a, b| {
+            a.load_order
+                .cmp(&b.load_order)
+                .then_with(|| a.namespace().cmp(b.namespace()))
+        });
+
+        let mut errors = Vec::new();
+
+        for (i, pack) in packs.iter().enumerate() {
+            if let Some(other) = packs[..i]
+                .iter()
+                .find(|other| other.namespace() == pack.namespace())
+            {
+                errors.push(ContentError {
+                    file: pack.directory.join(MANIFEST_FILE),
+                    field: Some("namespace".into()),
+                    message: format!(
+                        "{} is already used by the pack in {}",
+                        pack.namespace(),
+                        other.directory.display()
+                    ),
+                });
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        packs.insert(0, base);
+
+        Ok(Self { packs })
+    }
+
+    /// Finds the game's own content & every pack in the packs directory
+    pub fn discover() -> Result<Self, Vec<ContentError>> {
+        let Some(base_directory) = BASE_DIRECTORIES
+            .iter()
+            .map(PathBuf::from)
+            .find(|path| path.join(CONTENT_DIRECTORY).is_dir())
+        else {
+            return Err(vec![ContentError::file(
+                &Path::new(BASE_DIRECTORIES[0]).join(CONTENT_DIRECTORY),
+                "Unable to find the game's content directory",
+            )]);
+        };
+
+        let mut packs = Vec::new();
+        let mut errors = Vec::new();
+
+        if let Some(packs_directory) = PACKS_DIRECTORIES
+            .iter()
+            .map(PathBuf::from)
+            .find(|path| path.is_dir())
+        {
+            let entries = fs::read_dir(&packs_directory).map_err(|e| {
+                vec![ContentError::file(
+                    &packs_directory,
+                    format!("Unable to read directory - {e}"),
+                )]
+            })?;
+
+            let mut directories = entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.is_dir())
+                .collect::<Vec<PathBuf>>();
+
+            directories.sort();
+
+            for directory in directories {
+                let manifest_file = directory.join(MANIFEST_FILE);
+
+                let pack = fs::read(&manifest_file)
+                    .map_err(|e| {
+                        ContentError::file(&manifest_file, format!("Unable to read manifest - {e}"))
+                    })
+                    .and_then(|contents| {
+                        serde_json::from_slice::<Value>(&contents).map_err(|e| {
+                            ContentError::file(&manifest_file, format!("Invalid JSON - {e}"))
+                        })
+                    })
+                    .and_then(|value| parse_manifest(&directory, &manifest_file, &value));
+
+                match pack {
+                    Ok(pack) => packs.push(pack),
+                    Err(e) => errors.push(e),
+                }
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Self::new(ContentPack::base(base_directory), packs)
+    }
+
+    /// Iterates over every pack in load order
+    pub fn iter(&self) -> impl Iterator<Item = &ContentPack> {
+        self.packs.iter()
+    }
+
+    /// Gets the pack that uses this namespace
+    pub fn from_namespace(&self, namespace: &str) -> Option<&ContentPack> {
+        self.packs.iter().find(|pack| pack.namespace() == namespace)
+    }
+
+    /// Gets the pack an unlocalized name (such as `cosmos:stone`) belongs to
+    pub fn from_unlocalized_name(&self, unlocalized_name: &str) -> Option<&ContentPack> {
+        unlocalized_name
+            .split_once(':')
+            .and_then(|(namespace, _)| self.from_namespace(namespace))
+    }
+
+    /// The ids of every pack in load order, used to make sure the server & client have the same packs
+    pub fn ids(&self) -> Vec<ContentPackId> {
+        self.packs.iter().map(|pack| pack.id.clone()).collect()
+    }
+}
+
+fn load_content_packs(
+    mut packs: ResMut<ContentPacks>,
+    mut loading: ResMut<LoadingManager>,
+    mut end_writer: EventWriter<DoneLoadingEvent>,
+    mut start_writer: EventWriter<AddLoadingEvent>,
+) {
+    let id = loading.register_loader(&mut start_writer);
+
+    match ContentPacks::discover() {
+        Ok(discovered) => *packs = discovered,
+        Err(errors) => panic_with_errors("content packs", &errors),
+    }
+
+    for pack in packs.iter().skip(1) {
+        println!("Loaded content pack {} - {}", pack.name(), pack.id());
+    }
+
+    loading.finish_loading(id, &mut end_writer);
+}
+
+pub(super) fn register<T: States + Clone + Copy>(app: &mut App, pre_loading_state: T) {
+    app.insert_resource(ContentPacks::default())
+        .add_system(load_content_packs.in_schedule(OnEnter(pre_loading_state)));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn pack(namespace: &str, load_order: i32) -> ContentPack {
+        let value = serde_json::json!({
+            "namespace": namespace,
+            "name": namespace,
+            "version": "1.0.0",
+            "load_order": load_order,
+        });
+
+        let directory = Path::new("packs").join(namespace);
+
+        parse_manifest(&directory, &directory.join(MANIFEST_FILE), &value).unwrap()
+    }
+
+    #[test]
+    fn sorts_by_load_order() {
+        let packs = ContentPacks::new(
+            ContentPack::base("assets".into()),
+            vec![pack("zeta", 1), pack("beta", 5), pack("alpha", 1)],
+        )
+        .unwrap();
+
+        let namespaces = packs
+            .iter()
+            .map(|pack| pack.namespace())
+            .collect::<Vec<&str>>();
+
+        assert_eq!(namespaces, vec![BASE_NAMESPACE, "alpha", "zeta", "beta"]);
+    }
+
+    #[test]
+    fn rejects_bad_namespaces() {
+        let mut duplicate = pack("alpha", 2);
+        duplicate.directory = "packs/alpha_again".into();
+
+        let errors = ContentPacks::new(
+            ContentPack::base("assets".into()),
+            vec![pack("alpha", 1), duplicate],
+        )
+        .err()
+        .expect("Namespaces should conflict");
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].file, Path::new("packs/alpha_again/pack.json"));
+
+        let value = serde_json::json!({
+            "namespace": BASE_NAMESPACE,
+            "name": "Not Cosmos",
+            "version": "1.0.0",
+            "load_order": 0,
+        });
+
+        let error = parse_manifest(Path::new("packs/x"), Path::new("packs/x/pack.json"), &value)
+            .err()
+            .expect("Base namespace should be reserved");
+
+        assert_eq!(error.field.as_deref(), Some("namespace"));
+    }
+}
@@ -0,0 +1,10 @@
+//! Crafting turns items into other items, following a [`recipes::Recipe`]
+
+use bevy::prelude::{App, States};
+
+pub mod netty;
+pub mod recipes;
+
+pub(super) fn register<T: States + Clone + Copy>(app: &mut App, post_loading_state: T) {
+    recipes::register(app, post_loading_state);
+}
@@ -0,0 +1,46 @@
+//! The messages sent over [`NettyChannel::Crafting`](crate::netty::NettyChannel::Crafting)
+
+use bevy::prelude::{Component, Entity};
+use serde::{Deserialize, Serialize};
+
+use crate::structure::structure_block::StructureBlock;
+
+use super::recipes::Recipe;
+
+#[derive(Debug, Serialize, Deserialize, Component)]
+/// All the crafting messages the server sends
+pub enum CraftingServerMessages {
+    /// Every recipe on the server, using the server's item & block ids
+    Recipes {
+        /// Every recipe
+        recipes: Vec<Recipe>,
+    },
+    /// The craft was done, or queued at its station
+    CraftAccepted {
+        /// The server's id of the recipe
+        recipe_id: u16,
+    },
+    /// The craft couldn't be done
+    CraftRejected {
+        /// The server's id of the recipe
+        recipe_id: u16,
+        /// Why it was rejected
+        reason: String,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Component)]
+/// All the crafting messages a client sends
+pub enum CraftingClientMessages {
+    /// Asks for every recipe the server has
+    RequestRecipes,
+    /// The player wants to craft this recipe, using the items in their inventory
+    Craft {
+        /// The server's id of the recipe
+        recipe_id: u16,
+        /// The station to craft it at, which must be the container the player has open.
+        ///
+        /// None for recipes that are crafted by hand.
+        station: Option<(Entity, StructureBlock)>,
+    },
+}
@@ -0,0 +1,429 @@
+//! Every recipe is defined in the `content/recipes` files of a content pack.
+//!
+//! Recipes without a station are crafted by hand & are made right away. Recipes with a station can only be
+//! crafted at that block, and take `craft_time` seconds. Stations must be containers, since that's where what they make goes.
+//!
+//! An example recipe:
+//!
+//! ```json
+//! {
+//!     "unlocalized_name": "cosmos:ship_hull",
+//!     "inputs": [{ "item": "cosmos:stone", "quantity": 2 }],
+//!     "outputs": [{ "item": "cosmos:ship_hull", "quantity": 1 }],
+//!     "station": "cosmos:fabricator",
+//!     "craft_time": 2.0
+//! }
+//! ```
+
+use std::fmt;
+
+use bevy::prelude::{App, IntoSystemAppConfig, OnExit, Res, ResMut, States};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    block::{definitions::BlockDefinition, Block},
+    content::{self, ContentError, ContentObject, ContentPacks},
+    inventory::{itemstack::ItemStack, Inventory},
+    item::Item,
+    registry::{self, id_map::IdRemap, identifiable::Identifiable, Registry},
+};
+
+/// The kind of content recipes are
+const RECIPES_CONTENT: &str = "recipes";
+
+/// Every field a recipe can have
+const FIELDS: [&str; 5] = [
+    "unlocalized_name",
+    "inputs",
+    "outputs",
+    "station",
+    "craft_time",
+];
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RecipeItemDefinition {
+    item: String,
+    quantity: u16,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// An item & how many of it a recipe uses or makes
+pub struct RecipeItem {
+    /// The item's id
+    pub item_id: u16,
+    /// How many of the item
+    pub quantity: u16,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Why something couldn't be crafted
+pub enum CraftingError {
+    /// The inventory doesn't have every input the recipe needs
+    MissingInputs,
+    /// The inventory doesn't have room for everything the recipe makes
+    NoRoom,
+}
+
+impl fmt::Display for CraftingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingInputs => write!(f, "Missing the items needed"),
+            Self::NoRoom => write!(f, "Not enough room for the items made"),
+        }
+    }
+}
+
+impl std::error::Error for CraftingError {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Turns some items into other items
+pub struct Recipe {
+    id: u16,
+    unlocalized_name: String,
+
+    inputs: Vec<RecipeItem>,
+    outputs: Vec<RecipeItem>,
+    station: Option<u16>,
+    craft_time: f32,
+}
+
+impl Identifiable for Recipe {
+    fn id(&self) -> u16 {
+        self.id
+    }
+
+    fn set_numeric_id(&mut self, id: u16) {
+        self.id = id;
+    }
+
+    fn unlocalized_name(&self) -> &str {
+        &self.unlocalized_name
+    }
+}
+
+impl Recipe {
+    /// Creates a recipe. This still needs to be registered!
+    ///
+    /// * `station` The block this must be crafted at, or None if it's crafted by hand
+    /// * `craft_time` How many seconds this takes to craft at its station
+    pub fn new(
+        unlocalized_name: impl Into<String>,
+        inputs: Vec<RecipeItem>,
+        outputs: Vec<RecipeItem>,
+        station: Option<&Block>,
+        craft_time: f32,
+    ) -> Self {
+        Self {
+            id: 0,
+            unlocalized_name: unlocalized_name.into(),
+            inputs,
+            outputs,
+            station: station.map(|block| block.id()),
+            craft_time,
+        }
+    }
+
+    /// The items this uses up
+    pub fn inputs(&self) -> &[RecipeItem] {
+        &self.inputs
+    }
+
+    /// The items this makes
+    pub fn outputs(&self) -> &[RecipeItem] {
+        &self.outputs
+    }
+
+    /// The id of the block this must be crafted at, or None if it's crafted by hand
+    pub fn station(&self) -> Option<u16> {
+        self.station
+    }
+
+    /// How many seconds this takes to craft at its station
+    pub fn craft_time(&self) -> f32 {
+        self.craft_time
+    }
+
+    /// Checks if the inventory has every input this recipe needs
+    pub fn has_inputs(&self, inventory: &Inventory) -> bool {
+        self.take_inputs(&mut inventory.clone()).is_ok()
+    }
+
+    /// Removes every input this recipe needs from the inventory.
+    ///
+    /// If any are missing, nothing is removed.
+    pub fn take_inputs(&self, inventory: &mut Inventory) -> Result<(), CraftingError> {
+        let mut after = inventory.clone();
+
+        for input in self.inputs.iter() {
+            if after.take_item(input.item_id, input.quantity) != 0 {
+                return Err(CraftingError::MissingInputs);
+            }
+        }
+
+        *inventory = after;
+
+        Ok(())
+    }
+
+    /// Adds everything this recipe makes to the inventory.
+    ///
+    /// If it doesn't all fit, nothing is added.
+    pub fn give_outputs(
+        &self,
+        inventory: &mut Inventory,
+        items: &Registry<Item>,
+    ) -> Result<(), CraftingError> {
+        let mut after = inventory.clone();
+
+        for output in self.outputs.iter() {
+            let item = items.from_numeric_id(output.item_id);
+            let mut remaining = output.quantity;
+
+            while remaining > 0 {
+                let quantity = remaining.min(item.max_stack_size());
+                remaining -= quantity;
+
+                if after
+                    .insert_itemstack(ItemStack::with_quantity(item, quantity))
+                    .is_some()
+                {
+                    return Err(CraftingError::NoRoom);
+                }
+            }
+        }
+
+        *inventory = after;
+
+        Ok(())
+    }
+
+    /// Turns the inputs in this inventory into the outputs right away.
+    ///
+    /// If anything goes wrong, the inventory is left unchanged.
+    pub fn craft(
+        &self,
+        inventory: &mut Inventory,
+        items: &Registry<Item>,
+    ) -> Result<(), CraftingError> {
+        let mut after = inventory.clone();
+
+        self.take_inputs(&mut after)?;
+        self.give_outputs(&mut after, items)?;
+
+        *inventory = after;
+
+        Ok(())
+    }
+
+    /// Translates every item id in this recipe from the ids of another item registry into the current ones
+    pub fn remap_item_ids(&mut self, remap: &IdRemap) {
+        for recipe_item in self.inputs.iter_mut().chain(self.outputs.iter_mut()) {
+            recipe_item.item_id = remap.get(recipe_item.item_id);
+        }
+    }
+
+    /// Translates the station's block id from the ids of another block registry into the current ones
+    pub fn remap_block_ids(&mut self, remap: &IdRemap) {
+        if let Some(station) = self.station.as_mut() {
+            *station = remap.get(*station);
+        }
+    }
+}
+
+fn parse_recipe_items(
+    object: &ContentObject,
+    field: &str,
+    items: &Registry<Item>,
+) -> Result<Vec<RecipeItem>, ContentError> {
+    let definitions = object.required::<Vec<RecipeItemDefinition>>(field)?;
+
+    if definitions.is_empty() {
+        return Err(object.error(field, "Must have at least 1 item"));
+    }
+
+    definitions
+        .into_iter()
+        .enumerate()
+        .map(|(i, definition)| {
+            let Some(item) = items.from_id(&definition.item) else {
+                return Err(object.error(
+                    &format!("{field}[{i}].item"),
+                    format!("There is no item named {}", definition.item),
+                ));
+            };
+
+            if definition.quantity == 0 {
+                return Err(object.error(
+                    &format!("{field}[{i}].quantity"),
+                    "Must be more than 0, but was 0",
+                ));
+            }
+
+            Ok(RecipeItem {
+                item_id: item.id(),
+                quantity: definition.quantity,
+            })
+        })
+        .collect()
+}
+
+fn parse_recipe(
+    object: &ContentObject,
+    items: &Registry<Item>,
+    blocks: &Registry<Block>,
+    block_definitions: &Registry<BlockDefinition>,
+) -> Result<Recipe, ContentError> {
+    object.deny_unknown_fields(&FIELDS)?;
+
+    let unlocalized_name = object.required::<String>("unlocalized_name")?;
+
+    let inputs = parse_recipe_items(object, "inputs", items)?;
+    let outputs = parse_recipe_items(object, "outputs", items)?;
+
+    let station = match object.optional::<String>("station")? {
+        Some(name) => {
+            let Some(block) = blocks.from_id(&name) else {
+                return Err(object.error("station", format!("There is no block named {name}")));
+            };
+
+            let is_container = block_definitions
+                .from_id(&name)
+                .map(|definition| definition.container.is_some())
+                .unwrap_or(false);
+
+            if !is_container {
+                return Err(object.error(
+                    "station",
+                    format!("{name} must be a container to be a crafting station"),
+                ));
+            }
+
+            Some(block)
+        }
+        None => None,
+    };
+
+    let craft_time = object.optional::<f32>("craft_time")?.unwrap_or(0.0);
+
+    if !craft_time.is_finite() || craft_time < 0.0 {
+        return Err(object.error(
+            "craft_time",
+            format!("Must be 0 or more, but was {craft_time}"),
+        ));
+    }
+
+    Ok(Recipe::new(
+        unlocalized_name,
+        inputs,
+        outputs,
+        station,
+        craft_time,
+    ))
+}
+
+fn load_recipes(
+    mut recipes: ResMut<Registry<Recipe>>,
+    packs: Res<ContentPacks>,
+    items: Res<Registry<Item>>,
+    blocks: Res<Registry<Block>>,
+    block_definitions: Res<Registry<BlockDefinition>>,
+) {
+    match content::load_definitions(
+        &packs,
+        RECIPES_CONTENT,
+        |name| recipes.from_id(name).is_some(),
+        |object| parse_recipe(object, &items, &blocks, &block_definitions),
+    ) {
+        Ok(loaded) => {
+            for recipe in loaded {
+                recipes.register(recipe);
+            }
+        }
+        Err(errors) => content::panic_with_errors("recipes", &errors),
+    }
+}
+
+pub(super) fn register<T: States + Clone + Copy>(app: &mut App, post_loading_state: T) {
+    registry::create_registry::<Recipe>(app);
+
+    // Every item (including the ones made for blocks) is registered by the time post loading is over
+    app.add_system(load_recipes.in_schedule(OnExit(post_loading_state)));
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use super::*;
+
+    fn registered_item(items: &mut Registry<Item>, name: &str, max_stack_size: u16) -> u16 {
+        items.register(Item::new(name.into(), max_stack_size));
+        items.from_id(name).unwrap().id()
+    }
+
+    #[test]
+    fn crafting_changes_nothing_unless_it_all_works() {
+        let mut items = Registry::<Item>::new();
+        let stone = registered_item(&mut items, "test:stone", 10);
+        let hull = registered_item(&mut items, "test:hull", 10);
+
+        let recipe = Recipe::new(
+            "test:hull",
+            vec![RecipeItem {
+                item_id: stone,
+                quantity: 4,
+            }],
+            vec![RecipeItem {
+                item_id: hull,
+                quantity: 15,
+            }],
+            None,
+            0.0,
+        );
+
+        let mut inventory = Inventory::new(2);
+        inventory.insert(items.from_numeric_id(stone), 6);
+
+        // 15 hulls need 2 slots, but 1 is still taken up by stone
+        assert_eq!(
+            recipe.craft(&mut inventory, &items),
+            Err(CraftingError::NoRoom)
+        );
+        assert_eq!(inventory.quantity_of_id(stone), 6);
+
+        inventory.take_item(stone, 2);
+        assert_eq!(recipe.craft(&mut inventory, &items), Ok(()));
+        assert_eq!(inventory.quantity_of_id(stone), 0);
+        assert_eq!(inventory.quantity_of_id(hull), 15);
+
+        assert_eq!(
+            recipe.craft(&mut inventory, &items),
+            Err(CraftingError::MissingInputs)
+        );
+        assert_eq!(inventory.quantity_of_id(hull), 15);
+    }
+
+    #[test]
+    fn recipe_errors_name_the_field() {
+        let mut items = Registry::<Item>::new();
+        registered_item(&mut items, "test:stone", 10);
+
+        let blocks = Registry::<Block>::new();
+        let block_definitions = Registry::<BlockDefinition>::new();
+
+        let value = serde_json::json!({
+            "unlocalized_name": "test:thing",
+            "inputs": [{ "item": "test:stone", "quantity": 1 }],
+            "outputs": [{ "item": "test:missing", "quantity": 1 }]
+        });
+
+        let object = ContentObject::new(Path::new("recipes/test.json"), "[0]".into(), &value)
+            .expect("This is an object");
+
+        let error = parse_recipe(&object, &items, &blocks, &block_definitions)
+            .expect_err("test:missing isn't an item");
+
+        assert_eq!(error.field.as_deref(), Some("[0].outputs[0].item"));
+    }
+}
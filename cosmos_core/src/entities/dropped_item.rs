@@ -0,0 +1,39 @@
+//! Items that are floating around in the world instead of being in an inventory
+
+use bevy::{
+    prelude::{App, Component},
+    reflect::{FromReflect, Reflect},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::inventory::itemstack::ItemStack;
+
+/// How long each side of a dropped item's cube is
+pub const DROPPED_ITEM_SIZE: f32 = 0.25;
+
+#[derive(Component, Debug, Serialize, Deserialize, Reflect, FromReflect)]
+/// An item that is floating around in the world, waiting to be picked up
+pub struct DroppedItem {
+    item_stack: ItemStack,
+}
+
+impl DroppedItem {
+    /// Creates a dropped item that holds this stack
+    pub fn new(item_stack: ItemStack) -> Self {
+        Self { item_stack }
+    }
+
+    /// The items this holds
+    pub fn item_stack(&self) -> &ItemStack {
+        &self.item_stack
+    }
+
+    /// The items this holds
+    pub fn item_stack_mut(&mut self) -> &mut ItemStack {
+        &mut self.item_stack
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.register_type::<DroppedItem>();
+}
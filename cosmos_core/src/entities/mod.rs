@@ -4,8 +4,10 @@
 
 use bevy::prelude::App;
 
+pub mod dropped_item;
 pub mod player;
 
 pub(super) fn register(app: &mut App) {
+    dropped_item::register(app);
     player::register(app);
 }
@@ -1,6 +1,6 @@
 //! Events that are related to blocks
 
-use crate::block::BlockFace;
+use crate::block::BlockRotation;
 use crate::structure::structure_block::StructureBlock;
 use bevy::prelude::App;
 use bevy::prelude::Entity;
@@ -19,11 +19,54 @@ pub struct BlockChangedEvent {
     /// The block that is there now/will be there
     pub new_block: u16,
     /// Old block's rotation
-    pub old_block_up: BlockFace,
+    pub old_block_rotation: BlockRotation,
     /// New block's rotation
-    pub new_block_up: BlockFace,
+    pub new_block_rotation: BlockRotation,
+}
+
+#[derive(Debug, Clone, Copy)]
+/// A single block's change within a `BlockChangedBatchEvent`
+pub struct BlockChange {
+    /// The block that was changed
+    pub block: StructureBlock,
+    /// The block that was there before
+    pub old_block: u16,
+    /// The block that is there now
+    pub new_block: u16,
+    /// Old block's rotation
+    pub old_block_rotation: BlockRotation,
+    /// New block's rotation
+    pub new_block_rotation: BlockRotation,
+}
+
+#[derive(Debug)]
+/// Sent when many blocks are changed at once via `Structure::apply_block_edits`.
+///
+/// One of these is sent for every chunk that had blocks changed, and the blocks have already been updated.
+pub struct BlockChangedBatchEvent {
+    /// The structure entity
+    pub structure_entity: Entity,
+    /// The coordinates of the chunk every change is in
+    pub chunk: (usize, usize, usize),
+    /// Every block that was changed in this chunk
+    pub changes: Vec<BlockChange>,
+}
+
+#[derive(Debug)]
+/// Sent when the data stored on a block is changed or removed
+///
+/// The data has already been updated, and can be read via `Structure::block_data`.
+pub struct BlockDataChangedEvent {
+    /// The structure entity
+    pub structure_entity: Entity,
+    /// The block whose data was changed
+    pub block: StructureBlock,
+    /// The id of the data that was changed
+    pub data_id: String,
 }
 
 pub(super) fn register(app: &mut App) {
-    app.add_event::<BlockChangedEvent>();
+    app.add_event::<BlockChangedEvent>()
+        .add_event::<BlockChangedBatchEvent>()
+        .add_event::<BlockDataChangedEvent>();
 }
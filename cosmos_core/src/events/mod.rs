@@ -9,6 +9,7 @@ pub mod structure;
 pub mod wrappers;
 
 pub(super) fn register<T: States + Clone + Copy>(app: &mut App, playing_state: T) {
+    wrappers::register(app);
     block_events::register(app);
     structure::register(app, playing_state);
 }
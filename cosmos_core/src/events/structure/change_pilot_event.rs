@@ -2,7 +2,11 @@
 
 use bevy::prelude::{App, Entity};
 
+use crate::events::wrappers::cancellable_event::CancellableEvent;
+
 /// Sent when a pilot is changed
+///
+/// This is sent as a [`CancellableEvent`], so any system may stop the pilot from being changed.
 pub struct ChangePilotEvent {
     /// The entity of the structure
     pub structure_entity: Entity,
@@ -11,5 +15,5 @@ pub struct ChangePilotEvent {
 }
 
 pub(super) fn register(app: &mut App) {
-    app.add_event::<ChangePilotEvent>();
+    app.add_event::<CancellableEvent<ChangePilotEvent>>();
 }
@@ -7,6 +7,7 @@ use bevy_rapier3d::prelude::{RigidBody, Sensor};
 
 use crate::entities::player::Player;
 use crate::events::structure::change_pilot_event::ChangePilotEvent;
+use crate::events::wrappers::cancellable_event::{CancellableEvent, CancellableEventSet};
 use crate::physics::location::{handle_child_syncing, Location};
 use crate::structure::ship::pilot::Pilot;
 
@@ -15,11 +16,11 @@ struct PilotStartingDelta(Vec3);
 
 fn event_listener(
     mut commands: Commands,
-    mut event_reader: EventReader<ChangePilotEvent>,
+    mut event_reader: EventReader<CancellableEvent<ChangePilotEvent>>,
     location_query: Query<&Location>,
     pilot_query: Query<&Pilot>,
 ) {
-    for ev in event_reader.iter() {
+    for ev in event_reader.iter().filter(|ev| !ev.is_cancelled()) {
         // Make sure there is no other player thinking they are the pilot of this ship
         if let Ok(prev_pilot) = pilot_query.get(ev.structure_entity) {
             commands
@@ -144,6 +145,7 @@ pub(super) fn register<T: States + Clone + Copy>(app: &mut App, playing_state: T
         verify_pilot_exists.in_set(OnUpdate(playing_state)),
         event_listener
             .in_set(OnUpdate(playing_state))
+            .in_set(CancellableEventSet::Apply)
             .after(handle_child_syncing)
             .after(verify_pilot_exists),
     ))
@@ -1,62 +1,102 @@
-// use bevy::{
-//     prelude::Resource,
-//     reflect::{FromReflect, Reflect},
-//     utils::HashSet,
-// };
-
-// pub struct CancellableEvent<T> {
-//     pub event: T,
-//     id: u64,
-// }
-
-// impl<T> CancellableEvent<T> {
-//     pub fn new(event: T, event_manager: &mut CancellableEventManager) -> Self {
-//         Self {
-//             event,
-//             id: event_manager.new_event_entry(),
-//         }
-//     }
-
-//     pub fn cancel(&self, event_manager: &mut CancellableEventManager) {
-//         event_manager.cancel_event(self.id);
-//     }
-
-//     pub fn is_active(&self, event_manager: &mut CancellableEventManager) -> bool {
-//         event_manager.is_event_active(self.id)
-//     }
-
-//     pub fn unwrap_and_send()
-// }
-
-// #[derive(Resource, Reflect, FromReflect, Debug, Default)]
-// pub struct CancellableEventManager {
-//     active_events: HashSet<u64>,
-//     next_id: u64,
-// }
-
-// impl CancellableEventManager {
-//     fn new_event_entry(&mut self) -> u64 {
-//         self.next_id += 1;
-
-//         self.active_events.insert(self.next_id);
-
-//         self.next_id
-//     }
-
-//     /// Marks an event as inactive
-//     pub fn finish_event(&mut self, id: u64) {
-//         // It's fine if this removes nothing
-//         self.active_events.remove(&id);
-//     }
-
-//     /// Marks an event as inactive
-//     pub fn cancel_event(&mut self, id: u64) {
-//         // It's fine if this removes nothing
-//         self.active_events.remove(&id);
-//     }
-
-//     /// Returns true if an event with this id exists & has not been cancelled.
-//     pub fn is_event_active(&mut self, id: u64) -> bool {
-//         self.active_events.contains(&id)
-//     }
-// }
+//! Events that can be cancelled before they are carried out.
+//!
+//! Send a [`CancellableEvent`] instead of a normal event, and any system can veto it before it happens.
+//! Systems that use these events must be in the right [`CancellableEventSet`]:
+//!
+//! 1. [`CancellableEventSet::Send`] - Systems that send cancellable events.
+//! 2. [`CancellableEventSet::Cancel`] - Systems that decide if an event should be cancelled, such as protection or permission checks.
+//!    These don't depend on each other, so order them yourself if one needs to see another's decision.
+//! 3. [`CancellableEventSet::Apply`] - Systems that carry out every event that wasn't cancelled.
+//!
+//! Events sent outside the [`CancellableEventSet::Send`] set may be carried out before every system has had a chance to cancel them.
+
+use std::{
+    ops::Deref,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use bevy::prelude::{App, IntoSystemSetConfigs, SystemSet};
+
+#[derive(Debug)]
+/// An event that can be cancelled by any system in the [`CancellableEventSet::Cancel`] set.
+///
+/// This derefs into the event it wraps.
+pub struct CancellableEvent<T> {
+    event: T,
+    cancelled: AtomicBool,
+}
+
+impl<T> CancellableEvent<T> {
+    /// Wraps this event so it can be cancelled
+    pub fn new(event: T) -> Self {
+        Self {
+            event,
+            cancelled: AtomicBool::new(false),
+        }
+    }
+
+    /// Stops this event from being carried out.
+    ///
+    /// Once cancelled, an event cannot be un-cancelled.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true if any system has cancelled this event
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Returns the wrapped event
+    pub fn event(&self) -> &T {
+        &self.event
+    }
+}
+
+impl<T> Deref for CancellableEvent<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.event
+    }
+}
+
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The order systems that deal with [`CancellableEvent`]s run in
+pub enum CancellableEventSet {
+    /// Systems that send cancellable events
+    Send,
+    /// Systems that may cancel events before they are carried out
+    Cancel,
+    /// Systems that carry out every event that wasn't cancelled
+    Apply,
+}
+
+pub(super) fn register(app: &mut App) {
+    app.configure_sets(
+        (
+            CancellableEventSet::Send,
+            CancellableEventSet::Cancel,
+            CancellableEventSet::Apply,
+        )
+            .chain(),
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cancelling_is_permanent() {
+        let event = CancellableEvent::new(5);
+
+        assert!(!event.is_cancelled());
+
+        event.cancel();
+        event.cancel();
+
+        assert!(event.is_cancelled());
+        assert_eq!(*event, 5);
+    }
+}
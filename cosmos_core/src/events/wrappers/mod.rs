@@ -1,3 +1,9 @@
-//! todo
+//! Wrappers that add extra behavior to events
 
-// pub mod cancellable_event;
+use bevy::prelude::App;
+
+pub mod cancellable_event;
+
+pub(super) fn register(app: &mut App) {
+    cancellable_event::register(app);
+}
@@ -0,0 +1,72 @@
+//! Container blocks, such as cargo holds & chests, each hold their own [`Inventory`].
+//!
+//! A container's inventory is stored as block data on the container block, so it is saved with its structure
+//! and removed when the block is. It is [`PrivateBlockData`], so only players who open the container are sent it.
+
+use bevy::{
+    prelude::{App, IntoSystemAppConfig, OnEnter, Res, ResMut, Resource, States},
+    reflect::{FromReflect, Reflect},
+    utils::HashMap,
+};
+use serde::Deserialize;
+
+use crate::{
+    block::{definitions::BlockDefinition, Block},
+    registry::{identifiable::Identifiable, Registry},
+    structure::block_data::{BlockDataType, PrivateBlockData},
+};
+
+use super::Inventory;
+
+impl BlockDataType for Inventory {
+    const DATA_ID: &'static str = "cosmos:inventory";
+}
+
+#[derive(Debug, Default, FromReflect, Reflect, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+/// Every block that stores items should have this property
+pub struct ContainerProperty {
+    /// How many inventory slots this container has
+    pub slots: usize,
+}
+
+#[derive(Default, Resource)]
+/// All the container blocks - register them here.
+pub struct ContainerBlocks {
+    blocks: HashMap<u16, ContainerProperty>,
+}
+
+impl ContainerBlocks {
+    /// Inserts a block with a property
+    pub fn insert(&mut self, block: &Block, container_property: ContainerProperty) {
+        self.blocks.insert(block.id(), container_property);
+    }
+
+    /// Gets the property for that block if it has one
+    pub fn get(&self, block: &Block) -> Option<&ContainerProperty> {
+        self.blocks.get(&block.id())
+    }
+}
+
+fn register_container_blocks(
+    blocks: Res<Registry<Block>>,
+    definitions: Res<Registry<BlockDefinition>>,
+    mut containers: ResMut<ContainerBlocks>,
+    mut private_data: ResMut<PrivateBlockData>,
+) {
+    for definition in definitions.iter() {
+        if let (Some(container_property), Some(block)) = (
+            definition.container,
+            blocks.from_id(definition.unlocalized_name()),
+        ) {
+            containers.insert(block, container_property);
+        }
+    }
+
+    private_data.make_private::<Inventory>();
+}
+
+pub(super) fn register<T: States + Clone + Copy>(app: &mut App, post_loading_state: T) {
+    app.insert_resource(ContainerBlocks::default())
+        .add_system(register_container_blocks.in_schedule(OnEnter(post_loading_state)));
+}
@@ -1,4 +1,6 @@
 //! An ItemStack represents an item & the quantity of that item.
+//!
+//! A stack can also carry data about its items, such as how worn out they are. Stacks only merge when their data is identical.
 
 use bevy::{
     prelude::App,
@@ -6,24 +8,56 @@ use bevy::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::{item::Item, registry::identifiable::Identifiable};
+use crate::{
+    item::Item,
+    registry::{id_map::IdRemap, identifiable::Identifiable},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Reflect, FromReflect)]
+/// Extra information about every item in a stack
+pub struct ItemStackData {
+    /// How many more uses the items have before they break
+    pub durability: Option<u32>,
+    /// A name given to the items, shown instead of the item's own name
+    pub custom_name: Option<String>,
+    /// How much energy the items are storing
+    pub stored_energy: Option<f32>,
+}
+
+impl ItemStackData {
+    /// The data a brand new one of that item has, or None if it has nothing to store
+    pub fn for_item(item: &Item) -> Option<Self> {
+        Self {
+            durability: item.durability(),
+            custom_name: None,
+            stored_energy: item.energy_capacity(),
+        }
+        .into_option()
+    }
+
+    /// Checks if nothing is stored in this
+    pub fn is_empty(&self) -> bool {
+        self.durability.is_none() && self.custom_name.is_none() && self.stored_energy.is_none()
+    }
 
-#[derive(Serialize, Deserialize, Debug, Reflect, FromReflect)]
+    fn into_option(self) -> Option<Self> {
+        (!self.is_empty()).then_some(self)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Reflect, FromReflect)]
 /// An item & the quantity of that item
 pub struct ItemStack {
     item_id: u16,
     quantity: u16,
     max_stack_size: u16,
+    data: Option<ItemStackData>,
 }
 
 impl ItemStack {
     /// Creates an ItemStack of that item with an initial quantity of 0.
     pub fn new(item: &Item) -> Self {
-        Self {
-            item_id: item.id(),
-            max_stack_size: item.max_stack_size(),
-            quantity: 0,
-        }
+        Self::with_quantity(item, 0)
     }
 
     /// Creates an ItemStack of that item with the given initial quantity
@@ -32,15 +66,58 @@ impl ItemStack {
             item_id: item.id(),
             max_stack_size: item.max_stack_size(),
             quantity,
+            data: ItemStackData::for_item(item),
         }
     }
 
+    /// Replaces the data this stack has
+    pub fn with_data(mut self, data: Option<ItemStackData>) -> Self {
+        self.set_data(data);
+        self
+    }
+
     #[inline]
     /// Gets the item's id
     pub fn item_id(&self) -> u16 {
         self.item_id
     }
 
+    /// Translates this stack's item id from the ids of another item registry into the current ones
+    pub fn remap_item_id(&mut self, remap: &IdRemap) {
+        self.item_id = remap.get(self.item_id);
+    }
+
+    #[inline]
+    /// Gets the most of this item that can be in this stack
+    pub fn max_stack_size(&self) -> u16 {
+        self.max_stack_size
+    }
+
+    #[inline]
+    /// Gets the data about this stack's items, if they have any
+    pub fn data(&self) -> Option<&ItemStackData> {
+        self.data.as_ref()
+    }
+
+    /// Replaces the data this stack has. Empty data is treated as no data.
+    pub fn set_data(&mut self, data: Option<ItemStackData>) {
+        self.data = data.and_then(ItemStackData::into_option);
+    }
+
+    #[inline]
+    /// Gets the name given to this stack, if it was given one
+    pub fn custom_name(&self) -> Option<&str> {
+        self.data
+            .as_ref()
+            .and_then(|data| data.custom_name.as_deref())
+    }
+
+    #[inline]
+    /// Checks if this stack & the other are the same item with the same data, meaning they can be merged into one stack
+    pub fn can_stack_with(&self, other: &ItemStack) -> bool {
+        self.item_id == other.item_id && self.data == other.data
+    }
+
     #[inline]
     /// Gets the quantity
     pub fn quantity(&self) -> u16 {
@@ -91,5 +168,40 @@ impl ItemStack {
 }
 
 pub(super) fn register(app: &mut App) {
-    app.register_type::<ItemStack>();
+    app.register_type::<ItemStackData>()
+        .register_type::<ItemStack>();
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{inventory::Inventory, registry::Registry};
+
+    use super::*;
+
+    #[test]
+    fn only_identical_data_stacks() {
+        let mut items = Registry::<Item>::new();
+        items.register(Item::new("test:fuel_cell".into(), 10).with_energy_capacity(100.0));
+        let fuel_cell = items.from_id("test:fuel_cell").unwrap();
+
+        let full = ItemStack::with_quantity(fuel_cell, 2);
+
+        let mut drained = ItemStack::with_quantity(fuel_cell, 1);
+        drained.set_data(Some(ItemStackData {
+            stored_energy: Some(40.0),
+            ..Default::default()
+        }));
+
+        assert!(!full.can_stack_with(&drained));
+
+        let mut inventory = Inventory::new(3);
+        assert!(inventory.insert_itemstack(full).is_none());
+        assert!(inventory.insert_itemstack(drained).is_none());
+        assert_eq!(inventory.insert(fuel_cell, 3), 0);
+
+        // The new cells join the full ones, while the drained cell keeps its own slot
+        assert_eq!(inventory.itemstack_at(0).map(|is| is.quantity()), Some(5));
+        assert_eq!(inventory.itemstack_at(1).map(|is| is.quantity()), Some(1));
+        assert!(inventory.itemstack_at(2).is_none());
+    }
 }
@@ -1,18 +1,24 @@
 //! An inventory consists of a list of ItemStacks
 //!
-//! These ItemStacks can be modified freely. An inventory is owned by an entity.
+//! These ItemStacks can be modified freely. An inventory is owned by an entity, or by a container block.
 
 use bevy::{
-    prelude::{App, Component},
+    prelude::{App, Component, States},
     reflect::{FromReflect, Reflect},
 };
 use serde::{Deserialize, Serialize};
 
-use crate::{item::Item, registry::identifiable::Identifiable};
+use crate::{
+    item::Item,
+    registry::{id_map::IdRemap, identifiable::Identifiable},
+};
 
 use self::itemstack::ItemStack;
 
+pub mod container;
 pub mod itemstack;
+pub mod netty;
+pub mod transaction;
 
 // TODO
 // pub enum InventoryType {
@@ -20,7 +26,9 @@ pub mod itemstack;
 //     NormalInventory, // These inventories are organizable by the player
 // }
 
-#[derive(Default, Component, Serialize, Deserialize, Debug, Reflect, FromReflect)]
+#[derive(
+    Default, Component, Serialize, Deserialize, Debug, Clone, PartialEq, Reflect, FromReflect,
+)]
 /// A collection of ItemStacks, organized into slots
 pub struct Inventory {
     items: Vec<Option<ItemStack>>,
@@ -54,11 +62,13 @@ impl Inventory {
     pub fn insert(&mut self, item: &Item, mut quantity: u16) -> u16 {
         // Search for existing stacks, if none found that make new one(s)
 
+        let new_stack = ItemStack::new(item);
+
         for is in &mut self
             .items
             .iter_mut()
             .flatten()
-            .filter(|x| x.item_id() == item.id())
+            .filter(|x| x.can_stack_with(&new_stack))
         {
             quantity = is.increase_quantity(quantity);
 
@@ -87,6 +97,46 @@ impl Inventory {
         quantity
     }
 
+    /// Inserts this stack wherever it fits, merging it with stacks of the same item first.
+    ///
+    /// Returns whatever could not fit, if anything.
+    pub fn insert_itemstack(&mut self, mut item_stack: ItemStack) -> Option<ItemStack> {
+        for is in self
+            .items
+            .iter_mut()
+            .flatten()
+            .filter(|x| x.can_stack_with(&item_stack))
+        {
+            let leftover = is.increase_quantity(item_stack.quantity());
+            item_stack.decrease_quantity(item_stack.quantity() - leftover);
+
+            if item_stack.is_empty() {
+                return None;
+            }
+        }
+
+        if let Some(slot) = self.items.iter_mut().find(|x| x.is_none()) {
+            *slot = Some(item_stack);
+
+            return None;
+        }
+
+        Some(item_stack)
+    }
+
+    /// Gets every slot whose contents differ from that slot in the other inventory, along with what is in that slot in this inventory.
+    ///
+    /// Both inventories should have the same number of slots.
+    pub fn changed_slots(&self, other: &Inventory) -> Vec<(u32, Option<ItemStack>)> {
+        self.items
+            .iter()
+            .zip(other.items.iter())
+            .enumerate()
+            .filter(|(_, (a, b))| a != b)
+            .map(|(slot, (a, _))| (slot as u32, a.clone()))
+            .collect()
+    }
+
     /// Returns the ItemStack at that slot
     pub fn itemstack_at(&self, slot: usize) -> Option<&ItemStack> {
         self.items[slot].as_ref()
@@ -127,10 +177,10 @@ impl Inventory {
     }
 
     /// Inserts the items & quantity at that slot. Returns the number of items left over, or the full
-    /// quantity of items if that slot doesn't represent that item.
+    /// quantity of items if what is in that slot can't stack with that item.
     pub fn insert_at(&mut self, slot: usize, item: &Item, quantity: u16) -> u16 {
         if let Some(slot) = &mut self.items[slot] {
-            if slot.item_id() != item.id() {
+            if !slot.can_stack_with(&ItemStack::new(item)) {
                 quantity
             } else {
                 slot.increase_quantity(quantity)
@@ -144,21 +194,55 @@ impl Inventory {
 
     /// Calculates the number of that specific item in this inventory.
     pub fn quantity_of(&self, item: &Item) -> usize {
+        self.quantity_of_id(item.id())
+    }
+
+    /// Calculates the number of the item with this id in this inventory.
+    pub fn quantity_of_id(&self, item_id: u16) -> usize {
         self.items
             .iter()
             .filter_map(|x| x.as_ref())
-            .filter(|x| x.item_id() == item.id())
+            .filter(|x| x.item_id() == item_id)
             .map(|x| x.quantity() as usize)
             .sum()
     }
 
+    /// Removes up to that quantity of the item with this id, from whichever slots have it.
+    ///
+    /// Returns the quantity that could not be removed.
+    pub fn take_item(&mut self, item_id: u16, mut quantity: u16) -> u16 {
+        for slot in 0..self.items.len() {
+            if quantity == 0 {
+                break;
+            }
+
+            if self.items[slot]
+                .as_ref()
+                .map(|is| is.item_id() == item_id)
+                .unwrap_or(false)
+            {
+                quantity = self.decrease_quantity_at(slot, quantity);
+            }
+        }
+
+        quantity
+    }
+
+    /// Translates every item id in this inventory from the ids of another item registry into the current ones
+    pub fn remap_item_ids(&mut self, remap: &IdRemap) {
+        for item_stack in self.items.iter_mut().flatten() {
+            item_stack.remap_item_id(remap);
+        }
+    }
+
     /// Iterates over every slot in the inventory.
     pub fn iter(&self) -> std::slice::Iter<'_, std::option::Option<ItemStack>> {
         self.items.iter()
     }
 }
 
-pub(super) fn register(app: &mut App) {
+pub(super) fn register<T: States + Clone + Copy>(app: &mut App, post_loading_state: T) {
     itemstack::register(app);
+    container::register(app, post_loading_state);
     app.register_type::<Inventory>();
 }
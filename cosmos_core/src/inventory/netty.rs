@@ -0,0 +1,72 @@
+//! The messages sent over [`NettyChannel::Inventory`](crate::netty::NettyChannel::Inventory) to keep inventories in sync
+
+use bevy::prelude::{Component, Entity};
+use serde::{Deserialize, Serialize};
+
+use crate::structure::structure_block::StructureBlock;
+
+use super::{
+    itemstack::ItemStack,
+    transaction::{InventoryIdentifier, InventoryTransaction},
+    Inventory,
+};
+
+#[derive(Debug, Serialize, Deserialize, Component)]
+/// All the inventory messages the server sends
+pub enum InventoryServerMessages {
+    /// The player has opened this container, or its contents changed while they had it open
+    ContainerInventory {
+        /// The structure the container is on
+        structure_entity: Entity,
+        /// The container block
+        block: StructureBlock,
+        /// The container's [`Inventory`](super::Inventory) serialized with `cosmos_encoder`
+        serialized_inventory: Vec<u8>,
+    },
+    /// The container the player had open was closed, such as by it being broken or them moving too far away
+    ContainerClosed {
+        /// The structure the container is on
+        structure_entity: Entity,
+        /// The container block
+        block: StructureBlock,
+    },
+    /// Some slots of an inventory were changed by a transaction.
+    ///
+    /// Unlike `EntityInventory`, this only contains the slots that changed
+    SlotsChanged {
+        /// The inventory that changed
+        inventory: InventoryIdentifier,
+        /// Every slot that changed, and what is in it now
+        slots: Vec<(u32, Option<ItemStack>)>,
+    },
+    /// The server applied this transaction. Any changes it made have already been sent as `SlotsChanged`
+    TransactionAccepted {
+        /// The id the client gave the transaction
+        id: u32,
+    },
+    /// The server refused to apply this transaction, so the client should undo it
+    TransactionRejected {
+        /// The id the client gave the transaction
+        id: u32,
+        /// Why it was rejected
+        reason: String,
+        /// The current contents of every inventory the transaction used that the player can access.
+        ///
+        /// The client's copies may have changed since the transaction was sent, so these replace them entirely.
+        inventories: Vec<(InventoryIdentifier, Inventory)>,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Component)]
+/// All the inventory messages a client sends
+pub enum InventoryClientMessages {
+    /// The player closed whatever container they had open
+    CloseContainer,
+    /// The player wants to make this change to their inventory or the container they have open
+    Transaction {
+        /// Chosen by the client so it knows which transaction the server's reply is for
+        id: u32,
+        /// The change they want to make
+        transaction: InventoryTransaction,
+    },
+}
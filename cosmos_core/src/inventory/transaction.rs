@@ -0,0 +1,437 @@
+//! Inventory transactions are every way a player can rearrange items, either within one inventory or between two.
+//!
+//! The client applies a transaction as soon as the player makes it, then sends it to the server. The server
+//! checks that the player is allowed to make it, applies it for real, and tells the client if it was accepted.
+
+use std::fmt;
+
+use bevy::prelude::Entity;
+use serde::{Deserialize, Serialize};
+
+use crate::structure::structure_block::StructureBlock;
+
+use super::{itemstack::ItemStack, Inventory};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// Which inventory something refers to
+pub enum InventoryIdentifier {
+    /// The inventory component on this entity, such as a player's
+    Entity(Entity),
+    /// The inventory of a container block
+    Container {
+        /// The structure the container is on
+        structure_entity: Entity,
+        /// The container block
+        block: StructureBlock,
+    },
+}
+
+impl InventoryIdentifier {
+    /// Converts every entity this refers to, such as from the client's entities to the server's.
+    ///
+    /// Returns None if any entity could not be converted.
+    pub fn map_entities(self, mut map: impl FnMut(Entity) -> Option<Entity>) -> Option<Self> {
+        match self {
+            Self::Entity(entity) => map(entity).map(Self::Entity),
+            Self::Container {
+                structure_entity,
+                block,
+            } => map(structure_entity).map(|structure_entity| Self::Container {
+                structure_entity,
+                block,
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// A single slot of an inventory
+pub struct InventorySlot {
+    /// The inventory the slot is in
+    pub inventory: InventoryIdentifier,
+    /// The slot's index
+    pub slot: u32,
+}
+
+impl InventorySlot {
+    /// Creates a slot of that inventory
+    pub fn new(inventory: InventoryIdentifier, slot: u32) -> Self {
+        Self { inventory, slot }
+    }
+
+    fn map_entities(self, map: impl FnMut(Entity) -> Option<Entity>) -> Option<Self> {
+        self.inventory
+            .map_entities(map)
+            .map(|inventory| Self::new(inventory, self.slot))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// A change a player wants to make to one or two inventories
+pub enum InventoryTransaction {
+    /// Moves a whole stack to another slot.
+    ///
+    /// If that slot has the same item, as much as fits is merged into it. If it has a different item, the two stacks are swapped.
+    Move {
+        /// Where the stack is
+        from: InventorySlot,
+        /// Where the stack is going
+        to: InventorySlot,
+    },
+    /// Moves part of a stack to an empty slot, or merges it into a slot with the same item.
+    Split {
+        /// Where the stack is
+        from: InventorySlot,
+        /// Where the items are going
+        to: InventorySlot,
+        /// How many items to move
+        quantity: u16,
+    },
+    /// Moves a whole stack into another inventory, wherever it fits. Anything that doesn't fit stays where it was.
+    Transfer {
+        /// Where the stack is
+        from: InventorySlot,
+        /// The inventory it's going to
+        to: InventoryIdentifier,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Returned when a transaction cannot be applied
+pub enum TransactionError {
+    /// The inventory does not have this slot
+    InvalidSlot(u32),
+    /// There is nothing in this slot to move
+    EmptySlot(u32),
+    /// The quantity is 0 or more than is in the slot
+    InvalidQuantity(u16),
+    /// This slot has a different item in it
+    SlotOccupied(u32),
+    /// The items would be moved to the slot they're already in
+    SameSlot,
+    /// This transaction has to be between two different inventories
+    SameInventory,
+}
+
+impl fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSlot(slot) => write!(f, "Slot {slot} does not exist"),
+            Self::EmptySlot(slot) => write!(f, "Slot {slot} is empty"),
+            Self::InvalidQuantity(quantity) => write!(f, "Cannot move {quantity} items"),
+            Self::SlotOccupied(slot) => write!(f, "Slot {slot} has a different item in it"),
+            Self::SameSlot => write!(f, "Cannot move items to the slot they are in"),
+            Self::SameInventory => write!(f, "Cannot transfer items to the inventory they are in"),
+        }
+    }
+}
+
+impl std::error::Error for TransactionError {}
+
+/// Gets the inventory items are going to - either the other inventory, or the one they came from
+fn target<'a>(from: &'a mut Inventory, to: &'a mut Option<&mut Inventory>) -> &'a mut Inventory {
+    match to {
+        Some(to) => to,
+        None => from,
+    }
+}
+
+fn check_slot(inventory: &Inventory, slot: u32) -> Result<usize, TransactionError> {
+    let index = slot as usize;
+
+    if index < inventory.len() {
+        Ok(index)
+    } else {
+        Err(TransactionError::InvalidSlot(slot))
+    }
+}
+
+impl InventoryTransaction {
+    /// The slot the items are coming from
+    pub fn from(&self) -> InventorySlot {
+        match *self {
+            Self::Move { from, .. } | Self::Split { from, .. } | Self::Transfer { from, .. } => {
+                from
+            }
+        }
+    }
+
+    /// The inventory the items are going to
+    pub fn to_inventory(&self) -> InventoryIdentifier {
+        match *self {
+            Self::Move { to, .. } | Self::Split { to, .. } => to.inventory,
+            Self::Transfer { to, .. } => to,
+        }
+    }
+
+    /// Converts every entity this refers to, such as from the client's entities to the server's.
+    ///
+    /// Returns None if any entity could not be converted.
+    pub fn map_entities(self, mut map: impl FnMut(Entity) -> Option<Entity>) -> Option<Self> {
+        Some(match self {
+            Self::Move { from, to } => Self::Move {
+                from: from.map_entities(&mut map)?,
+                to: to.map_entities(&mut map)?,
+            },
+            Self::Split { from, to, quantity } => Self::Split {
+                from: from.map_entities(&mut map)?,
+                to: to.map_entities(&mut map)?,
+                quantity,
+            },
+            Self::Transfer { from, to } => Self::Transfer {
+                from: from.map_entities(&mut map)?,
+                to: to.map_entities(&mut map)?,
+            },
+        })
+    }
+
+    /// Applies this transaction. If an error is returned, neither inventory was changed.
+    ///
+    /// - `from` The inventory the items are coming from
+    /// - `to` The inventory the items are going to, or None if that is the same inventory as `from`
+    pub fn apply(
+        &self,
+        from: &mut Inventory,
+        mut to: Option<&mut Inventory>,
+    ) -> Result<(), TransactionError> {
+        match *self {
+            Self::Move {
+                from: from_slot,
+                to: to_slot,
+            } => {
+                let from_index = check_slot(from, from_slot.slot)?;
+                let to_index = check_slot(target(from, &mut to), to_slot.slot)?;
+
+                if to.is_none() && from_index == to_index {
+                    return Err(TransactionError::SameSlot);
+                }
+
+                let Some(mut moving) = from.items[from_index].take() else {
+                    return Err(TransactionError::EmptySlot(from_slot.slot));
+                };
+
+                let target_slot = &mut target(from, &mut to).items[to_index];
+
+                let left_behind = match target_slot.take() {
+                    Some(mut existing) if existing.can_stack_with(&moving) => {
+                        let leftover = existing.increase_quantity(moving.quantity());
+                        moving.decrease_quantity(moving.quantity() - leftover);
+
+                        *target_slot = Some(existing);
+
+                        (!moving.is_empty()).then_some(moving)
+                    }
+                    swapped => {
+                        *target_slot = Some(moving);
+
+                        swapped
+                    }
+                };
+
+                from.items[from_index] = left_behind;
+            }
+            Self::Split {
+                from: from_slot,
+                to: to_slot,
+                quantity,
+            } => {
+                let from_index = check_slot(from, from_slot.slot)?;
+                let to_index = check_slot(target(from, &mut to), to_slot.slot)?;
+
+                if to.is_none() && from_index == to_index {
+                    return Err(TransactionError::SameSlot);
+                }
+
+                let Some(source) = from.items[from_index].as_ref() else {
+                    return Err(TransactionError::EmptySlot(from_slot.slot));
+                };
+
+                if quantity == 0 || quantity > source.quantity() {
+                    return Err(TransactionError::InvalidQuantity(quantity));
+                }
+
+                let mut split = source.clone();
+                split.decrease_quantity(split.quantity() - quantity);
+
+                let target_slot = &mut target(from, &mut to).items[to_index];
+
+                let moved = match target_slot {
+                    Some(existing) => {
+                        if !existing.can_stack_with(&split) {
+                            return Err(TransactionError::SlotOccupied(to_slot.slot));
+                        }
+
+                        quantity - existing.increase_quantity(quantity)
+                    }
+                    None => {
+                        *target_slot = Some(split);
+
+                        quantity
+                    }
+                };
+
+                from.decrease_quantity_at(from_index, moved);
+            }
+            Self::Transfer {
+                from: from_slot, ..
+            } => {
+                let from_index = check_slot(from, from_slot.slot)?;
+
+                let Some(to) = to else {
+                    return Err(TransactionError::SameInventory);
+                };
+
+                let Some(moving) = from.items[from_index].take() else {
+                    return Err(TransactionError::EmptySlot(from_slot.slot));
+                };
+
+                from.items[from_index] = to.insert_itemstack(moving);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Applies this stack to that slot, as sent by the server.
+///
+/// Returns false if the inventory doesn't have that slot.
+pub fn apply_slot_change(
+    inventory: &mut Inventory,
+    slot: u32,
+    item_stack: Option<ItemStack>,
+) -> bool {
+    let Some(contents) = inventory.items.get_mut(slot as usize) else {
+        return false;
+    };
+
+    *contents = item_stack;
+
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{item::Item, registry::identifiable::Identifiable};
+
+    use super::*;
+
+    fn item(id: u16, max_stack_size: u16) -> Item {
+        let mut item = Item::new(format!("cosmos:test_{id}"), max_stack_size);
+        item.set_numeric_id(id);
+        item
+    }
+
+    fn slot(slot: u32) -> InventorySlot {
+        InventorySlot::new(InventoryIdentifier::Entity(Entity::from_raw(0)), slot)
+    }
+
+    fn quantities(inventory: &Inventory) -> Vec<Option<(u16, u16)>> {
+        inventory
+            .iter()
+            .map(|is| is.as_ref().map(|is| (is.item_id(), is.quantity())))
+            .collect()
+    }
+
+    #[test]
+    fn move_merges_then_swaps() {
+        let (stone, dirt) = (item(1, 10), item(2, 10));
+
+        let mut inventory = Inventory::new(3);
+        inventory.insert_at(0, &stone, 6);
+        inventory.insert_at(1, &stone, 6);
+        inventory.insert_at(2, &dirt, 1);
+
+        let merge = InventoryTransaction::Move {
+            from: slot(0),
+            to: slot(1),
+        };
+        merge.apply(&mut inventory, None).unwrap();
+
+        // Only 4 fit, so 2 stay behind
+        assert_eq!(
+            quantities(&inventory),
+            vec![Some((1, 2)), Some((1, 10)), Some((2, 1))]
+        );
+
+        let swap = InventoryTransaction::Move {
+            from: slot(2),
+            to: slot(0),
+        };
+        swap.apply(&mut inventory, None).unwrap();
+
+        assert_eq!(
+            quantities(&inventory),
+            vec![Some((2, 1)), Some((1, 10)), Some((1, 2))]
+        );
+    }
+
+    #[test]
+    fn split_and_transfer_between_inventories() {
+        let stone = item(1, 10);
+
+        let mut from = Inventory::new(2);
+        from.insert_at(0, &stone, 7);
+
+        let mut to = Inventory::new(2);
+
+        let split = InventoryTransaction::Split {
+            from: slot(0),
+            to: slot(1),
+            quantity: 3,
+        };
+        split.apply(&mut from, Some(&mut to)).unwrap();
+
+        assert_eq!(quantities(&from), vec![Some((1, 4)), None]);
+        assert_eq!(quantities(&to), vec![None, Some((1, 3))]);
+
+        let transfer = InventoryTransaction::Transfer {
+            from: slot(0),
+            to: slot(0).inventory,
+        };
+        transfer.apply(&mut from, Some(&mut to)).unwrap();
+
+        assert_eq!(quantities(&from), vec![None, None]);
+        assert_eq!(quantities(&to), vec![None, Some((1, 7))]);
+    }
+
+    #[test]
+    fn invalid_transactions_change_nothing() {
+        let (stone, dirt) = (item(1, 10), item(2, 10));
+
+        let mut inventory = Inventory::new(2);
+        inventory.insert_at(0, &stone, 5);
+        inventory.insert_at(1, &dirt, 5);
+
+        let before = inventory.clone();
+
+        let too_many = InventoryTransaction::Split {
+            from: slot(0),
+            to: slot(1),
+            quantity: 6,
+        };
+        let occupied = InventoryTransaction::Split {
+            from: slot(0),
+            to: slot(1),
+            quantity: 2,
+        };
+        let missing_slot = InventoryTransaction::Move {
+            from: slot(0),
+            to: slot(5),
+        };
+
+        assert_eq!(
+            too_many.apply(&mut inventory, None),
+            Err(TransactionError::InvalidQuantity(6))
+        );
+        assert_eq!(
+            occupied.apply(&mut inventory, None),
+            Err(TransactionError::SlotOccupied(1))
+        );
+        assert_eq!(
+            missing_slot.apply(&mut inventory, None),
+            Err(TransactionError::InvalidSlot(5))
+        );
+        assert_eq!(inventory, before);
+    }
+}
@@ -1,23 +1,106 @@
 //! Loads all the items for cosmos & adds the item registry.
+//!
+//! Every block automatically gets an item, so only items that aren't blocks need to be defined in
+//! the `content/items` files of a content pack.
+//!
+//! An example item:
+//!
+//! ```json
+//! {
+//!     "unlocalized_name": "cosmos:mining_laser",
+//!     "category": "Tool",
+//!     "max_stack_size": 1,
+//!     "durability": 500
+//! }
+//! ```
 
-// use crate::loader::{AddLoadingEvent, DoneLoadingEvent, LoadingManager};
-use crate::registry;
-use bevy::prelude::App;
+use crate::content::{self, ContentError, ContentObject, ContentPacks};
+use crate::loader::{AddLoadingEvent, DoneLoadingEvent, LoadingManager};
+use crate::registry::{self, Registry};
+use bevy::prelude::{App, EventWriter, IntoSystemAppConfig, OnEnter, Res, ResMut, States};
 
-use super::Item;
+use super::{Item, ItemCategory, DEFAULT_MAX_STACK_SIZE};
 
-// pub fn add_cosmos_items(
-//     mut items: ResMut<Registry<Item>>,
-//     mut loading: ResMut<LoadingManager>,
-//     mut end_writer: EventWriter<DoneLoadingEvent>,
-//     mut start_writer: EventWriter<AddLoadingEvent>,
-// ) {
-//     let id = loading.register_loader(&mut start_writer);
-//     loading.finish_loading(id, &mut end_writer);
-// }
+/// The kind of content item definitions are
+const ITEMS_CONTENT: &str = "items";
 
-pub(super) fn register(app: &mut App) {
+/// Every field an item definition can have
+const FIELDS: [&str; 5] = [
+    "unlocalized_name",
+    "max_stack_size",
+    "category",
+    "durability",
+    "energy_capacity",
+];
+
+fn parse_item_definition(object: &ContentObject) -> Result<Item, ContentError> {
+    object.deny_unknown_fields(&FIELDS)?;
+
+    let unlocalized_name = object.required::<String>("unlocalized_name")?;
+    let max_stack_size = object
+        .optional::<u16>("max_stack_size")?
+        .unwrap_or(DEFAULT_MAX_STACK_SIZE);
+
+    if max_stack_size == 0 {
+        return Err(object.error("max_stack_size", "Must be at least 1"));
+    }
+
+    let category = object
+        .optional::<ItemCategory>("category")?
+        .unwrap_or_default();
+
+    let mut item = Item::new(unlocalized_name, max_stack_size).with_category(category);
+
+    if let Some(durability) = object.optional::<u32>("durability")? {
+        if durability == 0 {
+            return Err(object.error("durability", "Must be at least 1"));
+        }
+
+        item = item.with_durability(durability);
+    }
+
+    if let Some(energy_capacity) = object.optional::<f32>("energy_capacity")? {
+        if !energy_capacity.is_finite() || energy_capacity <= 0.0 {
+            return Err(object.error(
+                "energy_capacity",
+                format!("Must be more than 0, but was {energy_capacity}"),
+            ));
+        }
+
+        item = item.with_energy_capacity(energy_capacity);
+    }
+
+    Ok(item)
+}
+
+fn add_cosmos_items(
+    mut items: ResMut<Registry<Item>>,
+    packs: Res<ContentPacks>,
+    mut loading: ResMut<LoadingManager>,
+    mut end_writer: EventWriter<DoneLoadingEvent>,
+    mut start_writer: EventWriter<AddLoadingEvent>,
+) {
+    let id = loading.register_loader(&mut start_writer);
+
+    match content::load_definitions(
+        &packs,
+        ITEMS_CONTENT,
+        |name| items.from_id(name).is_some(),
+        parse_item_definition,
+    ) {
+        Ok(loaded) => {
+            for item in loaded {
+                items.register(item);
+            }
+        }
+        Err(errors) => content::panic_with_errors("item definitions", &errors),
+    }
+
+    loading.finish_loading(id, &mut end_writer);
+}
+
+pub(super) fn register<T: States + Clone + Copy>(app: &mut App, loading_state: T) {
     registry::create_registry::<Item>(app);
 
-    // app.add_system_set(SystemSet::on_enter(loading_state).with_system(add_cosmos_items));
+    app.add_system(add_cosmos_items.in_schedule(OnEnter(loading_state)));
 }
@@ -2,15 +2,35 @@
 
 pub mod items;
 
-use bevy::prelude::App;
+use bevy::prelude::{App, States};
+use serde::{Deserialize, Serialize};
 
 use crate::registry::identifiable::Identifiable;
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// What sort of thing an item is
+pub enum ItemCategory {
+    /// An item that places a block
+    Block,
+    /// Something that is used & wears out, such as a mining laser
+    Tool,
+    #[default]
+    /// A part that is used to craft other things
+    Component,
+    /// Something that stores energy
+    Fuel,
+    /// Something that is fired from a weapon
+    Ammo,
+}
+
 /// An item represents something that can be stored in inventories.
 pub struct Item {
     unlocalized_name: String,
     numeric_id: u16,
     max_stack_size: u16,
+    category: ItemCategory,
+    durability: Option<u32>,
+    energy_capacity: Option<f32>,
 }
 
 impl Identifiable for Item {
@@ -29,7 +49,7 @@ impl Identifiable for Item {
     }
 }
 
-/// The max stack size for items, should load this from config file in future
+/// The max stack size for items that don't specify their own
 pub const DEFAULT_MAX_STACK_SIZE: u16 = 999;
 
 impl Item {
@@ -39,15 +59,51 @@ impl Item {
             unlocalized_name,
             numeric_id: 0, // this will get set when this item is registered
             max_stack_size,
+            category: ItemCategory::default(),
+            durability: None,
+            energy_capacity: None,
         }
     }
 
+    /// Sets what sort of thing this item is
+    pub fn with_category(mut self, category: ItemCategory) -> Self {
+        self.category = category;
+        self
+    }
+
+    /// Makes this item wear out after being used this many times
+    pub fn with_durability(mut self, durability: u32) -> Self {
+        self.durability = Some(durability);
+        self
+    }
+
+    /// Lets this item store up to this much energy
+    pub fn with_energy_capacity(mut self, energy_capacity: f32) -> Self {
+        self.energy_capacity = Some(energy_capacity);
+        self
+    }
+
     /// Returns the max stack size for this item
     pub fn max_stack_size(&self) -> u16 {
         self.max_stack_size
     }
+
+    /// Returns what sort of thing this item is
+    pub fn category(&self) -> ItemCategory {
+        self.category
+    }
+
+    /// Returns how many uses a new one of this item has, or None if it never wears out
+    pub fn durability(&self) -> Option<u32> {
+        self.durability
+    }
+
+    /// Returns the most energy this item can store, or None if it can't store any
+    pub fn energy_capacity(&self) -> Option<f32> {
+        self.energy_capacity
+    }
 }
 
-pub(super) fn register(app: &mut App) {
-    items::register(app);
+pub(super) fn register<T: States + Clone + Copy>(app: &mut App, loading_state: T) {
+    items::register(app, loading_state);
 }
@@ -5,6 +5,8 @@
 
 pub mod block;
 pub mod blockitems;
+pub mod content;
+pub mod crafting;
 pub mod ecs;
 pub mod entities;
 pub mod events;
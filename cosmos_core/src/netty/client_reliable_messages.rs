@@ -4,7 +4,7 @@
 use bevy::prelude::{Component, Entity};
 use serde::{Deserialize, Serialize};
 
-use crate::{block::BlockFace, entities::player::render_distance::RenderDistance};
+use crate::{block::BlockRotation, entities::player::render_distance::RenderDistance};
 
 #[derive(Debug, Serialize, Deserialize, Component)]
 /// All reliable messages a client can send
@@ -37,6 +37,10 @@ pub enum ClientReliableMessages {
         y: u32,
         /// The block's z
         z: u32,
+        /// The size of the structure in chunks when the client picked this block.
+        ///
+        /// Ships can grow before the client hears about it, so the server uses this to move the coordinates to where the block is now.
+        structure_size: (u32, u32, u32),
     },
     /// The client placed a block
     PlaceBlock {
@@ -48,12 +52,16 @@ pub enum ClientReliableMessages {
         y: u32,
         /// The block's z
         z: u32,
+        /// The size of the structure in chunks when the client picked this block.
+        ///
+        /// Ships can grow before the client hears about it, so the server uses this to move the coordinates to where the block is now.
+        structure_size: (u32, u32, u32),
         /// The block they placed
         ///
         /// This is passed along with `inventory_slot` to verify that the client + server are still in sync
         block_id: u16,
-        /// The block's top face
-        block_up: BlockFace,
+        /// The block's rotation
+        block_rotation: BlockRotation,
         /// The inventory slot the block came from
         inventory_slot: u32,
     },
@@ -67,6 +75,10 @@ pub enum ClientReliableMessages {
         y: u32,
         /// The block's z
         z: u32,
+        /// The size of the structure in chunks when the client picked this block.
+        ///
+        /// Ships can grow before the client hears about it, so the server uses this to move the coordinates to where the block is now.
+        structure_size: (u32, u32, u32),
     },
     /// Asks the server to create a ship
     CreateShip {
@@ -38,6 +38,10 @@ pub enum NettyChannel {
 
     /// Used for asteroids
     Asteroids,
+    /// Used for `InventoryServerMessages` and `InventoryClientMessages`
+    Inventory,
+    /// Used for `CraftingServerMessages` and `CraftingClientMessages`
+    Crafting,
 }
 
 /// In the future, this should be based off the game version.
@@ -53,6 +57,8 @@ impl NettyChannel {
             Self::Unreliable => 1,
             Self::LaserCannonSystem => 2,
             Self::Asteroids => 3,
+            Self::Inventory => 4,
+            Self::Crafting => 5,
         }
     }
 
@@ -94,6 +100,24 @@ impl NettyChannel {
                 ..Default::default()
             }
             .into(),
+            ReliableChannelConfig {
+                channel_id: Self::Inventory.id(),
+                message_send_queue_size: 1024,
+                message_receive_queue_size: 1024 * 4,
+                max_message_size: 6000,
+                packet_budget: 7000,
+                ..Default::default()
+            }
+            .into(),
+            ReliableChannelConfig {
+                channel_id: Self::Crafting.id(),
+                message_send_queue_size: 1024,
+                message_receive_queue_size: 1024,
+                max_message_size: 12000,
+                packet_budget: 13000,
+                ..Default::default()
+            }
+            .into(),
         ]
     }
 
@@ -135,6 +159,24 @@ impl NettyChannel {
                 ..Default::default()
             }
             .into(),
+            ReliableChannelConfig {
+                channel_id: Self::Inventory.id(),
+                message_send_queue_size: 1024 * 4,
+                message_receive_queue_size: 1024,
+                max_message_size: 6000,
+                packet_budget: 7000,
+                ..Default::default()
+            }
+            .into(),
+            ReliableChannelConfig {
+                channel_id: Self::Crafting.id(),
+                message_send_queue_size: 1024,
+                message_receive_queue_size: 1024,
+                max_message_size: 12000,
+                packet_budget: 13000,
+                ..Default::default()
+            }
+            .into(),
         ]
     }
 }
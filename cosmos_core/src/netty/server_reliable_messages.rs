@@ -7,9 +7,15 @@ use bevy::prelude::{Component, Entity};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    block::BlockFace,
+    block::BlockRotation,
+    content::ContentPackId,
     entities::player::render_distance::RenderDistance,
-    structure::{loading::ChunksNeedLoaded, planet::Planet},
+    inventory::itemstack::ItemStack,
+    registry::id_map::IdMap,
+    structure::{
+        block_edit_batch::BlockEdit, loading::ChunksNeedLoaded, planet::Planet,
+        structure_block::StructureBlock,
+    },
     universe::star::Star,
 };
 
@@ -18,6 +24,18 @@ use super::netty_rigidbody::NettyRigidBody;
 #[derive(Debug, Serialize, Deserialize, Component)]
 /// A mash of a bunch of different packets the server reliably sends.
 pub enum ServerReliableMessages {
+    /// The first message sent to a client when it connects.
+    ///
+    /// Numeric ids depend on the order things were registered in, so the client uses this to translate
+    /// the server's ids into its own. The client must have the same content packs as the server to play on it.
+    RegistrySync {
+        /// Every content pack the server uses, in load order
+        packs: Vec<ContentPackId>,
+        /// The unlocalized name of every block id the server uses
+        blocks: IdMap,
+        /// The unlocalized name of every item id the server uses
+        items: IdMap,
+    },
     /// A player has been created, and the client should add them.
     PlayerCreate {
         /// The server entity for this player
@@ -100,6 +118,20 @@ pub enum ServerReliableMessages {
         /// The number of chunks that need to be loaded from the server
         chunks_needed: ChunksNeedLoaded,
     },
+    /// A dropped item should be created on the client-side, or have its items updated if it already exists.
+    DroppedItem {
+        /// The dropped item's server entity
+        entity: Entity,
+        /// The dropped item's rigidbody
+        body: NettyRigidBody,
+        /// The items it holds
+        item_stack: ItemStack,
+    },
+    /// A dropped item has been removed (usually picked up), and the client should remove it.
+    DroppedItemRemove {
+        /// The dropped item's server entity
+        entity: Entity,
+    },
     /// Represents the inventory that an entity has.
     EntityInventory {
         /// The serialized version of an inventory
@@ -124,8 +156,55 @@ pub enum ServerReliableMessages {
         z: u32,
         /// The block it was changed to
         block_id: u16,
-        /// The block's up direction
-        block_up: BlockFace,
+        /// The block's rotation
+        block_rotation: BlockRotation,
+    },
+    /// Sent when the server changes many blocks in a structure at once
+    BlockChangeBatch {
+        /// The structure that was changed
+        structure_entity: Entity,
+        /// Every block that was changed
+        changes: Vec<BlockEdit>,
+    },
+    /// Sent when the data stored on a block changes
+    BlockDataChanged {
+        /// The structure the block is on
+        structure_entity: Entity,
+        /// The block whose data changed
+        block: StructureBlock,
+        /// The id of the data that changed
+        data_id: String,
+        /// The serialized data, or None if it was removed
+        data: Option<Vec<u8>>,
+    },
+    /// Sent when blocks on a structure have their health changed, such as by taking damage.
+    ///
+    /// The health of already damaged blocks is sent along with their chunk.
+    BlockHealthChanged {
+        /// The structure the blocks are on
+        structure_entity: Entity,
+        /// Every block that changed & its new health
+        changes: Vec<(StructureBlock, f32)>,
+    },
+    /// Sent when a structure grows.
+    ///
+    /// This is always sent before any block changes that use the structure's new coordinates.
+    StructureResized {
+        /// The structure that was resized
+        structure_entity: Entity,
+        /// The number of chunks added before the existing chunks on the x/y/z axes
+        negative: (u32, u32, u32),
+        /// The number of chunks added after the existing chunks on the x/y/z axes
+        positive: (u32, u32, u32),
+    },
+    /// Sent to a player when a block they tried to place couldn't be placed.
+    ///
+    /// Their inventory is sent again, since it still has the block in it.
+    BlockPlaceRejected {
+        /// The structure the block was going to be placed on
+        structure_entity: Entity,
+        /// Why the block couldn't be placed
+        reason: String,
     },
     /// Sent when a pilot changes
     PilotChange {
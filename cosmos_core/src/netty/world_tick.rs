@@ -6,14 +6,30 @@ use bevy::{
     prelude::{resource_exists, App, IntoSystemConfig, ResMut, Resource},
     time::common_conditions::on_timer,
 };
+use serde::{Deserialize, Serialize};
 
 /// The maximum amount of ticks per second the client/server will have.
 const MAX_TPS: u64 = 20;
 
-#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 /// Represents how many "game" ticks have occured
+///
+/// Ticks only happen once this resource has been inserted.
 pub struct WorldTick(u64);
 
+impl WorldTick {
+    /// Starts counting ticks from this tick
+    pub fn new(tick: u64) -> Self {
+        Self(tick)
+    }
+
+    #[inline]
+    /// Gets how many ticks have occured
+    pub fn get(&self) -> u64 {
+        self.0
+    }
+}
+
 fn tick(mut world_ticks: ResMut<WorldTick>) {
     world_ticks.0 += 1;
 }
@@ -0,0 +1,74 @@
+//! Explosions damage every block & push every rigid body within their radius.
+//!
+//! Send an [`ExplosionEvent`] to create one - the server handles the rest.
+
+use bevy::prelude::{App, Entity};
+
+use super::location::Location;
+
+#[derive(Debug, Clone, Copy)]
+/// Sent to create an explosion at a location
+pub struct ExplosionEvent {
+    /// The center of the explosion
+    pub location: Location,
+    /// How powerful the explosion is.
+    ///
+    /// This is how much damage a block at the very center of the explosion takes, and determines its radius.
+    pub power: f32,
+    /// What caused this explosion, if anything
+    pub source: Option<Entity>,
+}
+
+impl ExplosionEvent {
+    /// Creates an explosion at this location
+    pub fn new(location: Location, power: f32, source: Option<Entity>) -> Self {
+        Self {
+            location,
+            power,
+            source,
+        }
+    }
+
+    /// How far away from its center this explosion reaches
+    pub fn radius(&self) -> f32 {
+        self.power.max(0.0).sqrt()
+    }
+
+    /// How much of this explosion's power reaches something this far away from its center.
+    ///
+    /// This falls off linearly, and is 0 at or beyond the explosion's radius.
+    pub fn power_at(&self, distance: f32) -> f32 {
+        let radius = self.radius();
+
+        if radius <= 0.0 || distance >= radius {
+            0.0
+        } else {
+            self.power * (1.0 - distance / radius)
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_event::<ExplosionEvent>();
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::Vec3;
+
+    use crate::physics::location::{Location, Sector};
+
+    use super::ExplosionEvent;
+
+    #[test]
+    fn power_falls_off_with_distance() {
+        let explosion =
+            ExplosionEvent::new(Location::new(Vec3::ZERO, Sector::new(0, 0, 0)), 100.0, None);
+
+        assert_eq!(explosion.radius(), 10.0);
+        assert_eq!(explosion.power_at(0.0), 100.0);
+        assert_eq!(explosion.power_at(5.0), 50.0);
+        assert_eq!(explosion.power_at(10.0), 0.0);
+        assert_eq!(explosion.power_at(20.0), 0.0);
+    }
+}
@@ -2,6 +2,7 @@
 
 use bevy::prelude::App;
 
+pub mod explosion;
 pub mod gravity_system;
 pub mod location;
 pub mod player_world;
@@ -11,6 +12,7 @@ pub mod structure_physics;
 pub(super) fn register(app: &mut App) {
     structure_physics::register(app);
     gravity_system::register(app);
+    explosion::register(app);
     location::register(app);
     player_world::register(app);
     stop_near_unloaded_chunks::register(app);
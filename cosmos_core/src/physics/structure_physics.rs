@@ -3,7 +3,7 @@
 use std::sync::Mutex;
 
 use crate::block::Block;
-use crate::events::block_events::BlockChangedEvent;
+use crate::events::block_events::{BlockChangedBatchEvent, BlockChangedEvent};
 use crate::registry::Registry;
 use crate::structure::chunk::{Chunk, CHUNK_DIMENSIONS};
 use crate::structure::events::ChunkSetEvent;
@@ -247,6 +247,7 @@ fn listen_for_new_physics_event(
 
 fn listen_for_structure_event(
     mut event: EventReader<BlockChangedEvent>,
+    mut batch_event: EventReader<BlockChangedBatchEvent>,
     mut chunk_set_event: EventReader<ChunkSetEvent>,
     mut event_writer: EventWriter<ChunkNeedsPhysicsEvent>,
 ) {
@@ -259,6 +260,13 @@ fn listen_for_structure_event(
         });
     }
 
+    for ev in batch_event.iter() {
+        to_do.insert(ChunkNeedsPhysicsEvent {
+            chunk: ev.chunk,
+            structure_entity: ev.structure_entity,
+        });
+    }
+
     for ev in chunk_set_event.iter() {
         to_do.insert(ChunkNeedsPhysicsEvent {
             chunk: (ev.x, ev.y, ev.z),
@@ -282,7 +290,7 @@ pub(super) fn register(app: &mut App) {
 #[cfg(test)]
 mod test {
     use crate::{
-        block::{block_builder::BlockBuilder, Block, BlockFace},
+        block::{block_builder::BlockBuilder, Block, BlockRotation},
         registry::Registry,
         structure::chunk::{Chunk, CHUNK_DIMENSIONS},
     };
@@ -299,7 +307,7 @@ mod test {
 
         let test_block = blocks.from_id("test").unwrap();
 
-        chunk.set_block_at(1, 2, 3, test_block, BlockFace::Top);
+        chunk.set_block_at(1, 2, 3, test_block, BlockRotation::IDENTITY);
 
         let (_, mass) = generate_chunk_collider(&chunk, &blocks).unwrap();
 
@@ -316,14 +324,14 @@ mod test {
 
         let test_block = blocks.from_id("test").unwrap();
 
-        chunk.set_block_at(1, 2, 3, test_block, BlockFace::Top);
+        chunk.set_block_at(1, 2, 3, test_block, BlockRotation::IDENTITY);
 
         chunk.set_block_at(
             CHUNK_DIMENSIONS - 2,
             CHUNK_DIMENSIONS - 3,
             CHUNK_DIMENSIONS - 4,
             test_block,
-            BlockFace::Top,
+            BlockRotation::IDENTITY,
         );
 
         let (_, mass) = generate_chunk_collider(&chunk, &blocks).unwrap();
@@ -343,14 +351,14 @@ mod test {
         let test_block = blocks.from_id("test").unwrap();
         let test_block_2 = blocks.from_id("test2").unwrap();
 
-        chunk.set_block_at(0, 0, 0, test_block, BlockFace::Top);
+        chunk.set_block_at(0, 0, 0, test_block, BlockRotation::IDENTITY);
 
         chunk.set_block_at(
             CHUNK_DIMENSIONS - 1,
             CHUNK_DIMENSIONS - 1,
             CHUNK_DIMENSIONS - 1,
             test_block_2,
-            BlockFace::Top,
+            BlockRotation::IDENTITY,
         );
 
         let (_, mass) = generate_chunk_collider(&chunk, &blocks).unwrap();
@@ -5,7 +5,9 @@ use bevy::prelude::{App, Plugin, PluginGroup, States};
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
 use bevy_rapier3d::prelude::{NoUserData, RapierPhysicsPlugin};
 
-use crate::{block, ecs, entities, inventory, netty, persistence, projectiles, universe};
+use crate::{
+    block, content, crafting, ecs, entities, inventory, netty, persistence, projectiles, universe,
+};
 use crate::{blockitems, structure};
 use crate::{events, loader};
 use crate::{item, physics};
@@ -82,18 +84,20 @@ impl<T: States + Clone + Copy> Plugin for CosmosCorePlugin<T> {
             self.done_loading_state,
         );
 
+        content::register(app, self.pre_loading_state);
         block::register(
             app,
             self.pre_loading_state,
             self.loading_state,
             self.post_loading_state,
         );
-        item::register(app);
+        item::register(app, self.loading_state);
         blockitems::register(app, self.post_loading_state);
         physics::register(app);
         events::register(app, self.playing_game_state);
         structure::register(app, self.post_loading_state, self.playing_game_state);
-        inventory::register(app);
+        inventory::register(app, self.post_loading_state);
+        crafting::register(app, self.post_loading_state);
         projectiles::register(app);
         entities::register(app);
         ecs::register(app);
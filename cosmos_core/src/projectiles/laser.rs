@@ -36,6 +36,7 @@ use crate::{
 pub struct LaserCollideEvent {
     entity_hit: Entity,
     local_position_hit: Vec3,
+    local_direction: Vec3,
     laser_strength: f32,
 }
 
@@ -56,6 +57,11 @@ impl LaserCollideEvent {
     pub fn local_position_hit(&self) -> Vec3 {
         self.local_position_hit
     }
+
+    /// The direction this laser was travelling relative to the entity it hit's transform.
+    pub fn local_direction(&self) -> Vec3 {
+        self.local_direction
+    }
 }
 
 #[derive(Component)]
@@ -247,24 +253,24 @@ fn handle_events(
 
                 if let Ok(parent) = parent_query.get(entity) {
                     if let Ok(transform) = transform_query.get(parent.get()) {
-                        let lph = Quat::from_affine3(&transform.affine())
-                            .inverse()
-                            .mul_vec3(pos - transform.translation());
+                        let inverse_rotation = Quat::from_affine3(&transform.affine()).inverse();
+                        let lph = inverse_rotation.mul_vec3(pos - transform.translation());
 
                         event_writer.send(LaserCollideEvent {
                             entity_hit: entity,
                             local_position_hit: lph,
+                            local_direction: inverse_rotation.mul_vec3(ray_direction),
                             laser_strength: laser.strength,
                         });
                     }
                 } else if let Ok(transform) = transform_query.get(entity) {
-                    let lph = Quat::from_affine3(&transform.affine())
-                        .inverse()
-                        .mul_vec3(pos - transform.translation());
+                    let inverse_rotation = Quat::from_affine3(&transform.affine()).inverse();
+                    let lph = inverse_rotation.mul_vec3(pos - transform.translation());
 
                     event_writer.send(LaserCollideEvent {
                         entity_hit: entity,
                         local_position_hit: lph,
+                        local_direction: inverse_rotation.mul_vec3(ray_direction),
                         laser_strength: laser.strength,
                     });
                 }
@@ -0,0 +1,197 @@
+//! Numeric ids are assigned in the order things are registered, so they can change whenever something is added,
+//! removed, or reordered. Anything that stores numeric ids somewhere that outlives the current registry (such as a
+//! save file) should also store an [`IdMap`] of the registry it used, so those ids can be translated back
+//! into the current registry's ids via an [`IdRemap`].
+
+use bevy::utils::HashMap;
+use serde::{Deserialize, Serialize};
+
+use super::{identifiable::Identifiable, Registry};
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A snapshot of which unlocalized name every numeric id in a registry belonged to
+pub struct IdMap {
+    /// The index of each name is the numeric id it had
+    names: Vec<String>,
+}
+
+impl IdMap {
+    /// Creates a snapshot of the ids currently used by this registry
+    pub fn from_registry<T: Identifiable + Sync + Send>(registry: &Registry<T>) -> Self {
+        Self {
+            names: registry
+                .iter()
+                .map(|x| x.unlocalized_name().to_owned())
+                .collect(),
+        }
+    }
+
+    /// Gets the unlocalized name that had this numeric id
+    pub fn name(&self, id: u16) -> Option<&str> {
+        self.names.get(id as usize).map(|x| x.as_str())
+    }
+
+    /// The number of ids stored in this map
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Returns true if this map has no ids stored in it
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Creates a remap that translates ids from this map into the current ids of the given registry.
+    ///
+    /// * `registry` The registry to translate ids into
+    /// * `placeholder` The unlocalized name of what anything not in the registry should become.
+    /// If the placeholder isn't registered either, those ids become 0.
+    pub fn remap_for<T: Identifiable + Sync + Send>(
+        &self,
+        registry: &Registry<T>,
+        placeholder: &str,
+    ) -> IdRemap {
+        self.remap_with(|name| registry.from_id(name).map(|x| x.id()), placeholder)
+    }
+
+    /// Creates a remap that translates ids from this map into the ids of another map.
+    ///
+    /// * `other` The map to translate ids into
+    /// * `placeholder` The unlocalized name of what anything not in the other map should become.
+    /// If the placeholder isn't in the other map either, those ids become 0.
+    pub fn remap_to(&self, other: &IdMap, placeholder: &str) -> IdRemap {
+        let ids = other
+            .names
+            .iter()
+            .enumerate()
+            .map(|(id, name)| (name.as_str(), id as u16))
+            .collect::<HashMap<&str, u16>>();
+
+        self.remap_with(|name| ids.get(name).copied(), placeholder)
+    }
+
+    fn remap_with(&self, id_of: impl Fn(&str) -> Option<u16>, placeholder: &str) -> IdRemap {
+        let placeholder = id_of(placeholder).unwrap_or(0);
+
+        let mut missing = Vec::new();
+
+        let ids = self
+            .names
+            .iter()
+            .map(|name| {
+                id_of(name).unwrap_or_else(|| {
+                    missing.push(name.clone());
+                    placeholder
+                })
+            })
+            .collect();
+
+        IdRemap {
+            ids,
+            placeholder,
+            missing,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// Translates ids from an old [`IdMap`] into the ids of the current registry
+pub struct IdRemap {
+    ids: Vec<u16>,
+    placeholder: u16,
+    missing: Vec<String>,
+}
+
+impl IdRemap {
+    /// Gets the current id for this old id.
+    ///
+    /// Any id that is no longer registered (or was never in the old map) becomes the placeholder.
+    #[inline]
+    pub fn get(&self, old_id: u16) -> u16 {
+        self.ids
+            .get(old_id as usize)
+            .copied()
+            .unwrap_or(self.placeholder)
+    }
+
+    /// Returns true if every id maps to itself, meaning nothing actually needs to be remapped
+    pub fn is_identity(&self) -> bool {
+        self.ids.iter().enumerate().all(|(i, id)| i == *id as usize)
+    }
+
+    /// The unlocalized names of everything in the old map that isn't in the registry anymore
+    pub fn missing(&self) -> &[String] {
+        &self.missing
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Thing {
+        id: u16,
+        name: String,
+    }
+
+    impl Identifiable for Thing {
+        fn id(&self) -> u16 {
+            self.id
+        }
+
+        fn unlocalized_name(&self) -> &str {
+            &self.name
+        }
+
+        fn set_numeric_id(&mut self, id: u16) {
+            self.id = id;
+        }
+    }
+
+    fn registry(names: &[&str]) -> Registry<Thing> {
+        let mut registry = Registry::new();
+
+        for name in names {
+            registry.register(Thing {
+                id: 0,
+                name: (*name).to_owned(),
+            });
+        }
+
+        registry
+    }
+
+    #[test]
+    fn remaps_reordered_and_missing() {
+        let old = IdMap::from_registry(&registry(&["a", "b", "c"]));
+        let current = registry(&["a", "missing", "c", "b"]);
+
+        let remap = old.remap_for(&current, "missing");
+
+        assert_eq!(remap.get(0), 0);
+        assert_eq!(remap.get(1), 3);
+        assert_eq!(remap.get(2), 2);
+        assert_eq!(remap.get(3), 1);
+        assert!(!remap.is_identity());
+        assert!(remap.missing().is_empty());
+
+        let remap = IdMap::from_registry(&current).remap_for(&registry(&["a", "b", "c"]), "b");
+
+        assert_eq!(remap.get(1), 1);
+        assert_eq!(remap.get(3), 1);
+        assert_eq!(remap.missing(), &["missing".to_owned()]);
+    }
+
+    #[test]
+    fn remaps_between_maps() {
+        let old = IdMap::from_registry(&registry(&["a", "b", "c"]));
+        let new = IdMap::from_registry(&registry(&["c", "a"]));
+
+        let remap = old.remap_to(&new, "a");
+
+        assert_eq!(remap.get(0), 1);
+        assert_eq!(remap.get(1), 1);
+        assert_eq!(remap.get(2), 0);
+        assert_eq!(remap.missing(), &["b".to_owned()]);
+    }
+}
@@ -1,5 +1,6 @@
 //! Handles the various types of registries you can use to register data.
 
+pub mod id_map;
 pub mod identifiable;
 pub mod many_to_one;
 
@@ -61,6 +62,14 @@ impl<T: Identifiable + Sync + Send> Registry<T> {
         &self.contents[id as usize]
     }
 
+    /// Gets the value registered with this numeric id, or None if nothing has been.
+    ///
+    /// Use this over `Self::from_numeric_id` for ids that can't be trusted, such as ones sent by clients
+    #[inline]
+    pub fn try_from_numeric_id(&self, id: u16) -> Option<&T> {
+        self.contents.get(id as usize)
+    }
+
     /// Gets the value that has been registered with that unlocalized name.
     ///
     /// Returns None if no value was found.
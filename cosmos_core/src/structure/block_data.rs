@@ -0,0 +1,156 @@
+//! Blocks can have extra data attached to them, such as a container's inventory or a sign's text.
+//!
+//! Each piece of data is stored under its type's [`BlockDataType::DATA_ID`], so one block can have many
+//! different types of data at the same time. All data for a block is removed when that block is changed.
+//!
+//! Some data should only ever be known by the server, such as what is inside a container. Mark those with
+//! [`PrivateBlockData`] so they are never sent to clients.
+
+use bevy::{
+    prelude::{App, Resource},
+    reflect::{FromReflect, Reflect},
+    utils::{HashMap, HashSet},
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{netty::cosmos_encoder, utils::array_utils::flatten};
+
+use super::chunk::CHUNK_DIMENSIONS;
+
+/// Something that can be stored on an individual block
+pub trait BlockDataType: Serialize + DeserializeOwned {
+    /// This should be unique for this type of data with the following formatting: `mod_id:data_name`. Such as: `cosmos:door_open`
+    const DATA_ID: &'static str;
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Reflect, FromReflect)]
+/// Every block in a chunk that has data attached to it is stored here
+pub struct BlockData {
+    /// Block index -> data id -> serialized data
+    block_data: HashMap<u32, HashMap<String, Vec<u8>>>,
+}
+
+impl BlockData {
+    #[inline]
+    fn index(&self, x: usize, y: usize, z: usize) -> u32 {
+        flatten(x, y, z, CHUNK_DIMENSIONS, CHUNK_DIMENSIONS) as u32
+    }
+
+    /// Gets the data stored on this block with this id
+    /// - x/y/z: block coordinate
+    pub(crate) fn get_raw(&self, x: usize, y: usize, z: usize, data_id: &str) -> Option<&[u8]> {
+        self.block_data
+            .get(&self.index(x, y, z))
+            .and_then(|data| data.get(data_id))
+            .map(|data| data.as_slice())
+    }
+
+    /// Deserializes the data stored on this block for this type.
+    ///
+    /// Returns None if there is no data or it couldn't be deserialized.
+    /// - x/y/z: block coordinate
+    pub(crate) fn get<T: BlockDataType>(&self, x: usize, y: usize, z: usize) -> Option<T> {
+        self.get_raw(x, y, z, T::DATA_ID)
+            .and_then(|data| cosmos_encoder::deserialize(data).ok())
+    }
+
+    /// Sets the data stored on this block with this id, overwriting any that is already there
+    /// - x/y/z: block coordinate
+    pub(crate) fn set_raw(&mut self, x: usize, y: usize, z: usize, data_id: &str, data: Vec<u8>) {
+        self.block_data
+            .entry(self.index(x, y, z))
+            .or_default()
+            .insert(data_id.to_owned(), data);
+    }
+
+    /// Removes the data stored on this block with this id.
+    ///
+    /// Returns true if there was data to remove
+    /// - x/y/z: block coordinate
+    pub(crate) fn remove_raw(&mut self, x: usize, y: usize, z: usize, data_id: &str) -> bool {
+        let index = self.index(x, y, z);
+
+        let Some(data) = self.block_data.get_mut(&index) else {
+            return false;
+        };
+
+        let removed = data.remove(data_id).is_some();
+
+        if data.is_empty() {
+            self.block_data.remove(&index);
+        }
+
+        removed
+    }
+
+    /// Removes all the data stored on this block
+    /// - x/y/z: block coordinate
+    pub(crate) fn clear(&mut self, x: usize, y: usize, z: usize) {
+        self.block_data.remove(&self.index(x, y, z));
+    }
+
+    /// Iterates over every piece of data stored on this block as (data id, data)
+    /// - x/y/z: block coordinate
+    pub(crate) fn all_raw(
+        &self,
+        x: usize,
+        y: usize,
+        z: usize,
+    ) -> impl Iterator<Item = (&str, &[u8])> + '_ {
+        self.block_data
+            .get(&self.index(x, y, z))
+            .into_iter()
+            .flat_map(|data| data.iter().map(|(id, data)| (id.as_str(), data.as_slice())))
+    }
+
+    /// Iterates over every block that has data of this type as (block index, data)
+    pub(crate) fn iter<T: BlockDataType>(&self) -> impl Iterator<Item = (u32, T)> + '_ {
+        self.block_data.iter().filter_map(|(index, data)| {
+            data.get(T::DATA_ID)
+                .and_then(|data| cosmos_encoder::deserialize(data).ok())
+                .map(|data| (*index, data))
+        })
+    }
+
+    /// Iterates over every piece of data in the chunk as (block index, data id, data)
+    pub(crate) fn entries(&self) -> impl Iterator<Item = (u32, &str, &[u8])> + '_ {
+        self.block_data.iter().flat_map(|(index, data)| {
+            data.iter()
+                .map(|(id, data)| (*index, id.as_str(), data.as_slice()))
+        })
+    }
+
+    /// Sets the data of the block at this index directly.
+    ///
+    /// Only use this to restore data that came from `entries`.
+    pub(crate) fn set_entry(&mut self, index: u32, data_id: String, data: Vec<u8>) {
+        self.block_data
+            .entry(index)
+            .or_default()
+            .insert(data_id, data);
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+/// Every type of block data that should never be sent to clients along with the rest of a chunk.
+///
+/// The server still stores & saves this data like any other - it just has to be sent to clients some other way.
+pub struct PrivateBlockData {
+    data_ids: HashSet<String>,
+}
+
+impl PrivateBlockData {
+    /// Marks this type of data as private
+    pub fn make_private<T: BlockDataType>(&mut self) {
+        self.data_ids.insert(T::DATA_ID.to_owned());
+    }
+
+    /// Checks if the data with this id is private
+    pub fn is_private(&self, data_id: &str) -> bool {
+        self.data_ids.contains(data_id)
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.init_resource::<PrivateBlockData>();
+}
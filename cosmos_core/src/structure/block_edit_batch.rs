@@ -0,0 +1,102 @@
+//! Used to change many blocks on a structure at once.
+//!
+//! See [`super::Structure::apply_block_edits`].
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    block::{blocks::AIR_BLOCK_ID, Block, BlockRotation},
+    registry::identifiable::Identifiable,
+};
+
+use super::structure_block::StructureBlock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// A single block change within a [`BlockEditBatch`]
+pub struct BlockEdit {
+    /// The block being changed
+    pub block: StructureBlock,
+    /// The block's new id
+    pub block_id: u16,
+    /// The block's new rotation
+    pub block_rotation: BlockRotation,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+/// A bunch of block changes that should be applied to a structure together.
+///
+/// Edits are applied in the order they are added, so if the same block is edited twice the last edit wins.
+pub struct BlockEditBatch {
+    edits: Vec<BlockEdit>,
+}
+
+impl BlockEditBatch {
+    /// Creates an empty batch
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues setting the block at these block coordinates
+    pub fn set_block_at(
+        &mut self,
+        x: usize,
+        y: usize,
+        z: usize,
+        block: &Block,
+        block_rotation: BlockRotation,
+    ) {
+        self.edits.push(BlockEdit {
+            block: StructureBlock::new(x, y, z),
+            block_id: block.id(),
+            block_rotation,
+        });
+    }
+
+    /// Queues removing the block at these block coordinates (setting it to air)
+    pub fn remove_block_at(&mut self, x: usize, y: usize, z: usize) {
+        self.edits.push(BlockEdit {
+            block: StructureBlock::new(x, y, z),
+            block_id: AIR_BLOCK_ID,
+            block_rotation: BlockRotation::IDENTITY,
+        });
+    }
+
+    /// Queues an edit
+    pub fn push(&mut self, edit: BlockEdit) {
+        self.edits.push(edit);
+    }
+
+    #[inline]
+    /// The number of edits queued
+    pub fn len(&self) -> usize {
+        self.edits.len()
+    }
+
+    #[inline]
+    /// Returns true if there are no edits queued
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    /// Iterates over every edit in the order they were added
+    pub fn iter(&self) -> std::slice::Iter<BlockEdit> {
+        self.edits.iter()
+    }
+}
+
+impl IntoIterator for BlockEditBatch {
+    type Item = BlockEdit;
+    type IntoIter = std::vec::IntoIter<BlockEdit>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.edits.into_iter()
+    }
+}
+
+impl FromIterator<BlockEdit> for BlockEditBatch {
+    fn from_iter<T: IntoIterator<Item = BlockEdit>>(iter: T) -> Self {
+        Self {
+            edits: iter.into_iter().collect(),
+        }
+    }
+}
@@ -0,0 +1,19 @@
+//! Block health changed event
+
+use bevy::prelude::{App, Entity};
+
+use crate::structure::structure_block::StructureBlock;
+
+/// This event is sent when a block's health changes, such as when it takes damage
+pub struct BlockHealthChangedEvent {
+    /// The structure that had its block's health changed
+    pub structure_entity: Entity,
+    /// The block whose health changed
+    pub block: StructureBlock,
+    /// The block's new health
+    pub new_health: f32,
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_event::<BlockHealthChangedEvent>();
+}
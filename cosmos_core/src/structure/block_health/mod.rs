@@ -8,6 +8,7 @@ use bevy::{
 use serde::{Deserialize, Serialize};
 
 pub mod block_destroyed_event;
+pub mod block_health_changed_event;
 
 use crate::{block::hardness::BlockHardness, utils::array_utils::flatten};
 
@@ -53,7 +54,7 @@ impl BlockHealth {
     /// Sets the block's health at that specific coordinate
     /// - x/y/z: block coordinate
     /// - block_hardness: The hardness for the block at those coordinates
-    /// - value: Any value, is clamped to always be 0.0 or above.
+    /// - value: Any value, is clamped to always be between 0.0 and the block's hardness.
     pub(crate) fn set_health(
         &mut self,
         x: usize,
@@ -65,7 +66,7 @@ impl BlockHealth {
         debug_assert!(x < CHUNK_DIMENSIONS);
         debug_assert!(y < CHUNK_DIMENSIONS);
 
-        if block_hardness.hardness() == value {
+        if value >= block_hardness.hardness() {
             self.reset_health(x, y, z);
         } else {
             self.block_healths
@@ -95,8 +96,23 @@ impl BlockHealth {
 
         amount <= 0.0
     }
+
+    /// Iterates over every block that has taken damage as (block index, health)
+    pub(crate) fn damaged_blocks(&self) -> impl Iterator<Item = (u32, f32)> + '_ {
+        self.block_healths
+            .iter()
+            .map(|(index, health)| (*index, *health))
+    }
+
+    /// Sets the health of the block at this index directly, without checking its hardness.
+    ///
+    /// Only use this to restore health values that came from `damaged_blocks`.
+    pub(crate) fn set_damaged_block(&mut self, index: u32, health: f32) {
+        self.block_healths.insert(index, health);
+    }
 }
 
 pub(super) fn register(app: &mut App) {
     block_destroyed_event::register(app);
+    block_health_changed_event::register(app);
 }
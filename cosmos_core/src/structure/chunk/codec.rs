@@ -0,0 +1,604 @@
+//! A compact & versioned binary format for chunks.
+//!
+//! This is used whenever a chunk is sent over the network or saved, so changes to the layout of
+//! [`Chunk`] don't silently break old saves or clients.
+//!
+//! ## Format (version 2)
+//! All numbers are little endian.
+//! - [`CHUNK_MAGIC`] followed by the version (u8)
+//! - The chunk's x, y, and z position in its structure (u32 each)
+//! - Flags (u8), see [`FLAG_HAS_HEALTH`]
+//! - The palette length (u16), then every palette entry's block id (u16) + block info (u8)
+//! - The block section type (u8), followed by either
+//!   - [`SECTION_RUNS`]: The number of runs (u32), then every run's palette index (u16) + length (u16)
+//!   - [`SECTION_PACKED`]: Every block's palette index packed into the fewest bits that fit the palette
+//! - If [`FLAG_HAS_HEALTH`] is set, the number of damaged blocks (u32), then every block's index (u16) + health (f32)
+//! - If [`FLAG_HAS_BLOCK_DATA`] is set, the number of block data entries (u32), then every entry's block index (u16),
+//!   data id length (u16) + data id (utf8), and data length (u32) + data
+//!
+//! Version 1 is the same, but never has block data.
+//!
+//! Anything that doesn't start with [`CHUNK_MAGIC`] is read as a chunk from before this format existed.
+
+use std::fmt;
+
+use bevy::utils::{HashMap, HashSet};
+use serde::Deserialize;
+
+use crate::{
+    block::blocks::AIR_BLOCK_ID,
+    netty::cosmos_encoder,
+    structure::{
+        block_data::{BlockData, PrivateBlockData},
+        block_health::BlockHealth,
+        Structure,
+    },
+    utils::array_utils::expand,
+};
+
+use super::{
+    palette::{BlockPalette, PaletteEntry},
+    BlockInfo, Chunk, N_BLOCKS,
+};
+
+/// Every encoded chunk starts with these bytes
+pub const CHUNK_MAGIC: [u8; 4] = *b"CCNK";
+
+/// The version chunks are currently encoded with
+pub const CHUNK_CODEC_VERSION: u8 = 2;
+
+/// Set if the chunk has a block health section
+pub const FLAG_HAS_HEALTH: u8 = 0b1;
+/// Set if the chunk has a block data section (version 2+)
+pub const FLAG_HAS_BLOCK_DATA: u8 = 0b10;
+
+/// Blocks are stored as runs of the same palette entry
+pub const SECTION_RUNS: u8 = 0;
+/// Blocks are stored as bit-packed palette indices
+pub const SECTION_PACKED: u8 = 1;
+
+#[derive(Debug)]
+/// Returned when an encoded chunk could not be read
+pub enum ChunkDecodeError {
+    /// The data ended before the whole chunk was read
+    UnexpectedEnd,
+    /// The chunk was encoded with a version this build does not know about
+    UnsupportedVersion(u8),
+    /// The data is not a valid chunk
+    Malformed(&'static str),
+    /// The data is not in this format, and could not be read as a legacy chunk either
+    Legacy(Box<bincode::ErrorKind>),
+}
+
+impl fmt::Display for ChunkDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEnd => write!(f, "Chunk data ended unexpectedly"),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "Chunk was encoded with version {version}, but only versions up to {CHUNK_CODEC_VERSION} are supported"
+            ),
+            Self::Malformed(reason) => write!(f, "Malformed chunk data - {reason}"),
+            Self::Legacy(e) => write!(f, "Unable to read legacy chunk data - {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ChunkDecodeError {}
+
+/// Encodes this chunk in the latest version of the chunk format
+pub fn encode(chunk: &Chunk) -> Vec<u8> {
+    encode_filtered(chunk, |_| true)
+}
+
+/// Encodes this chunk to be sent to clients, leaving out any [`PrivateBlockData`]
+pub fn encode_for_clients(chunk: &Chunk, private_data: &PrivateBlockData) -> Vec<u8> {
+    encode_filtered(chunk, |data_id| !private_data.is_private(data_id))
+}
+
+/// Encodes this chunk in the latest version of the chunk format, only keeping the block data whose id passes `keep_data`
+fn encode_filtered(chunk: &Chunk, keep_data: impl Fn(&str) -> bool) -> Vec<u8> {
+    let mut palette = Vec::new();
+    let mut palette_lookup = HashMap::<PaletteEntry, u16>::new();
+
+    let indices = chunk
+        .blocks
+        .iter()
+        .map(|entry| {
+            *palette_lookup.entry(entry).or_insert_with(|| {
+                palette.push(entry);
+                (palette.len() - 1) as u16
+            })
+        })
+        .collect::<Vec<u16>>();
+
+    let mut runs: Vec<(u16, u16)> = Vec::new();
+    for &index in indices.iter() {
+        match runs.last_mut() {
+            Some((last, len)) if *last == index => *len += 1,
+            _ => runs.push((index, 1)),
+        }
+    }
+
+    let bits = bits_for_palette(palette.len());
+    let packed_len = (N_BLOCKS * bits + 7) / 8;
+
+    let mut bytes = Vec::with_capacity(32 + palette.len() * 3 + packed_len.min(runs.len() * 4));
+
+    bytes.extend_from_slice(&CHUNK_MAGIC);
+    bytes.push(CHUNK_CODEC_VERSION);
+    bytes.extend_from_slice(&(chunk.x as u32).to_le_bytes());
+    bytes.extend_from_slice(&(chunk.y as u32).to_le_bytes());
+    bytes.extend_from_slice(&(chunk.z as u32).to_le_bytes());
+
+    let mut damaged = chunk
+        .block_health
+        .damaged_blocks()
+        .collect::<Vec<(u32, f32)>>();
+
+    let mut block_data = chunk
+        .block_data
+        .entries()
+        .filter(|(_, id, _)| keep_data(id))
+        .collect::<Vec<(u32, &str, &[u8])>>();
+
+    let mut flags = 0;
+    if !damaged.is_empty() {
+        flags |= FLAG_HAS_HEALTH;
+    }
+    if !block_data.is_empty() {
+        flags |= FLAG_HAS_BLOCK_DATA;
+    }
+
+    bytes.push(flags);
+
+    bytes.extend_from_slice(&(palette.len() as u16).to_le_bytes());
+    for entry in palette.iter() {
+        bytes.extend_from_slice(&entry.block_id.to_le_bytes());
+        bytes.push(entry.block_info.0);
+    }
+
+    if runs.len() * 4 <= packed_len {
+        bytes.push(SECTION_RUNS);
+        bytes.extend_from_slice(&(runs.len() as u32).to_le_bytes());
+        for (index, len) in runs {
+            bytes.extend_from_slice(&index.to_le_bytes());
+            bytes.extend_from_slice(&len.to_le_bytes());
+        }
+    } else {
+        bytes.push(SECTION_PACKED);
+
+        let start = bytes.len();
+        bytes.resize(start + packed_len, 0);
+
+        for (i, index) in indices.into_iter().enumerate() {
+            for bit in 0..bits {
+                if (index >> bit) & 1 == 1 {
+                    let at = i * bits + bit;
+                    bytes[start + at / 8] |= 1 << (at % 8);
+                }
+            }
+        }
+    }
+
+    if !damaged.is_empty() {
+        // Keeps the output the same for identical chunks
+        damaged.sort_by_key(|(index, _)| *index);
+
+        bytes.extend_from_slice(&(damaged.len() as u32).to_le_bytes());
+        for (index, health) in damaged {
+            bytes.extend_from_slice(&(index as u16).to_le_bytes());
+            bytes.extend_from_slice(&health.to_le_bytes());
+        }
+    }
+
+    if !block_data.is_empty() {
+        block_data.sort_by_key(|(index, id, _)| (*index, *id));
+
+        bytes.extend_from_slice(&(block_data.len() as u32).to_le_bytes());
+        for (index, id, data) in block_data {
+            bytes.extend_from_slice(&(index as u16).to_le_bytes());
+            bytes.extend_from_slice(&(id.len() as u16).to_le_bytes());
+            bytes.extend_from_slice(id.as_bytes());
+            bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(data);
+        }
+    }
+
+    bytes
+}
+
+/// Decodes a chunk from any version of the chunk format, or from the legacy format
+/// (the `cosmos_encoder` output of the chunk before this format existed).
+pub fn decode(raw: &[u8]) -> Result<Chunk, ChunkDecodeError> {
+    if !raw.starts_with(&CHUNK_MAGIC) {
+        return cosmos_encoder::deserialize::<LegacyChunk>(raw)
+            .map(|legacy| legacy.into())
+            .map_err(ChunkDecodeError::Legacy);
+    }
+
+    let mut reader = Reader {
+        data: raw,
+        position: CHUNK_MAGIC.len(),
+    };
+
+    match reader.read_u8()? {
+        version @ 1..=CHUNK_CODEC_VERSION => decode_versioned(&mut reader, version),
+        version => Err(ChunkDecodeError::UnsupportedVersion(version)),
+    }
+}
+
+fn decode_versioned(reader: &mut Reader, version: u8) -> Result<Chunk, ChunkDecodeError> {
+    let x = reader.read_u32()? as usize;
+    let y = reader.read_u32()? as usize;
+    let z = reader.read_u32()? as usize;
+
+    let flags = reader.read_u8()?;
+
+    let palette_len = reader.read_u16()? as usize;
+    if palette_len == 0 {
+        return Err(ChunkDecodeError::Malformed("empty palette"));
+    }
+
+    let mut palette = Vec::with_capacity(palette_len);
+    for _ in 0..palette_len {
+        let block_id = reader.read_u16()?;
+        let block_info = BlockInfo(reader.read_u8()?);
+
+        palette.push(PaletteEntry::new(block_id, block_info));
+    }
+
+    let mut entries = Vec::with_capacity(N_BLOCKS);
+
+    match reader.read_u8()? {
+        SECTION_RUNS => {
+            let n_runs = reader.read_u32()?;
+
+            for _ in 0..n_runs {
+                let index = reader.read_u16()? as usize;
+                let len = reader.read_u16()? as usize;
+
+                let entry = *palette
+                    .get(index)
+                    .ok_or(ChunkDecodeError::Malformed("palette index out of bounds"))?;
+
+                if entries.len() + len > N_BLOCKS {
+                    return Err(ChunkDecodeError::Malformed("too many blocks"));
+                }
+
+                entries.extend(std::iter::repeat(entry).take(len));
+            }
+
+            if entries.len() != N_BLOCKS {
+                return Err(ChunkDecodeError::Malformed("too few blocks"));
+            }
+        }
+        SECTION_PACKED => {
+            let bits = bits_for_palette(palette_len);
+            let packed = reader.read_bytes((N_BLOCKS * bits + 7) / 8)?;
+
+            for i in 0..N_BLOCKS {
+                let mut index = 0;
+                for bit in 0..bits {
+                    let at = i * bits + bit;
+                    index |= (((packed[at / 8] >> (at % 8)) & 1) as usize) << bit;
+                }
+
+                entries.push(
+                    *palette
+                        .get(index)
+                        .ok_or(ChunkDecodeError::Malformed("palette index out of bounds"))?,
+                );
+            }
+        }
+        _ => return Err(ChunkDecodeError::Malformed("unknown block section")),
+    }
+
+    let mut block_health = BlockHealth::default();
+
+    if flags & FLAG_HAS_HEALTH != 0 {
+        let n_damaged = reader.read_u32()?;
+
+        for _ in 0..n_damaged {
+            let index = reader.read_u16()? as u32;
+            let health = reader.read_f32()?;
+
+            block_health.set_damaged_block(index, health);
+        }
+    }
+
+    let mut block_data = BlockData::default();
+
+    if version >= 2 && flags & FLAG_HAS_BLOCK_DATA != 0 {
+        let n_entries = reader.read_u32()?;
+
+        for _ in 0..n_entries {
+            let index = reader.read_u16()? as u32;
+
+            let id_len = reader.read_u16()? as usize;
+            let id = std::str::from_utf8(reader.read_bytes(id_len)?)
+                .map_err(|_| ChunkDecodeError::Malformed("block data id is not utf8"))?;
+
+            let data_len = reader.read_u32()? as usize;
+            let data = reader.read_bytes(data_len)?;
+
+            block_data.set_entry(index, id.to_owned(), data.to_vec());
+        }
+    }
+
+    Ok(build_chunk(
+        x,
+        y,
+        z,
+        entries.into_iter(),
+        block_health,
+        block_data,
+    ))
+}
+
+fn build_chunk(
+    x: usize,
+    y: usize,
+    z: usize,
+    entries: impl Iterator<Item = PaletteEntry>,
+    block_health: BlockHealth,
+    block_data: BlockData,
+) -> Chunk {
+    let mut blocks = BlockPalette::new(N_BLOCKS, PaletteEntry::default());
+    let mut non_air_blocks = 0;
+
+    for (i, entry) in entries.enumerate() {
+        if entry.block_id != AIR_BLOCK_ID {
+            non_air_blocks += 1;
+        }
+
+        blocks.set(i, entry);
+    }
+
+    Chunk {
+        x,
+        y,
+        z,
+        blocks,
+        block_health,
+        block_data,
+        non_air_blocks,
+    }
+}
+
+/// The bits each block needs in a packed section for a palette of this size
+fn bits_for_palette(palette_len: usize) -> usize {
+    if palette_len <= 1 {
+        0
+    } else {
+        (usize::BITS - (palette_len - 1).leading_zeros()) as usize
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn read_bytes(&mut self, amount: usize) -> Result<&'a [u8], ChunkDecodeError> {
+        let bytes = self
+            .data
+            .get(self.position..self.position + amount)
+            .ok_or(ChunkDecodeError::UnexpectedEnd)?;
+
+        self.position += amount;
+
+        Ok(bytes)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, ChunkDecodeError> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_u16(&mut self) -> Result<u16, ChunkDecodeError> {
+        let bytes = self.read_bytes(2)?;
+
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn read_u32(&mut self) -> Result<u32, ChunkDecodeError> {
+        let bytes = self.read_bytes(4)?;
+
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn read_f32(&mut self) -> Result<f32, ChunkDecodeError> {
+        Ok(f32::from_bits(self.read_u32()?))
+    }
+}
+
+#[derive(Deserialize)]
+/// How chunks were serialized before this format existed
+struct LegacyChunk {
+    x: usize,
+    y: usize,
+    z: usize,
+    blocks: Vec<u16>,
+    block_info: Vec<BlockInfo>,
+    block_health: BlockHealth,
+    _non_air_blocks: usize,
+}
+
+impl From<LegacyChunk> for Chunk {
+    fn from(legacy: LegacyChunk) -> Self {
+        build_chunk(
+            legacy.x,
+            legacy.y,
+            legacy.z,
+            legacy
+                .blocks
+                .into_iter()
+                .zip(legacy.block_info)
+                .map(|(block_id, block_info)| PaletteEntry::new(block_id, block_info)),
+            legacy.block_health,
+            BlockData::default(),
+        )
+    }
+}
+
+#[derive(Deserialize)]
+/// How structures were serialized before chunks used this format
+struct LegacyStructure {
+    chunks: HashMap<usize, LegacyChunk>,
+    empty_chunks: HashSet<usize>,
+    width: usize,
+    height: usize,
+    length: usize,
+}
+
+/// Reads a structure that was serialized (via `cosmos_encoder`) before chunks used this format.
+///
+/// Use this as a fallback when a saved structure can't be deserialized normally.
+pub fn decode_legacy_structure(raw: &[u8]) -> Result<Structure, ChunkDecodeError> {
+    let legacy =
+        cosmos_encoder::deserialize::<LegacyStructure>(raw).map_err(ChunkDecodeError::Legacy)?;
+
+    let mut structure = Structure::new(legacy.width, legacy.height, legacy.length);
+
+    for (_, chunk) in legacy.chunks {
+        structure.set_chunk(chunk.into());
+    }
+
+    for index in legacy.empty_chunks {
+        let (cx, cy, cz) = expand(index, legacy.width, legacy.height);
+
+        structure.set_to_empty_chunk(cx, cy, cz);
+    }
+
+    Ok(structure)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        block::{BlockFace, BlockRotation, BlockSubRotation},
+        structure::block_data::BlockDataType,
+    };
+
+    use super::*;
+
+    fn test_chunk() -> Chunk {
+        let mut info = BlockInfo::default();
+        info.set_rotation(BlockRotation::new(BlockFace::Front, BlockSubRotation::CCW));
+
+        build_chunk(
+            1,
+            2,
+            3,
+            (0..N_BLOCKS).map(|i| PaletteEntry::new((i % 3) as u16, info)),
+            BlockHealth::default(),
+            BlockData::default(),
+        )
+    }
+
+    #[test]
+    fn round_trip_packed() {
+        let mut chunk = test_chunk();
+        chunk.block_health.set_damaged_block(5, 3.5);
+
+        let decoded = decode(&encode(&chunk)).expect("Failed to decode chunk");
+
+        assert_eq!(
+            (decoded.x, decoded.y, decoded.z),
+            (chunk.x, chunk.y, chunk.z)
+        );
+        assert_eq!(decoded.non_air_blocks, chunk.non_air_blocks);
+        assert!(decoded.blocks.iter().eq(chunk.blocks.iter()));
+        assert_eq!(
+            decoded.block_health.damaged_blocks().collect::<Vec<_>>(),
+            vec![(5, 3.5)]
+        );
+    }
+
+    #[test]
+    fn round_trip_runs() {
+        let chunk = Chunk::new(4, 5, 6);
+
+        let encoded = encode(&chunk);
+        let decoded = decode(&encoded).expect("Failed to decode chunk");
+
+        assert!(decoded.is_empty());
+        assert_eq!(decoded.structure_z(), 6);
+        // Only the header + palette, since a single palette entry needs no indices
+        assert!(encoded.len() < 40);
+    }
+
+    #[test]
+    fn round_trip_block_data() {
+        let mut chunk = test_chunk();
+        chunk
+            .block_data
+            .set_entry(7, "cosmos:a".into(), vec![1, 2, 3]);
+        chunk.block_data.set_entry(7, "cosmos:b".into(), vec![]);
+
+        let decoded = decode(&encode(&chunk)).expect("Failed to decode chunk");
+
+        let mut entries = decoded.block_data.entries().collect::<Vec<_>>();
+        entries.sort();
+
+        assert_eq!(
+            entries,
+            vec![
+                (7, "cosmos:a", [1, 2, 3].as_slice()),
+                (7, "cosmos:b", [0_u8; 0].as_slice())
+            ]
+        );
+    }
+
+    #[test]
+    fn private_block_data_is_not_sent_to_clients() {
+        #[derive(serde::Serialize, Deserialize)]
+        struct Secret;
+
+        impl BlockDataType for Secret {
+            const DATA_ID: &'static str = "cosmos:a";
+        }
+
+        let mut chunk = test_chunk();
+        chunk
+            .block_data
+            .set_entry(7, "cosmos:a".into(), vec![1, 2, 3]);
+        chunk.block_data.set_entry(7, "cosmos:b".into(), vec![4]);
+
+        let mut private_data = PrivateBlockData::default();
+        private_data.make_private::<Secret>();
+
+        let decoded =
+            decode(&encode_for_clients(&chunk, &private_data)).expect("Failed to decode chunk");
+
+        assert_eq!(
+            decoded.block_data.entries().collect::<Vec<_>>(),
+            vec![(7, "cosmos:b", [4_u8].as_slice())]
+        );
+    }
+
+    #[test]
+    fn reads_version_1() {
+        let chunk = test_chunk();
+
+        // Without block data, the only difference from version 1 is the version number
+        let mut encoded = encode(&chunk);
+        encoded[CHUNK_MAGIC.len()] = 1;
+
+        let decoded = decode(&encoded).expect("Failed to decode chunk");
+
+        assert!(decoded.blocks.iter().eq(chunk.blocks.iter()));
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let mut encoded = encode(&Chunk::new(0, 0, 0));
+        encoded[CHUNK_MAGIC.len()] = CHUNK_CODEC_VERSION + 1;
+
+        assert!(matches!(
+            decode(&encoded),
+            Err(ChunkDecodeError::UnsupportedVersion(_))
+        ));
+    }
+}
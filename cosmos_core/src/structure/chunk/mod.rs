@@ -0,0 +1,415 @@
+//! Represents a fixed region of blocks.
+//!
+//! These blocks can be updated.
+
+use crate::block::blocks::AIR_BLOCK_ID;
+use crate::block::hardness::BlockHardness;
+use crate::block::{Block, BlockFace, BlockRotation, BlockSubRotation};
+use crate::inventory::Inventory;
+use crate::netty::cosmos_encoder;
+use crate::registry::id_map::IdRemap;
+use crate::registry::identifiable::Identifiable;
+use crate::registry::Registry;
+use crate::utils::array_utils::{expand, flatten};
+use bevy::prelude::{Component, Entity, Vec3};
+use bevy::reflect::{FromReflect, Reflect};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use self::palette::{BlockPalette, PaletteEntry};
+
+use super::block_data::{BlockData, BlockDataType};
+use super::block_health::BlockHealth;
+
+pub mod codec;
+pub mod palette;
+
+/// The number of blocks a chunk can have in the x/y/z directions.
+///
+/// A chunk contains `CHUNK_DIMENSIONS`^3 blocks total.
+pub const CHUNK_DIMENSIONS: usize = 32;
+
+/// Short for `CHUNK_DIMENSIONS as f32`
+pub const CHUNK_DIMENSIONSF: f32 = CHUNK_DIMENSIONS as f32;
+
+/// The number of blocks a chunk contains (`CHUNK_DIMENSIONS^3`)
+const N_BLOCKS: usize = CHUNK_DIMENSIONS * CHUNK_DIMENSIONS * CHUNK_DIMENSIONS;
+
+#[derive(Debug, Reflect, FromReflect)]
+/// Stores a bunch of blocks, information about those blocks, and where they are in the structure.
+///
+/// This is serialized using the format in [`codec`].
+pub struct Chunk {
+    x: usize,
+    y: usize,
+    z: usize,
+    blocks: BlockPalette,
+
+    block_health: BlockHealth,
+    block_data: BlockData,
+
+    non_air_blocks: usize,
+}
+
+impl Chunk {
+    /// Creates a chunk containing all air blocks.
+    ///
+    /// * `x` The x chunk location in the structure
+    /// * `y` The y chunk location in the structure
+    /// * `z` The z chunk location in the structure
+    pub fn new(x: usize, y: usize, z: usize) -> Self {
+        Self {
+            x,
+            y,
+            z,
+            blocks: BlockPalette::new(N_BLOCKS, PaletteEntry::default()),
+            block_health: BlockHealth::default(),
+            block_data: BlockData::default(),
+            non_air_blocks: 0,
+        }
+    }
+
+    #[inline]
+    /// The position in the structure x
+    pub fn structure_x(&self) -> usize {
+        self.x
+    }
+
+    #[inline]
+    /// The position in the structure y
+    pub fn structure_y(&self) -> usize {
+        self.y
+    }
+
+    #[inline]
+    /// The position in the structure z
+    pub fn structure_z(&self) -> usize {
+        self.z
+    }
+
+    /// Moves this chunk to a different position in its structure.
+    ///
+    /// Only used when the structure is resized & its chunks are shifted.
+    pub(crate) fn set_structure_position(&mut self, x: usize, y: usize, z: usize) {
+        self.x = x;
+        self.y = y;
+        self.z = z;
+    }
+
+    #[inline]
+    /// Returns true if this chunk only contains air
+    pub fn is_empty(&self) -> bool {
+        self.non_air_blocks == 0
+    }
+
+    /// Sets the block at the given location.
+    ///
+    /// Generally, you should use the structure's version of this because this doesn't handle everything the structure does.
+    /// You should only call this if you know what you're doing.
+    ///
+    /// No events are generated from this.
+    pub fn set_block_at(
+        &mut self,
+        x: usize,
+        y: usize,
+        z: usize,
+        b: &Block,
+        block_rotation: BlockRotation,
+    ) {
+        debug_assert!(x < CHUNK_DIMENSIONS);
+        debug_assert!(y < CHUNK_DIMENSIONS);
+        debug_assert!(z < CHUNK_DIMENSIONS);
+        let index = flatten(x, y, z, CHUNK_DIMENSIONS, CHUNK_DIMENSIONS);
+        let id = b.id();
+
+        self.block_health.reset_health(x, y, z);
+
+        let mut block_info = self.blocks.get(index).block_info;
+        block_info.set_rotation(block_rotation);
+
+        let old = self.blocks.set(index, PaletteEntry::new(id, block_info));
+
+        if old.block_id != id {
+            self.block_data.clear(x, y, z);
+
+            if old.block_id == AIR_BLOCK_ID {
+                self.non_air_blocks += 1;
+            } else if id == AIR_BLOCK_ID {
+                self.non_air_blocks -= 1;
+            }
+        }
+    }
+
+    /// Translates every block id in this chunk from the ids of an older block registry into the current ones.
+    ///
+    /// No events are generated from this.
+    pub fn remap_block_ids(&mut self, remap: &IdRemap) {
+        self.blocks
+            .map_entries(|entry| PaletteEntry::new(remap.get(entry.block_id), entry.block_info));
+
+        self.non_air_blocks = self
+            .blocks
+            .iter()
+            .filter(|entry| entry.block_id != AIR_BLOCK_ID)
+            .count();
+    }
+
+    /// Translates the item ids of every inventory stored on this chunk's blocks from the ids of an older item registry into the current ones.
+    ///
+    /// No events are generated from this.
+    pub fn remap_item_ids(&mut self, remap: &IdRemap) {
+        let inventories = self.block_data_iter::<Inventory>().collect::<Vec<_>>();
+
+        for ((x, y, z), mut inventory) in inventories {
+            inventory.remap_item_ids(remap);
+            self.set_block_data(x, y, z, &inventory);
+        }
+    }
+
+    #[inline]
+    /// Returns true if the block at this location is see-through. This is not determined from the block's texture, but
+    /// rather the flags the block was constructed with.
+    pub fn has_see_through_block_at(
+        &self,
+        x: usize,
+        y: usize,
+        z: usize,
+        blocks: &Registry<Block>,
+    ) -> bool {
+        blocks
+            .from_numeric_id(self.block_at(x, y, z))
+            .is_see_through()
+    }
+
+    #[inline]
+    /// Returns true if the block at this location is not air.
+    pub fn has_block_at(&self, x: usize, y: usize, z: usize) -> bool {
+        self.block_at(x, y, z) != AIR_BLOCK_ID
+    }
+
+    #[inline]
+    /// Gets the block at this location. Air is returned for empty blocks.
+    pub fn block_at(&self, x: usize, y: usize, z: usize) -> u16 {
+        self.blocks
+            .get(flatten(x, y, z, CHUNK_DIMENSIONS, CHUNK_DIMENSIONS))
+            .block_id
+    }
+
+    #[inline]
+    /// Gets the block's rotation at this location
+    pub fn block_rotation(&self, x: usize, y: usize, z: usize) -> BlockRotation {
+        self.blocks
+            .get(flatten(x, y, z, CHUNK_DIMENSIONS, CHUNK_DIMENSIONS))
+            .block_info
+            .get_rotation()
+    }
+
+    #[inline]
+    /// Returns true if the block at these coordinates is a full block (1x1x1 cube). This is not determined
+    /// by the model, but rather the flags the block is constructed with.
+    pub fn has_full_block_at(
+        &self,
+        x: usize,
+        y: usize,
+        z: usize,
+        blocks: &Registry<Block>,
+    ) -> bool {
+        blocks.from_numeric_id(self.block_at(x, y, z)).is_full()
+    }
+
+    /// Calculates the block coordinates used in something like `Chunk::block_at` from their f32 coordinates relative to the chunk's center.
+    pub fn relative_coords_to_block_coords(&self, relative: &Vec3) -> (usize, usize, usize) {
+        (
+            (relative.x + CHUNK_DIMENSIONS as f32 / 2.0) as usize,
+            (relative.y + CHUNK_DIMENSIONS as f32 / 2.0) as usize,
+            (relative.z + CHUNK_DIMENSIONS as f32 / 2.0) as usize,
+        )
+    }
+
+    /// Gets the block's health at that given coordinate
+    /// * `x/y/z`: block coordinate
+    /// * `block_hardness`: The hardness for the block at those coordinates
+    pub fn get_block_health(
+        &self,
+        x: usize,
+        y: usize,
+        z: usize,
+        block_hardness: &BlockHardness,
+    ) -> f32 {
+        self.block_health.get_health(x, y, z, block_hardness)
+    }
+
+    /// Sets a block's health at the given coordinates
+    ///
+    /// * `x/y/z` Block coordinates
+    /// * `block_hardness` The hardness for that block
+    /// * `health` The block's new health - clamped to always be 0.0 or above
+    pub fn set_block_health(
+        &mut self,
+        x: usize,
+        y: usize,
+        z: usize,
+        block_hardness: &BlockHardness,
+        health: f32,
+    ) {
+        self.block_health
+            .set_health(x, y, z, block_hardness, health);
+    }
+
+    /// Causes a block at the given coordinates to take damage
+    ///
+    /// * `x/y/z` Block coordinates
+    /// * `block_hardness` The hardness for that block
+    /// * `amount` The amount of damage to take - cannot be negative
+    ///
+    /// **Returns:** true if that block was destroyed, false if not
+    pub fn block_take_damage(
+        &mut self,
+        x: usize,
+        y: usize,
+        z: usize,
+        block_hardness: &BlockHardness,
+        amount: f32,
+    ) -> bool {
+        self.block_health
+            .take_damage(x, y, z, block_hardness, amount)
+    }
+
+    /// Iterates over every block in this chunk that has taken damage as ((x, y, z), health)
+    pub fn damaged_blocks_iter(&self) -> impl Iterator<Item = ((usize, usize, usize), f32)> + '_ {
+        self.block_health.damaged_blocks().map(|(index, health)| {
+            (
+                expand(index as usize, CHUNK_DIMENSIONS, CHUNK_DIMENSIONS),
+                health,
+            )
+        })
+    }
+
+    /// Gets the data of this type stored on the block at these coordinates.
+    ///
+    /// Returns None if there is no data or it couldn't be deserialized.
+    pub fn block_data<T: BlockDataType>(&self, x: usize, y: usize, z: usize) -> Option<T> {
+        self.block_data.get(x, y, z)
+    }
+
+    /// Stores this data on the block at these coordinates, replacing any data of the same type.
+    ///
+    /// The data is removed when the block is changed.
+    pub fn set_block_data<T: BlockDataType>(&mut self, x: usize, y: usize, z: usize, data: &T) {
+        self.block_data
+            .set_raw(x, y, z, T::DATA_ID, cosmos_encoder::serialize(data));
+    }
+
+    /// Removes the data of this type stored on the block at these coordinates.
+    ///
+    /// **Returns:** true if there was data to remove
+    pub fn remove_block_data<T: BlockDataType>(&mut self, x: usize, y: usize, z: usize) -> bool {
+        self.block_data.remove_raw(x, y, z, T::DATA_ID)
+    }
+
+    /// Gets the serialized data stored on the block at these coordinates with this data id
+    pub fn raw_block_data(&self, x: usize, y: usize, z: usize, data_id: &str) -> Option<&[u8]> {
+        self.block_data.get_raw(x, y, z, data_id)
+    }
+
+    /// Sets (or removes if `data` is None) the serialized data stored on the block at these coordinates with this data id
+    pub fn set_raw_block_data(
+        &mut self,
+        x: usize,
+        y: usize,
+        z: usize,
+        data_id: &str,
+        data: Option<Vec<u8>>,
+    ) {
+        if let Some(data) = data {
+            self.block_data.set_raw(x, y, z, data_id, data);
+        } else {
+            self.block_data.remove_raw(x, y, z, data_id);
+        }
+    }
+
+    /// Iterates over every piece of data stored on the block at these coordinates as (data id, serialized data)
+    pub fn all_raw_block_data(
+        &self,
+        x: usize,
+        y: usize,
+        z: usize,
+    ) -> impl Iterator<Item = (&str, &[u8])> + '_ {
+        self.block_data.all_raw(x, y, z)
+    }
+
+    /// Iterates over every block in this chunk that has data of this type as ((x, y, z), data)
+    pub fn block_data_iter<T: BlockDataType>(
+        &self,
+    ) -> impl Iterator<Item = ((usize, usize, usize), T)> + '_ {
+        self.block_data.iter().map(|(index, data)| {
+            (
+                expand(index as usize, CHUNK_DIMENSIONS, CHUNK_DIMENSIONS),
+                data,
+            )
+        })
+    }
+
+    /// Returns the iterator for every block in the chunk
+    pub fn blocks(&self) -> impl Iterator<Item = u16> + '_ {
+        self.blocks.iter().map(|x| x.block_id)
+    }
+
+    /// Returns the iterator for all the block info of the chunk
+    pub fn block_info_iterator(&self) -> impl Iterator<Item = BlockInfo> + '_ {
+        self.blocks.iter().map(|x| x.block_info)
+    }
+}
+
+impl Serialize for Chunk {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&codec::encode(self))
+    }
+}
+
+impl<'de> Deserialize<'de> for Chunk {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = Vec::<u8>::deserialize(deserializer)?;
+
+        codec::decode(&bytes).map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(
+    Debug, Default, Reflect, FromReflect, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash,
+)]
+/// This represents the information for a block. The first 5 bits are reserved for rotation data.
+///
+/// All other bits can be used for anything else
+pub struct BlockInfo(u8);
+
+impl BlockInfo {
+    #[inline]
+    /// Gets the rotation data
+    ///
+    /// The first 3 bits are the BlockFace that represents the UP direction, and the next 2 are the sub rotation.
+    /// No rotation is [`BlockRotation::IDENTITY`].
+    pub fn get_rotation(&self) -> BlockRotation {
+        BlockRotation::new(
+            BlockFace::from_index((self.0 & 0b111) as usize),
+            BlockSubRotation::from_index(((self.0 >> 3) & 0b11) as usize),
+        )
+    }
+
+    /// Sets the rotation data
+    ///
+    /// No rotation is [`BlockRotation::IDENTITY`].
+    pub fn set_rotation(&mut self, rotation: BlockRotation) {
+        self.0 = self.0 & !0b11111
+            | rotation.block_up.index() as u8
+            | (rotation.sub_rotation.index() as u8) << 3;
+    }
+}
+
+/// Represents a child of a structure that represents a chunk
+#[derive(Debug, Reflect, FromReflect, Component)]
+pub struct ChunkEntity {
+    /// The entity of the structure this is a part of
+    pub structure_entity: Entity,
+    /// The chunk's position in the structure (x, y, z)
+    pub chunk_location: (usize, usize, usize),
+}
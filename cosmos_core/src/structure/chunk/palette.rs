@@ -0,0 +1,294 @@
+//! Compact storage for the blocks within a chunk.
+//!
+//! Instead of storing a block id + block info for every single block, every unique combination of those
+//! is stored once in a palette, and each block only stores a bit-packed index into that palette.
+//!
+//! Most chunks only contain a handful of different blocks, so this is far smaller than storing everything.
+//! The number of bits used per index grows automatically as more unique blocks are added.
+
+use bevy::reflect::{FromReflect, Reflect};
+use serde::{Deserialize, Serialize};
+
+use super::BlockInfo;
+
+#[derive(
+    Debug, Default, Reflect, FromReflect, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash,
+)]
+/// A single unique block stored in a palette
+pub struct PaletteEntry {
+    /// The block's numeric id
+    pub block_id: u16,
+    /// The block's info (rotation, etc)
+    pub block_info: BlockInfo,
+}
+
+impl PaletteEntry {
+    /// Creates a new palette entry
+    pub fn new(block_id: u16, block_info: BlockInfo) -> Self {
+        Self {
+            block_id,
+            block_info,
+        }
+    }
+}
+
+#[derive(Debug, Reflect, FromReflect, Serialize, Deserialize, Clone)]
+/// Stores a fixed amount of blocks as indices into a palette of unique blocks.
+pub struct BlockPalette {
+    len: usize,
+    palette: Vec<PaletteEntry>,
+    /// How many blocks use each palette entry. An entry with 0 references can be reused.
+    references: Vec<u32>,
+    /// 0 means every block is the first palette entry, so `data` is empty.
+    bits_per_index: u8,
+    data: Vec<u64>,
+}
+
+/// The number of bits needed to store an index for a palette of this size
+fn bits_needed(palette_len: usize) -> u8 {
+    if palette_len <= 1 {
+        0
+    } else {
+        (usize::BITS - (palette_len - 1).leading_zeros()) as u8
+    }
+}
+
+/// Indices never span two words, so some bits at the end of each word may be unused
+fn words_needed(len: usize, bits_per_index: u8) -> usize {
+    if bits_per_index == 0 {
+        0
+    } else {
+        let per_word = 64 / bits_per_index as usize;
+
+        (len + per_word - 1) / per_word
+    }
+}
+
+impl BlockPalette {
+    /// Creates a palette storage of `len` blocks, all set to `fill`.
+    pub fn new(len: usize, fill: PaletteEntry) -> Self {
+        Self {
+            len,
+            palette: vec![fill],
+            references: vec![len as u32],
+            bits_per_index: 0,
+            data: Vec::new(),
+        }
+    }
+
+    #[inline]
+    /// The number of blocks stored
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    /// Returns true if this stores no blocks
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline]
+    /// The number of bits each block currently uses to store its palette index
+    pub fn bits_per_index(&self) -> u8 {
+        self.bits_per_index
+    }
+
+    /// Iterates over every unique block currently in use
+    pub fn unique_entries(&self) -> impl Iterator<Item = PaletteEntry> + '_ {
+        self.palette
+            .iter()
+            .zip(self.references.iter())
+            .filter(|(_, references)| **references != 0)
+            .map(|(entry, _)| *entry)
+    }
+
+    #[inline]
+    fn palette_index(&self, index: usize) -> usize {
+        debug_assert!(index < self.len);
+
+        if self.bits_per_index == 0 {
+            return 0;
+        }
+
+        let bits = self.bits_per_index as usize;
+        let per_word = 64 / bits;
+        let shift = (index % per_word) * bits;
+
+        ((self.data[index / per_word] >> shift) & ((1 << bits) - 1)) as usize
+    }
+
+    #[inline]
+    fn set_palette_index(&mut self, index: usize, palette_index: usize) {
+        let bits = self.bits_per_index as usize;
+        let per_word = 64 / bits;
+        let shift = (index % per_word) * bits;
+        let mask = ((1u64 << bits) - 1) << shift;
+
+        let word = &mut self.data[index / per_word];
+        *word = (*word & !mask) | ((palette_index as u64) << shift);
+    }
+
+    #[inline]
+    /// Gets the block stored at this index
+    pub fn get(&self, index: usize) -> PaletteEntry {
+        self.palette[self.palette_index(index)]
+    }
+
+    /// Sets the block at this index, growing the palette if needed.
+    ///
+    /// Returns the entry that was previously there.
+    pub fn set(&mut self, index: usize, entry: PaletteEntry) -> PaletteEntry {
+        let old_palette_index = self.palette_index(index);
+        let old_entry = self.palette[old_palette_index];
+
+        if old_entry == entry {
+            return old_entry;
+        }
+
+        self.references[old_palette_index] -= 1;
+
+        let palette_index = self.palette_index_for(entry);
+        self.references[palette_index] += 1;
+
+        if self.bits_per_index != 0 {
+            self.set_palette_index(index, palette_index);
+        }
+
+        old_entry
+    }
+
+    /// Changes every block stored by passing its palette entry through `f`.
+    ///
+    /// This only touches each palette entry once, unless `f` turns two different entries into the same one.
+    pub fn map_entries(&mut self, f: impl Fn(PaletteEntry) -> PaletteEntry) {
+        for entry in self.palette.iter_mut() {
+            *entry = f(*entry);
+        }
+
+        // Every entry has to stay unique, so any that are now the same get merged into the first of them
+        let merged_into = (0..self.palette.len())
+            .map(|i| {
+                self.palette[..i]
+                    .iter()
+                    .position(|x| *x == self.palette[i])
+                    .unwrap_or(i)
+            })
+            .collect::<Vec<usize>>();
+
+        if merged_into.iter().enumerate().all(|(i, x)| i == *x) {
+            return;
+        }
+
+        for index in 0..self.len {
+            let palette_index = self.palette_index(index);
+
+            if merged_into[palette_index] != palette_index {
+                self.set_palette_index(index, merged_into[palette_index]);
+            }
+        }
+
+        for (i, into) in merged_into.into_iter().enumerate() {
+            if i != into {
+                self.references[into] += self.references[i];
+                self.references[i] = 0;
+            }
+        }
+    }
+
+    /// Iterates over every block in order
+    pub fn iter(&self) -> impl Iterator<Item = PaletteEntry> + '_ {
+        (0..self.len).map(|i| self.get(i))
+    }
+
+    /// Finds or creates the palette entry for this block, resizing the index storage if the palette outgrows it.
+    fn palette_index_for(&mut self, entry: PaletteEntry) -> usize {
+        if let Some(i) = self.palette.iter().position(|x| *x == entry) {
+            // Unused entries can still be matched, their reference count will just go back up.
+            return i;
+        }
+
+        if let Some(i) = self.references.iter().position(|x| *x == 0) {
+            self.palette[i] = entry;
+            return i;
+        }
+
+        self.palette.push(entry);
+        self.references.push(0);
+
+        let needed = bits_needed(self.palette.len());
+        if needed > self.bits_per_index {
+            self.resize_indices(needed);
+        }
+
+        self.palette.len() - 1
+    }
+
+    fn resize_indices(&mut self, bits_per_index: u8) {
+        let indices = (0..self.len)
+            .map(|i| self.palette_index(i))
+            .collect::<Vec<usize>>();
+
+        self.bits_per_index = bits_per_index;
+        self.data = vec![0; words_needed(self.len, bits_per_index)];
+
+        for (i, palette_index) in indices.into_iter().enumerate() {
+            self.set_palette_index(i, palette_index);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn grows_and_keeps_blocks() {
+        let mut palette = BlockPalette::new(4096, PaletteEntry::default());
+
+        assert_eq!(palette.bits_per_index(), 0);
+
+        for i in 0..4096 {
+            palette.set(i, PaletteEntry::new((i % 20) as u16, BlockInfo::default()));
+        }
+
+        assert_eq!(palette.bits_per_index(), 5);
+
+        for i in 0..4096 {
+            assert_eq!(palette.get(i).block_id, (i % 20) as u16);
+        }
+    }
+
+    #[test]
+    fn reuses_unused_entries() {
+        let mut palette = BlockPalette::new(64, PaletteEntry::default());
+
+        palette.set(3, PaletteEntry::new(1, BlockInfo::default()));
+        palette.set(3, PaletteEntry::new(2, BlockInfo::default()));
+
+        assert_eq!(palette.unique_entries().count(), 2);
+        assert_eq!(palette.bits_per_index(), 1);
+        assert_eq!(palette.get(3).block_id, 2);
+        assert_eq!(palette.get(4).block_id, 0);
+    }
+
+    #[test]
+    fn merges_mapped_entries() {
+        let mut palette = BlockPalette::new(64, PaletteEntry::default());
+
+        for i in 0..64 {
+            palette.set(i, PaletteEntry::new((i % 4) as u16, BlockInfo::default()));
+        }
+
+        palette.map_entries(|x| PaletteEntry::new(x.block_id / 2, x.block_info));
+
+        assert_eq!(palette.unique_entries().count(), 2);
+
+        for i in 0..64 {
+            assert_eq!(palette.get(i).block_id, (i % 4) as u16 / 2);
+        }
+
+        palette.set(0, PaletteEntry::new(1, BlockInfo::default()));
+        assert_eq!(palette.unique_entries().count(), 2);
+    }
+}
@@ -0,0 +1,261 @@
+//! Used to find pieces of a structure that are no longer connected to the rest of it.
+//!
+//! Blocks are connected if they share a face.
+
+use bevy::utils::HashMap;
+
+use super::{structure_block::StructureBlock, Structure};
+
+const NEIGHBORS: [(i32, i32, i32); 6] = [
+    (1, 0, 0),
+    (-1, 0, 0),
+    (0, 1, 0),
+    (0, -1, 0),
+    (0, 0, 1),
+    (0, 0, -1),
+];
+
+/// A flood fill started from a single block
+struct Search {
+    /// Blocks that still need their neighbors checked
+    frontier: Vec<StructureBlock>,
+    /// Every block this search has claimed
+    found: Vec<StructureBlock>,
+}
+
+fn root(parents: &[usize], mut i: usize) -> usize {
+    while parents[i] != i {
+        i = parents[i];
+    }
+
+    i
+}
+
+fn neighbors(
+    structure: &Structure,
+    block: StructureBlock,
+) -> impl Iterator<Item = StructureBlock> + '_ {
+    NEIGHBORS.iter().filter_map(move |(dx, dy, dz)| {
+        let (x, y, z) = (
+            block.x as i32 + dx,
+            block.y as i32 + dy,
+            block.z as i32 + dz,
+        );
+
+        if x < 0 || y < 0 || z < 0 {
+            return None;
+        }
+
+        let (x, y, z) = (x as usize, y as usize, z as usize);
+
+        if structure.is_within_blocks(x, y, z) && structure.has_block_at(x, y, z) {
+            Some(StructureBlock::new(x, y, z))
+        } else {
+            None
+        }
+    })
+}
+
+/// Expands every unfinished search by one block, grouping together searches that run into each other
+fn explore(
+    structure: &Structure,
+    searches: &mut [Search],
+    owners: &mut HashMap<StructureBlock, usize>,
+    parents: &mut [usize],
+) {
+    for i in 0..searches.len() {
+        let Some(block) = searches[i].frontier.pop() else {
+            continue;
+        };
+
+        for neighbor in neighbors(structure, block) {
+            if let Some(&owner) = owners.get(&neighbor) {
+                let (a, b) = (root(parents, i), root(parents, owner));
+
+                if a != b {
+                    parents[b] = a;
+                }
+            } else {
+                owners.insert(neighbor, i);
+                searches[i].frontier.push(neighbor);
+                searches[i].found.push(neighbor);
+            }
+        }
+    }
+}
+
+/// Finds every piece of the structure that was cut off from the rest of it by these blocks being removed.
+///
+/// The piece containing the anchor (such as a ship's core) is treated as the rest of the structure and is never returned.
+/// If no piece has the anchor, the largest piece is used instead. To avoid walking the entire structure every time a
+/// block is removed, the pieces are flood filled at the same time, and searching stops as soon as only one piece is
+/// left unexplored - so that piece is assumed to be the largest, unless the anchor was found in another piece.
+///
+/// * `removed` The blocks that were removed. These should already be air in the structure.
+/// * `is_anchor` Checks if a block decides which piece stays the structure
+pub fn disconnected_fragments(
+    structure: &Structure,
+    removed: impl IntoIterator<Item = StructureBlock>,
+    is_anchor: impl Fn(StructureBlock) -> bool,
+) -> Vec<Vec<StructureBlock>> {
+    let mut owners = HashMap::<StructureBlock, usize>::default();
+    let mut searches = Vec::<Search>::new();
+
+    for block in removed {
+        for neighbor in neighbors(structure, block) {
+            if owners.contains_key(&neighbor) {
+                continue;
+            }
+
+            owners.insert(neighbor, searches.len());
+            searches.push(Search {
+                frontier: vec![neighbor],
+                found: vec![neighbor],
+            });
+        }
+    }
+
+    if searches.len() <= 1 {
+        return vec![];
+    }
+
+    // Searches that run into each other are part of the same piece, and are grouped together
+    let mut parents = (0..searches.len()).collect::<Vec<usize>>();
+
+    let unfinished_groups = |searches: &[Search], parents: &[usize]| {
+        let mut roots = searches
+            .iter()
+            .enumerate()
+            .filter(|(_, search)| !search.frontier.is_empty())
+            .map(|(i, _)| root(parents, i))
+            .collect::<Vec<usize>>();
+
+        roots.sort_unstable();
+        roots.dedup();
+
+        roots.len()
+    };
+
+    while unfinished_groups(&searches, &parents) > 1 {
+        explore(structure, &mut searches, &mut owners, &mut parents);
+    }
+
+    let unfinished_root = searches
+        .iter()
+        .position(|search| !search.frontier.is_empty())
+        .map(|i| root(&parents, i));
+
+    let anchored = (0..searches.len()).find(|&i| {
+        Some(root(&parents, i)) != unfinished_root
+            && searches[i].found.iter().any(|block| is_anchor(*block))
+    });
+
+    // The anchor's piece is staying, so the unexplored piece is being split off & needs to be fully explored
+    if anchored.is_some() {
+        while unfinished_groups(&searches, &parents) > 0 {
+            explore(structure, &mut searches, &mut owners, &mut parents);
+        }
+    }
+
+    if let Some(anchored) = anchored {
+        let anchored_group = root(&parents, anchored);
+        let mut fragments = HashMap::<usize, Vec<StructureBlock>>::default();
+
+        for (i, search) in searches.into_iter().enumerate() {
+            let group = root(&parents, i);
+
+            if group != anchored_group {
+                fragments.entry(group).or_default().extend(search.found);
+            }
+        }
+
+        return fragments.into_values().collect();
+    }
+
+    let mut pieces = HashMap::<usize, (bool, Vec<StructureBlock>)>::default();
+
+    for (i, search) in searches.into_iter().enumerate() {
+        let (finished, blocks) = pieces.entry(root(&parents, i)).or_insert((true, vec![]));
+
+        *finished = *finished && search.frontier.is_empty();
+        blocks.extend(search.found);
+    }
+
+    // If every piece was fully explored, the largest one is still the structure
+    let all_finished = pieces.values().all(|(finished, _)| *finished);
+
+    let mut fragments = pieces
+        .into_values()
+        .filter(|(finished, _)| *finished)
+        .map(|(_, blocks)| blocks)
+        .collect::<Vec<Vec<StructureBlock>>>();
+
+    if all_finished {
+        if let Some((largest, _)) = fragments.iter().enumerate().max_by_key(|(_, x)| x.len()) {
+            fragments.swap_remove(largest);
+        }
+    }
+
+    fragments
+}
+
+#[cfg(test)]
+mod test {
+    use crate::block::{Block, BlockRotation};
+
+    use super::*;
+
+    #[test]
+    fn splits_cut_line() {
+        let block = Block::new(&vec![], 1, "cosmos:test".into(), 1.0);
+
+        let mut structure = Structure::new(1, 1, 1);
+
+        for x in 0..20 {
+            structure.set_block_at_without_events(x, 0, 0, &block, BlockRotation::IDENTITY);
+        }
+
+        let air = Block::new(&vec![], 0, "cosmos:air".into(), 0.0);
+        structure.set_block_at_without_events(5, 0, 0, &air, BlockRotation::IDENTITY);
+
+        let fragments =
+            disconnected_fragments(&structure, [StructureBlock::new(5, 0, 0)], |_| false);
+
+        assert_eq!(fragments.len(), 1);
+        assert_eq!(fragments[0].len(), 5);
+        assert!(fragments[0].iter().all(|block| block.x < 5));
+    }
+
+    #[test]
+    fn anchored_piece_stays() {
+        let block = Block::new(&vec![], 1, "cosmos:test".into(), 1.0);
+
+        let mut structure = Structure::new(1, 1, 1);
+
+        for x in 0..20 {
+            structure.set_block_at_without_events(x, 0, 0, &block, BlockRotation::IDENTITY);
+        }
+
+        let air = Block::new(&vec![], 0, "cosmos:air".into(), 0.0);
+        structure.set_block_at_without_events(5, 0, 0, &air, BlockRotation::IDENTITY);
+
+        // The anchor is in the smaller piece, so the larger piece is split off instead
+        let fragments =
+            disconnected_fragments(&structure, [StructureBlock::new(5, 0, 0)], |block| {
+                block == StructureBlock::new(2, 0, 0)
+            });
+
+        assert_eq!(fragments.len(), 1);
+        assert_eq!(fragments[0].len(), 14);
+        assert!(fragments[0].iter().all(|block| block.x > 5));
+
+        // An anchor that isn't in any piece falls back to the largest piece
+        let fragments =
+            disconnected_fragments(&structure, [StructureBlock::new(5, 0, 0)], |block| {
+                block == StructureBlock::new(5, 0, 0)
+            });
+
+        assert_eq!(fragments.len(), 1);
+        assert_eq!(fragments[0].len(), 5);
+    }
+}
@@ -2,7 +2,10 @@
 
 use bevy::prelude::{App, Entity};
 
-use super::{structure_iterator::BlockIterator, Structure};
+use super::{
+    chunk::CHUNK_DIMENSIONS, structure_block::StructureBlock, structure_iterator::BlockIterator,
+    Structure,
+};
 
 /// This will be created once all chunks have been populated
 pub struct StructureLoadedEvent {
@@ -34,7 +37,35 @@ impl ChunkSetEvent {
     }
 }
 
+/// Sent whenever a structure grows via [`Structure::grow`].
+///
+/// By the time this is sent, every chunk + block in the structure has already been moved to its new coordinates.
+/// Anything that stores block or chunk coordinates for this structure should shift them by `negative` chunks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StructureResizedEvent {
+    /// The entity of the structure that was resized
+    pub structure_entity: Entity,
+    /// The number of chunks added before the old chunks on the x/y/z axes.
+    ///
+    /// Every existing chunk was shifted by this many chunks.
+    pub negative: (usize, usize, usize),
+    /// The number of chunks added after the old chunks on the x/y/z axes
+    pub positive: (usize, usize, usize),
+}
+
+impl StructureResizedEvent {
+    /// Returns where this block is now, given its coordinates from before the resize
+    pub fn shift_block(&self, block: StructureBlock) -> StructureBlock {
+        StructureBlock::new(
+            block.x + self.negative.0 * CHUNK_DIMENSIONS,
+            block.y + self.negative.1 * CHUNK_DIMENSIONS,
+            block.z + self.negative.2 * CHUNK_DIMENSIONS,
+        )
+    }
+}
+
 pub(super) fn register(app: &mut App) {
     app.add_event::<ChunkSetEvent>()
-        .add_event::<StructureLoadedEvent>();
+        .add_event::<StructureLoadedEvent>()
+        .add_event::<StructureResizedEvent>();
 }
@@ -8,11 +8,15 @@ use bevy::utils::{HashMap, HashSet};
 use bevy_rapier3d::prelude::PhysicsWorld;
 
 pub mod asteroid;
+pub mod block_data;
+pub mod block_edit_batch;
 pub mod block_health;
 pub mod chunk;
+pub mod connectivity;
 pub mod events;
 pub mod loading;
 pub mod planet;
+pub mod raycast;
 pub mod ship;
 pub mod structure_block;
 pub mod structure_builder;
@@ -21,24 +25,30 @@ pub mod systems;
 
 use crate::block::blocks::AIR_BLOCK_ID;
 use crate::block::hardness::BlockHardness;
-use crate::block::{Block, BlockFace};
+use crate::block::{Block, BlockRotation};
 use crate::ecs::NeedsDespawned;
-use crate::events::block_events::BlockChangedEvent;
-use crate::netty::NoSendEntity;
+use crate::events::block_events::{
+    BlockChange, BlockChangedBatchEvent, BlockChangedEvent, BlockDataChangedEvent,
+};
+use crate::netty::{cosmos_encoder, NoSendEntity};
 use crate::physics::location::Location;
+use crate::registry::id_map::IdRemap;
 use crate::registry::identifiable::Identifiable;
 use crate::registry::Registry;
 use crate::structure::chunk::{Chunk, CHUNK_DIMENSIONS};
-use crate::utils::array_utils::flatten;
+use crate::utils::array_utils::{expand, flatten};
 use bevy::prelude::{
-    BuildChildren, Commands, Component, Entity, EventReader, EventWriter, GlobalTransform,
-    IntoSystemConfig, PbrBundle, Query, States, Transform, Vec3,
+    BuildChildren, Children, Commands, Component, Entity, EventReader, EventWriter,
+    GlobalTransform, IntoSystemConfig, PbrBundle, Query, States, Transform, Vec3, With, Without,
 };
 use serde::{Deserialize, Serialize};
 
+use self::block_data::BlockDataType;
+use self::block_edit_batch::BlockEditBatch;
 use self::block_health::block_destroyed_event::BlockDestroyedEvent;
+use self::block_health::block_health_changed_event::BlockHealthChangedEvent;
 use self::chunk::ChunkEntity;
-use self::events::ChunkSetEvent;
+use self::events::{ChunkSetEvent, StructureResizedEvent};
 use self::structure_block::StructureBlock;
 use self::structure_iterator::{BlockIterator, ChunkIterator};
 
@@ -331,10 +341,10 @@ impl Structure {
         (xx.floor() as i32, yy.floor() as i32, zz.floor() as i32)
     }
 
-    /// Gets the block's up facing face at this location.
+    /// Gets the block's rotation at this location.
     ///
-    /// If no block was found, returns BlockFace::Top.
-    pub fn block_rotation(&self, x: usize, y: usize, z: usize) -> BlockFace {
+    /// If no block was found, returns BlockRotation::IDENTITY.
+    pub fn block_rotation(&self, x: usize, y: usize, z: usize) -> BlockRotation {
         self.chunk_at_block_coordinates(x, y, z)
             .map(|chunk| {
                 chunk.block_rotation(
@@ -343,7 +353,7 @@ impl Structure {
                     z % CHUNK_DIMENSIONS,
                 )
             })
-            .unwrap_or(BlockFace::Top)
+            .unwrap_or(BlockRotation::IDENTITY)
     }
 
     /// If the chunk is loaded, non-empty, returns the block at that coordinate.
@@ -399,7 +409,7 @@ impl Structure {
             y,
             z,
             blocks.from_numeric_id(AIR_BLOCK_ID),
-            BlockFace::Top,
+            BlockRotation::IDENTITY,
             blocks,
             event_writer,
         )
@@ -428,7 +438,7 @@ impl Structure {
         y: usize,
         z: usize,
         block: &Block,
-        block_up: BlockFace,
+        block_rotation: BlockRotation,
         blocks: &Registry<Block>,
         event_writer: Option<&mut EventWriter<BlockChangedEvent>>,
     ) {
@@ -444,12 +454,23 @@ impl Structure {
                     old_block,
                     structure_entity: self_entity,
                     block: StructureBlock::new(x, y, z),
-                    old_block_up: self.block_rotation(x, y, z),
-                    new_block_up: block_up,
+                    old_block_rotation: self.block_rotation(x, y, z),
+                    new_block_rotation: block_rotation,
                 });
             }
         }
 
+        self.set_block_at_without_events(x, y, z, block, block_rotation);
+    }
+
+    fn set_block_at_without_events(
+        &mut self,
+        x: usize,
+        y: usize,
+        z: usize,
+        block: &Block,
+        block_rotation: BlockRotation,
+    ) {
         let (bx, by, bz) = (
             x % CHUNK_DIMENSIONS,
             y % CHUNK_DIMENSIONS,
@@ -463,14 +484,89 @@ impl Structure {
         );
 
         if let Some(chunk) = self.mut_chunk_at_block_coordinates(x, y, z) {
-            chunk.set_block_at(bx, by, bz, block, block_up);
+            chunk.set_block_at(bx, by, bz, block, block_rotation);
 
             if chunk.is_empty() {
                 self.unload_chunk(cx, cy, cz);
             }
         } else if block.id() != AIR_BLOCK_ID {
             let chunk = self.create_chunk_at(cx, cy, cz);
-            chunk.set_block_at(bx, by, bz, block, block_up);
+            chunk.set_block_at(bx, by, bz, block, block_rotation);
+        }
+    }
+
+    /// Applies every edit in this batch to the structure.
+    ///
+    /// Instead of sending a `BlockChangedEvent` for every block, this sends a single `BlockChangedBatchEvent`
+    /// for every chunk that had blocks changed. Use this when changing a lot of blocks at once.
+    ///
+    /// * `event_writer` If this is `None`, no events will be generated.
+    pub fn apply_block_edits(
+        &mut self,
+        batch: BlockEditBatch,
+        blocks: &Registry<Block>,
+        event_writer: Option<&mut EventWriter<BlockChangedBatchEvent>>,
+    ) {
+        let mut chunk_changes = HashMap::<(usize, usize, usize), Vec<BlockChange>>::default();
+        // Where each block's change is in its chunk's list, so editing a block twice only creates one change
+        let mut change_indices = HashMap::<StructureBlock, usize>::default();
+
+        for edit in batch {
+            let (x, y, z) = edit.block.into();
+
+            let old_block = self.block_id_at(x, y, z);
+            let old_block_rotation = self.block_rotation(x, y, z);
+
+            if old_block == edit.block_id && old_block_rotation == edit.block_rotation {
+                continue;
+            }
+
+            self.set_block_at_without_events(
+                x,
+                y,
+                z,
+                blocks.from_numeric_id(edit.block_id),
+                edit.block_rotation,
+            );
+
+            let changes = chunk_changes.entry(edit.block.chunk_coords()).or_default();
+
+            if let Some(&i) = change_indices.get(&edit.block) {
+                changes[i].new_block = edit.block_id;
+                changes[i].new_block_rotation = edit.block_rotation;
+            } else {
+                change_indices.insert(edit.block, changes.len());
+
+                changes.push(BlockChange {
+                    block: edit.block,
+                    old_block,
+                    new_block: edit.block_id,
+                    old_block_rotation,
+                    new_block_rotation: edit.block_rotation,
+                });
+            }
+        }
+
+        let (Some(structure_entity), Some(event_writer)) = (self.self_entity, event_writer) else {
+            return;
+        };
+
+        for (chunk, changes) in chunk_changes {
+            // A block could have been changed back to what it was originally later in the batch
+            let changes = changes
+                .into_iter()
+                .filter(|c| {
+                    c.old_block != c.new_block || c.old_block_rotation != c.new_block_rotation
+                })
+                .collect::<Vec<BlockChange>>();
+
+            if !changes.is_empty() {
+                event_writer.send(BlockChangedBatchEvent {
+                    structure_entity,
+                    chunk,
+                    changes,
+                });
+            }
         }
     }
 
@@ -565,6 +661,40 @@ impl Structure {
         }
     }
 
+    /// Translates every block id in this structure from the ids of an older block registry into the current ones.
+    ///
+    /// Used when loading a structure that was saved with a different block registry.
+    ///
+    /// This does not trigger any events, so make sure to handle those properly.
+    pub fn remap_block_ids(&mut self, remap: &IdRemap) {
+        for chunk in self.chunks.values_mut() {
+            chunk.remap_block_ids(remap);
+        }
+
+        let emptied = self
+            .chunks
+            .iter()
+            .filter(|(_, chunk)| chunk.is_empty())
+            .map(|(i, _)| *i)
+            .collect::<Vec<usize>>();
+
+        for i in emptied {
+            self.chunks.remove(&i);
+            self.empty_chunks.insert(i);
+        }
+    }
+
+    /// Translates the item ids of every inventory stored on this structure's blocks from the ids of an older item registry into the current ones.
+    ///
+    /// Used when loading a structure that was saved with a different item registry.
+    ///
+    /// This does not trigger any events, so make sure to handle those properly.
+    pub fn remap_item_ids(&mut self, remap: &IdRemap) {
+        for chunk in self.chunks.values_mut() {
+            chunk.remap_item_ids(remap);
+        }
+    }
+
     /// Sets the chunk at this chunk location to be empty (all air).
     ///
     /// Used generally when loading stuff on client from server.
@@ -740,6 +870,8 @@ impl Structure {
     /// - x/y/z: Block coordinates
     /// - block_hardness: The hardness for that block
     /// - amount: The amount of damage to take - cannot be negative
+    /// - event_writer: If this is None, no event will be generated when the block is destroyed
+    /// - health_event_writer: If this is None, no event will be generated for the block's health changing
     ///
     /// Returns: true if that block was destroyed, false if not
     pub fn block_take_damage(
@@ -750,18 +882,31 @@ impl Structure {
         block_hardness: &BlockHardness,
         amount: f32,
         event_writer: Option<&mut EventWriter<BlockDestroyedEvent>>,
+        health_event_writer: Option<&mut EventWriter<BlockHealthChangedEvent>>,
     ) -> bool {
+        let self_entity = self.get_entity();
+
         if let Some(chunk) = self.mut_chunk_at_block_coordinates(bx, by, bz) {
-            let destroyed = chunk.block_take_damage(
+            let (x, y, z) = (
                 bx % CHUNK_DIMENSIONS,
                 by % CHUNK_DIMENSIONS,
                 bz % CHUNK_DIMENSIONS,
-                block_hardness,
-                amount,
             );
 
+            let destroyed = chunk.block_take_damage(x, y, z, block_hardness, amount);
+
+            if let (Some(structure_entity), Some(health_event_writer)) =
+                (self_entity, health_event_writer)
+            {
+                health_event_writer.send(BlockHealthChangedEvent {
+                    structure_entity,
+                    block: StructureBlock::new(bx, by, bz),
+                    new_health: chunk.get_block_health(x, y, z, block_hardness),
+                });
+            }
+
             if destroyed {
-                if let Some(structure_entity) = self.get_entity() {
+                if let Some(structure_entity) = self_entity {
                     if let Some(event_writer) = event_writer {
                         event_writer.send(BlockDestroyedEvent {
                             block: StructureBlock::new(bx, by, bz),
@@ -777,6 +922,194 @@ impl Structure {
         }
     }
 
+    /// Sets a block's health at the given coordinates
+    ///
+    /// - x/y/z: Block coordinates
+    /// - block_hardness: The hardness for that block
+    /// - health: The block's new health - clamped to always be 0.0 or above. Setting it to the block's hardness marks it as undamaged.
+    /// - event_writer: If this is None, no event will be generated
+    pub fn set_block_health(
+        &mut self,
+        bx: usize,
+        by: usize,
+        bz: usize,
+        block_hardness: &BlockHardness,
+        health: f32,
+        event_writer: Option<&mut EventWriter<BlockHealthChangedEvent>>,
+    ) {
+        let self_entity = self.get_entity();
+
+        let Some(chunk) = self.mut_chunk_at_block_coordinates(bx, by, bz) else {
+            return;
+        };
+
+        let (x, y, z) = (
+            bx % CHUNK_DIMENSIONS,
+            by % CHUNK_DIMENSIONS,
+            bz % CHUNK_DIMENSIONS,
+        );
+
+        chunk.set_block_health(x, y, z, block_hardness, health);
+
+        if let (Some(structure_entity), Some(event_writer)) = (self_entity, event_writer) {
+            event_writer.send(BlockHealthChangedEvent {
+                structure_entity,
+                block: StructureBlock::new(bx, by, bz),
+                new_health: chunk.get_block_health(x, y, z, block_hardness),
+            });
+        }
+    }
+
+    /// Gets the data of this type stored on the block at these coordinates.
+    ///
+    /// Returns None if there is no data or it couldn't be deserialized.
+    pub fn block_data<T: BlockDataType>(&self, x: usize, y: usize, z: usize) -> Option<T> {
+        self.chunk_at_block_coordinates(x, y, z).and_then(|chunk| {
+            chunk.block_data(
+                x % CHUNK_DIMENSIONS,
+                y % CHUNK_DIMENSIONS,
+                z % CHUNK_DIMENSIONS,
+            )
+        })
+    }
+
+    /// Gets the serialized data stored on the block at these coordinates with this data id
+    pub fn raw_block_data(&self, x: usize, y: usize, z: usize, data_id: &str) -> Option<&[u8]> {
+        self.chunk_at_block_coordinates(x, y, z).and_then(|chunk| {
+            chunk.raw_block_data(
+                x % CHUNK_DIMENSIONS,
+                y % CHUNK_DIMENSIONS,
+                z % CHUNK_DIMENSIONS,
+                data_id,
+            )
+        })
+    }
+
+    /// Iterates over every piece of data stored on the block at these coordinates as (data id, serialized data)
+    pub fn all_raw_block_data(
+        &self,
+        x: usize,
+        y: usize,
+        z: usize,
+    ) -> impl Iterator<Item = (&str, &[u8])> + '_ {
+        self.chunk_at_block_coordinates(x, y, z)
+            .into_iter()
+            .flat_map(move |chunk| {
+                chunk.all_raw_block_data(
+                    x % CHUNK_DIMENSIONS,
+                    y % CHUNK_DIMENSIONS,
+                    z % CHUNK_DIMENSIONS,
+                )
+            })
+    }
+
+    /// Iterates over every block in this structure that has taken damage as (block, health)
+    pub fn all_damaged_blocks(&self) -> impl Iterator<Item = (StructureBlock, f32)> + '_ {
+        self.chunks.values().flat_map(|chunk| {
+            let (cx, cy, cz) = (
+                chunk.structure_x() * CHUNK_DIMENSIONS,
+                chunk.structure_y() * CHUNK_DIMENSIONS,
+                chunk.structure_z() * CHUNK_DIMENSIONS,
+            );
+
+            chunk.damaged_blocks_iter().map(move |((x, y, z), health)| {
+                (StructureBlock::new(cx + x, cy + y, cz + z), health)
+            })
+        })
+    }
+
+    /// Iterates over every block in this structure that has data of this type
+    pub fn all_block_data<T: BlockDataType>(
+        &self,
+    ) -> impl Iterator<Item = (StructureBlock, T)> + '_ {
+        self.chunks.values().flat_map(|chunk| {
+            let (cx, cy, cz) = (
+                chunk.structure_x() * CHUNK_DIMENSIONS,
+                chunk.structure_y() * CHUNK_DIMENSIONS,
+                chunk.structure_z() * CHUNK_DIMENSIONS,
+            );
+
+            chunk
+                .block_data_iter::<T>()
+                .map(move |((x, y, z), data)| (StructureBlock::new(cx + x, cy + y, cz + z), data))
+        })
+    }
+
+    /// Stores this data on the block at these coordinates, replacing any data of the same type.
+    ///
+    /// Nothing is stored if there is no block there. The data is removed when the block is changed.
+    ///
+    /// * `event_writer` If this is `None`, no event will be generated.
+    pub fn set_block_data<T: BlockDataType>(
+        &mut self,
+        x: usize,
+        y: usize,
+        z: usize,
+        data: &T,
+        event_writer: Option<&mut EventWriter<BlockDataChangedEvent>>,
+    ) {
+        self.set_raw_block_data(
+            x,
+            y,
+            z,
+            T::DATA_ID,
+            Some(cosmos_encoder::serialize(data)),
+            event_writer,
+        );
+    }
+
+    /// Removes the data of this type stored on the block at these coordinates.
+    ///
+    /// * `event_writer` If this is `None`, no event will be generated.
+    pub fn remove_block_data<T: BlockDataType>(
+        &mut self,
+        x: usize,
+        y: usize,
+        z: usize,
+        event_writer: Option<&mut EventWriter<BlockDataChangedEvent>>,
+    ) {
+        self.set_raw_block_data(x, y, z, T::DATA_ID, None, event_writer);
+    }
+
+    /// Sets (or removes if `data` is None) the serialized data stored on the block at these coordinates with this data id.
+    ///
+    /// Nothing is stored if there is no block there. Prefer `set_block_data` & `remove_block_data` when the type is known.
+    ///
+    /// * `event_writer` If this is `None`, no event will be generated.
+    pub fn set_raw_block_data(
+        &mut self,
+        x: usize,
+        y: usize,
+        z: usize,
+        data_id: &str,
+        data: Option<Vec<u8>>,
+        event_writer: Option<&mut EventWriter<BlockDataChangedEvent>>,
+    ) {
+        if !self.is_within_blocks(x, y, z) || !self.has_block_at(x, y, z) {
+            return;
+        }
+
+        let Some(chunk) = self.mut_chunk_at_block_coordinates(x, y, z) else {
+            return;
+        };
+
+        chunk.set_raw_block_data(
+            x % CHUNK_DIMENSIONS,
+            y % CHUNK_DIMENSIONS,
+            z % CHUNK_DIMENSIONS,
+            data_id,
+            data,
+        );
+
+        if let (Some(structure_entity), Some(event_writer)) = (self.self_entity, event_writer) {
+            event_writer.send(BlockDataChangedEvent {
+                structure_entity,
+                block: StructureBlock::new(x, y, z),
+                data_id: data_id.to_owned(),
+            });
+        }
+    }
+
     /// Returns the chunk's state
     pub fn get_chunk_state(&self, cx: usize, cy: usize, cz: usize) -> ChunkState {
         if cx >= self.width || cy >= self.height || cz >= self.length {
@@ -824,6 +1157,112 @@ impl Structure {
         chunk
     }
 
+    /// Grows the structure by the given number of chunks on each side.
+    ///
+    /// Growing in the negative direction shifts every existing chunk + block by that many chunks, so any
+    /// block coordinates held onto from before this call will be wrong.
+    ///
+    /// * `negative` The number of chunks to add before the existing chunks on the x/y/z axes
+    /// * `positive` The number of chunks to add after the existing chunks on the x/y/z axes
+    /// * `event_writer` If this is `None`, no event will be generated. Chunk entities + structure systems rely on this event
+    /// to stay in sync, so only pass `None` if this structure hasn't been spawned yet.
+    pub fn grow(
+        &mut self,
+        negative: (usize, usize, usize),
+        positive: (usize, usize, usize),
+        event_writer: Option<&mut EventWriter<StructureResizedEvent>>,
+    ) {
+        if negative == (0, 0, 0) && positive == (0, 0, 0) {
+            return;
+        }
+
+        let (nx, ny, nz) = negative;
+        let (old_width, old_height) = (self.width, self.height);
+
+        self.width += nx + positive.0;
+        self.height += ny + positive.1;
+        self.length += nz + positive.2;
+
+        let (width, height) = (self.width, self.height);
+
+        let reindex = |index: usize| {
+            let (cx, cy, cz) = expand(index, old_width, old_height);
+
+            flatten(cx + nx, cy + ny, cz + nz, width, height)
+        };
+
+        self.chunks = self
+            .chunks
+            .drain()
+            .map(|(index, mut chunk)| {
+                chunk.set_structure_position(
+                    chunk.structure_x() + nx,
+                    chunk.structure_y() + ny,
+                    chunk.structure_z() + nz,
+                );
+
+                (reindex(index), chunk)
+            })
+            .collect();
+        self.empty_chunks = self.empty_chunks.drain().map(reindex).collect();
+        self.loading_chunks = self.loading_chunks.drain().map(reindex).collect();
+        self.chunk_entities = self
+            .chunk_entities
+            .drain()
+            .map(|(index, entity)| (reindex(index), entity))
+            .collect();
+        self.chunk_entity_map = self
+            .chunk_entity_map
+            .drain()
+            .map(|(entity, index)| (entity, reindex(index)))
+            .collect();
+
+        if let Some(structure_entity) = self.self_entity {
+            if let Some(event_writer) = event_writer {
+                event_writer.send(StructureResizedEvent {
+                    structure_entity,
+                    negative,
+                    positive,
+                });
+            }
+        }
+    }
+
+    /// Grows the structure so that there are at least `padding` chunks between the chunk this block is in
+    /// and every edge of the structure.
+    ///
+    /// Returns the block's coordinates after growing, since growing can shift every block.
+    ///
+    /// * `event_writer` See [`Structure::grow`]
+    pub fn grow_to_fit(
+        &mut self,
+        block: StructureBlock,
+        padding: usize,
+        event_writer: Option<&mut EventWriter<StructureResizedEvent>>,
+    ) -> StructureBlock {
+        let (cx, cy, cz) = block.chunk_coords();
+
+        let negative = (
+            padding.saturating_sub(cx),
+            padding.saturating_sub(cy),
+            padding.saturating_sub(cz),
+        );
+
+        let positive = (
+            (cx + padding + 1).saturating_sub(self.width),
+            (cy + padding + 1).saturating_sub(self.height),
+            (cz + padding + 1).saturating_sub(self.length),
+        );
+
+        self.grow(negative, positive, event_writer);
+
+        StructureBlock::new(
+            block.x + negative.0 * CHUNK_DIMENSIONS,
+            block.y + negative.1 * CHUNK_DIMENSIONS,
+            block.z + negative.2 * CHUNK_DIMENSIONS,
+        )
+    }
+
     /// Tells the structure that every chunk, empty or not, has been loaded. Do not call this
     /// manually, this should be handled automatically when the StructureLoaded event is
     /// sent. This is also not used on planets.
@@ -864,16 +1303,24 @@ pub struct ChunkInitEvent {
 // Removes chunk entities if they have no blocks
 fn remove_empty_chunks(
     mut block_change_event: EventReader<BlockChangedEvent>,
+    mut block_change_batch_event: EventReader<BlockChangedBatchEvent>,
     mut structure_query: Query<&mut Structure>,
     mut commands: Commands,
 ) {
-    for bce in block_change_event.iter() {
-        let Ok(mut structure) = structure_query.get_mut(bce.structure_entity) else {
+    let changed_chunks = block_change_event
+        .iter()
+        .map(|bce| (bce.structure_entity, bce.block.chunk_coords()))
+        .chain(
+            block_change_batch_event
+                .iter()
+                .map(|ev| (ev.structure_entity, ev.chunk)),
+        );
+
+    for (structure_entity, (cx, cy, cz)) in changed_chunks {
+        let Ok(mut structure) = structure_query.get_mut(structure_entity) else {
             continue;
         };
 
-        let (cx, cy, cz) = bce.block.chunk_coords();
-
         if structure.chunk_from_chunk_coordinates(cx, cy, cz).is_none() {
             if let Some(chunk_entity) = structure.chunk_entity(cx, cy, cz) {
                 commands.entity(chunk_entity).insert(NeedsDespawned);
@@ -891,6 +1338,7 @@ fn remove_empty_chunks(
 fn add_chunks_system(
     mut chunk_init_reader: EventReader<ChunkInitEvent>,
     mut block_reader: EventReader<BlockChangedEvent>,
+    mut block_batch_reader: EventReader<BlockChangedBatchEvent>,
     mut structure_query: Query<(&mut Structure, Option<&PhysicsWorld>)>,
     mut chunk_set_event_writer: EventWriter<ChunkSetEvent>,
     mut commands: Commands,
@@ -909,6 +1357,10 @@ fn add_chunks_system(
         ));
     }
 
+    for ev in block_batch_reader.iter() {
+        s_chunks.insert((ev.structure_entity, ev.chunk));
+    }
+
     for ev in chunk_init_reader.iter() {
         s_chunks.insert((ev.structure_entity, (ev.x, ev.y, ev.z)));
         chunk_set_events.insert(ChunkSetEvent {
@@ -963,6 +1415,54 @@ fn add_chunks_system(
     }
 }
 
+/// Keeps the blocks of a resized structure in the same place in the world.
+///
+/// Chunk positions are relative to the center of the structure, so when it grows unevenly the structure itself
+/// is moved to make up for it & every non-chunk child is moved back to where it was.
+fn on_structure_resized(
+    mut event_reader: EventReader<StructureResizedEvent>,
+    structure_query: Query<(&Structure, Option<&Children>)>,
+    mut structure_transform_query: Query<&mut Transform, With<Structure>>,
+    mut chunk_query: Query<(&mut ChunkEntity, &mut Transform), Without<Structure>>,
+    mut child_query: Query<&mut Transform, (Without<ChunkEntity>, Without<Structure>)>,
+) {
+    for ev in event_reader.iter() {
+        let Ok((structure, children)) = structure_query.get(ev.structure_entity) else {
+            continue;
+        };
+
+        // How far every block moved relative to the structure's transform
+        let offset = Vec3::new(
+            ev.negative.0 as f32 - ev.positive.0 as f32,
+            ev.negative.1 as f32 - ev.positive.1 as f32,
+            ev.negative.2 as f32 - ev.positive.2 as f32,
+        ) * (CHUNK_DIMENSIONS as f32 / 2.0);
+
+        if let Ok(mut transform) = structure_transform_query.get_mut(ev.structure_entity) {
+            let delta = transform.rotation * offset;
+            transform.translation -= delta;
+        }
+
+        let Some(children) = children else {
+            continue;
+        };
+
+        for &child in children.iter() {
+            if let Ok((mut chunk_entity, mut transform)) = chunk_query.get_mut(child) {
+                let (cx, cy, cz) = chunk_entity.chunk_location;
+
+                chunk_entity.chunk_location =
+                    (cx + ev.negative.0, cy + ev.negative.1, cz + ev.negative.2);
+
+                let (cx, cy, cz) = chunk_entity.chunk_location;
+                transform.translation = structure.chunk_relative_position(cx, cy, cz);
+            } else if let Ok(mut transform) = child_query.get_mut(child) {
+                transform.translation += offset;
+            }
+        }
+    }
+}
+
 pub(super) fn register<T: States + Clone + Copy>(
     app: &mut App,
     post_loading_state: T,
@@ -978,8 +1478,214 @@ pub(super) fn register<T: States + Clone + Copy>(
     events::register(app);
     loading::register(app);
     block_health::register(app);
+    block_data::register(app);
     structure_block::register(app);
 
     app.add_system(add_chunks_system.in_base_set(CoreSet::PreUpdate))
-        .add_system(remove_empty_chunks.after(add_chunks_system));
+        .add_system(remove_empty_chunks.after(add_chunks_system))
+        .add_system(
+            on_structure_resized
+                .in_base_set(CoreSet::PreUpdate)
+                .before(add_chunks_system),
+        );
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::{
+        ecs::system::SystemState,
+        prelude::{Events, World},
+    };
+
+    use crate::{
+        inventory::Inventory,
+        item::Item,
+        registry::{id_map::IdMap, Registry},
+    };
+
+    use super::*;
+
+    #[test]
+    fn block_edits_are_batched_per_chunk() {
+        let mut blocks = Registry::<Block>::new();
+        blocks.register(Block::new(&vec![], 0, "cosmos:air".into(), 0.0));
+        blocks.register(Block::new(&vec![], 0, "cosmos:a".into(), 1.0));
+        blocks.register(Block::new(&vec![], 0, "cosmos:b".into(), 1.0));
+
+        let a = blocks.from_id("cosmos:a").unwrap();
+        let b = blocks.from_id("cosmos:b").unwrap();
+
+        let mut structure = Structure::new(2, 2, 2);
+        structure.set_entity(Entity::from_raw(7));
+
+        let mut batch = BlockEditBatch::new();
+        // Chunk (0, 0, 0)
+        batch.set_block_at(1, 1, 1, a, BlockRotation::IDENTITY);
+        batch.set_block_at(2, 2, 2, a, BlockRotation::IDENTITY);
+        batch.set_block_at(3, 3, 3, a, BlockRotation::IDENTITY);
+        batch.remove_block_at(2, 2, 2);
+        batch.set_block_at(3, 3, 3, b, BlockRotation::IDENTITY);
+        // Chunk (1, 0, 0)
+        batch.set_block_at(CHUNK_DIMENSIONS + 1, 0, 0, b, BlockRotation::IDENTITY);
+        // Chunk (0, 1, 0) - only changed and then changed back
+        batch.set_block_at(0, CHUNK_DIMENSIONS, 0, a, BlockRotation::IDENTITY);
+        batch.remove_block_at(0, CHUNK_DIMENSIONS, 0);
+
+        let mut world = World::new();
+        world.init_resource::<Events<BlockChangedBatchEvent>>();
+
+        let mut system_state = SystemState::<EventWriter<BlockChangedBatchEvent>>::new(&mut world);
+        let mut event_writer = system_state.get_mut(&mut world);
+
+        structure.apply_block_edits(batch, &blocks, Some(&mut event_writer));
+
+        assert_eq!(structure.block_id_at(1, 1, 1), a.id());
+        assert_eq!(structure.block_id_at(2, 2, 2), AIR_BLOCK_ID);
+        assert_eq!(structure.block_id_at(3, 3, 3), b.id());
+        assert_eq!(structure.block_id_at(CHUNK_DIMENSIONS + 1, 0, 0), b.id());
+        assert_eq!(structure.block_id_at(0, CHUNK_DIMENSIONS, 0), AIR_BLOCK_ID);
+
+        let mut events = world
+            .resource_mut::<Events<BlockChangedBatchEvent>>()
+            .drain()
+            .collect::<Vec<BlockChangedBatchEvent>>();
+        events.sort_by_key(|ev| ev.chunk);
+
+        assert_eq!(events.len(), 2);
+        assert!(events
+            .iter()
+            .all(|ev| ev.structure_entity == Entity::from_raw(7)));
+
+        let changes = |ev: &BlockChangedBatchEvent| {
+            ev.changes
+                .iter()
+                .map(|change| (change.block, change.old_block, change.new_block))
+                .collect::<Vec<(StructureBlock, u16, u16)>>()
+        };
+
+        assert_eq!(events[0].chunk, (0, 0, 0));
+        assert_eq!(
+            changes(&events[0]),
+            vec![
+                (StructureBlock::new(1, 1, 1), AIR_BLOCK_ID, a.id()),
+                (StructureBlock::new(3, 3, 3), AIR_BLOCK_ID, b.id()),
+            ]
+        );
+
+        assert_eq!(events[1].chunk, (1, 0, 0));
+        assert_eq!(
+            changes(&events[1]),
+            vec![(
+                StructureBlock::new(CHUNK_DIMENSIONS + 1, 0, 0),
+                AIR_BLOCK_ID,
+                b.id()
+            )]
+        );
+    }
+
+    #[test]
+    fn grow_to_fit_shifts_blocks() {
+        let block = Block::new(&vec![], 1, "cosmos:test".into(), 1.0);
+
+        let mut structure = Structure::new(2, 2, 2);
+        structure.set_block_at_without_events(5, 40, 3, &block, BlockRotation::IDENTITY);
+
+        let moved = structure.grow_to_fit(StructureBlock::new(5, 40, 3), 1, None);
+
+        assert_eq!(
+            (
+                structure.chunks_width(),
+                structure.chunks_height(),
+                structure.chunks_length()
+            ),
+            (3, 3, 3)
+        );
+        assert_eq!(
+            moved,
+            StructureBlock::new(5 + CHUNK_DIMENSIONS, 40, 3 + CHUNK_DIMENSIONS)
+        );
+        assert_eq!(structure.block_id_at(moved.x, moved.y, moved.z), 1);
+
+        let chunk = structure
+            .chunk_at_block_coordinates(moved.x, moved.y, moved.z)
+            .expect("Chunk should have been moved");
+
+        assert_eq!(
+            (
+                chunk.structure_x(),
+                chunk.structure_y(),
+                chunk.structure_z()
+            ),
+            moved.chunk_coords()
+        );
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct TestData(String);
+
+    impl BlockDataType for TestData {
+        const DATA_ID: &'static str = "cosmos:test_data";
+    }
+
+    #[test]
+    fn block_data_is_removed_with_block() {
+        let block = Block::new(&vec![], 1, "cosmos:test".into(), 1.0);
+        let air = Block::new(&vec![], AIR_BLOCK_ID, "cosmos:air".into(), 0.0);
+
+        let mut structure = Structure::new(1, 1, 1);
+
+        // There's no block to hold this
+        structure.set_block_data(3, 4, 5, &TestData("nothing".into()), None);
+        assert_eq!(structure.block_data::<TestData>(3, 4, 5), None);
+
+        structure.set_block_at_without_events(3, 4, 5, &block, BlockRotation::IDENTITY);
+        structure.set_block_data(3, 4, 5, &TestData("hello".into()), None);
+
+        assert_eq!(
+            structure.block_data::<TestData>(3, 4, 5),
+            Some(TestData("hello".into()))
+        );
+        assert_eq!(
+            structure.all_block_data::<TestData>().collect::<Vec<_>>(),
+            vec![(StructureBlock::new(3, 4, 5), TestData("hello".into()))]
+        );
+
+        structure.set_block_at_without_events(3, 4, 5, &air, BlockRotation::IDENTITY);
+        structure.set_block_at_without_events(3, 4, 5, &block, BlockRotation::IDENTITY);
+
+        assert_eq!(structure.block_data::<TestData>(3, 4, 5), None);
+    }
+
+    #[test]
+    fn container_item_ids_are_remapped() {
+        let block = Block::new(&vec![], 1, "cosmos:test".into(), 1.0);
+
+        let mut old_items = Registry::<Item>::new();
+        old_items.register(Item::new("cosmos:a".into(), 10));
+        old_items.register(Item::new("cosmos:b".into(), 10));
+
+        let mut new_items = Registry::<Item>::new();
+        new_items.register(Item::new("cosmos:new".into(), 10));
+        new_items.register(Item::new("cosmos:a".into(), 10));
+        new_items.register(Item::new("cosmos:b".into(), 10));
+
+        let mut inventory = Inventory::new(1);
+        inventory.insert(old_items.from_id("cosmos:b").unwrap(), 3);
+
+        let mut structure = Structure::new(1, 1, 1);
+        structure.set_block_at_without_events(1, 2, 3, &block, BlockRotation::IDENTITY);
+        structure.set_block_data(1, 2, 3, &inventory, None);
+
+        structure
+            .remap_item_ids(&IdMap::from_registry(&old_items).remap_for(&new_items, "cosmos:new"));
+
+        let inventory = structure
+            .block_data::<Inventory>(1, 2, 3)
+            .expect("The inventory should still be there");
+
+        assert_eq!(
+            inventory.itemstack_at(0).map(|is| is.item_id()),
+            Some(new_items.from_id("cosmos:b").unwrap().id())
+        );
+    }
 }
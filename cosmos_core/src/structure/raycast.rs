@@ -0,0 +1,282 @@
+//! A voxel raycast for finding which block of a structure a ray hits.
+//!
+//! This walks the blocks the ray passes through one at a time (a DDA traversal), so it doesn't rely on any
+//! colliders & works the same on both the client + server.
+
+use bevy::prelude::{GlobalTransform, Vec3};
+
+use crate::block::BlockFace;
+
+use super::{structure_block::StructureBlock, Structure};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// The result of a raycast that hit a block
+pub struct RaycastHit {
+    /// The block that was hit
+    pub block: StructureBlock,
+    /// The face of the block the ray entered through
+    pub face: BlockFace,
+    /// How far along the ray the block was hit
+    pub distance: f32,
+}
+
+/// The face a ray moving along this axis (0 = x, 1 = y, 2 = z) in this direction enters a block through
+fn face_entered(axis: usize, step: i64) -> BlockFace {
+    match (axis, step > 0) {
+        (0, true) => BlockFace::Left,
+        (0, false) => BlockFace::Right,
+        (1, true) => BlockFace::Bottom,
+        (1, false) => BlockFace::Top,
+        (2, true) => BlockFace::Back,
+        _ => BlockFace::Front,
+    }
+}
+
+impl Structure {
+    /// Finds the first non-air block hit by a ray in world space.
+    ///
+    /// * `origin` Where the ray starts in world space
+    /// * `direction` The direction of the ray in world space - this does not need to be normalized
+    /// * `max_distance` How far the ray can travel before giving up
+    /// * `structure_transform` This structure's global transform
+    pub fn raycast(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        max_distance: f32,
+        structure_transform: &GlobalTransform,
+    ) -> Option<RaycastHit> {
+        let inverse = structure_transform.compute_matrix().inverse();
+
+        self.raycast_local(
+            inverse.transform_point3(origin),
+            inverse.transform_vector3(direction),
+            max_distance,
+        )
+    }
+
+    /// Finds the first non-air block hit by a ray relative to this structure's transform.
+    ///
+    /// This is the same space as [`Structure::block_relative_position`].
+    ///
+    /// * `origin` Where the ray starts relative to the structure
+    /// * `direction` The direction of the ray relative to the structure - this does not need to be normalized
+    /// * `max_distance` How far the ray can travel before giving up
+    pub fn raycast_local(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        max_distance: f32,
+    ) -> Option<RaycastHit> {
+        let direction = direction.normalize_or_zero();
+
+        if direction == Vec3::ZERO || max_distance < 0.0 {
+            return None;
+        }
+
+        let dims = Vec3::new(
+            self.blocks_width() as f32,
+            self.blocks_height() as f32,
+            self.blocks_length() as f32,
+        );
+
+        // In this space, the block at (x, y, z) fills [x, x + 1) on each axis
+        let start = origin + dims / 2.0;
+
+        // Clip the ray to the structure's bounds
+        let mut t_enter = 0.0;
+        let mut t_exit = max_distance;
+        let mut enter_axis = None;
+
+        for axis in 0..3 {
+            let (s, d, size) = (start[axis], direction[axis], dims[axis]);
+
+            if d == 0.0 {
+                if s < 0.0 || s >= size {
+                    return None;
+                }
+
+                continue;
+            }
+
+            let (t0, t1) = ((0.0 - s) / d, (size - s) / d);
+            let (t0, t1) = if t0 < t1 { (t0, t1) } else { (t1, t0) };
+
+            if t0 > t_enter {
+                t_enter = t0;
+                enter_axis = Some(axis);
+            }
+
+            t_exit = f32::min(t_exit, t1);
+        }
+
+        if t_enter > t_exit {
+            return None;
+        }
+
+        let entry = start + direction * t_enter;
+
+        let mut cell = [0_i64; 3];
+        let mut step = [0_i64; 3];
+        let mut t_max = [f32::INFINITY; 3];
+        let mut t_delta = [f32::INFINITY; 3];
+
+        for axis in 0..3 {
+            let size = dims[axis] as i64;
+            let d = direction[axis];
+
+            // Clamped because floating point error can put the entry point on the wrong side of the boundary
+            cell[axis] = (entry[axis].floor() as i64).clamp(0, size - 1);
+
+            if d != 0.0 {
+                step[axis] = if d > 0.0 { 1 } else { -1 };
+
+                let boundary = if d > 0.0 { cell[axis] + 1 } else { cell[axis] } as f32;
+
+                t_max[axis] = (boundary - start[axis]) / d;
+                t_delta[axis] = 1.0 / d.abs();
+            }
+        }
+
+        let mut face = match enter_axis {
+            Some(axis) => face_entered(axis, step[axis]),
+            // The ray started inside the structure, so use the face it's pointing most towards
+            None => {
+                let abs = direction.abs();
+                let axis = if abs.x >= abs.y && abs.x >= abs.z {
+                    0
+                } else if abs.y >= abs.z {
+                    1
+                } else {
+                    2
+                };
+
+                face_entered(axis, step[axis])
+            }
+        };
+
+        let mut distance = t_enter;
+
+        loop {
+            let (x, y, z) = (cell[0] as usize, cell[1] as usize, cell[2] as usize);
+
+            if self.has_block_at(x, y, z) {
+                return Some(RaycastHit {
+                    block: StructureBlock::new(x, y, z),
+                    face,
+                    distance,
+                });
+            }
+
+            let axis = if t_max[0] <= t_max[1] && t_max[0] <= t_max[2] {
+                0
+            } else if t_max[1] <= t_max[2] {
+                1
+            } else {
+                2
+            };
+
+            distance = t_max[axis];
+
+            if distance > t_exit {
+                return None;
+            }
+
+            cell[axis] += step[axis];
+
+            if cell[axis] < 0 || cell[axis] >= dims[axis] as i64 {
+                return None;
+            }
+
+            t_max[axis] += t_delta[axis];
+            face = face_entered(axis, step[axis]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::{Quat, Transform};
+
+    use crate::block::{Block, BlockRotation};
+
+    use super::*;
+
+    fn structure_with_block(x: usize, y: usize, z: usize) -> Structure {
+        let block = Block::new(&vec![], 1, "cosmos:test".into(), 1.0);
+
+        let mut structure = Structure::new(1, 1, 1);
+        structure.set_block_at_without_events(x, y, z, &block, BlockRotation::IDENTITY);
+
+        structure
+    }
+
+    #[test]
+    fn hits_block_in_front() {
+        let structure = structure_with_block(16, 16, 10);
+
+        let origin = structure.block_relative_position(16, 16, 0);
+        let hit = structure
+            .raycast_local(origin, Vec3::Z, 20.0)
+            .expect("Should hit block");
+
+        assert_eq!(hit.block, StructureBlock::new(16, 16, 10));
+        assert_eq!(hit.face, BlockFace::Back);
+        assert!((hit.distance - 9.5).abs() < 0.001);
+    }
+
+    #[test]
+    fn misses_when_too_far_or_behind() {
+        let structure = structure_with_block(16, 16, 10);
+
+        let origin = structure.block_relative_position(16, 16, 0);
+
+        assert_eq!(structure.raycast_local(origin, Vec3::Z, 5.0), None);
+        assert_eq!(structure.raycast_local(origin, -Vec3::Z, 100.0), None);
+    }
+
+    #[test]
+    fn hits_from_outside_structure() {
+        let structure = structure_with_block(0, 3, 7);
+
+        let origin = structure.block_relative_position(0, 3, 7) - Vec3::new(10.0, 0.0, 0.0);
+        let hit = structure
+            .raycast_local(origin, Vec3::X, 20.0)
+            .expect("Should hit block");
+
+        assert_eq!(hit.block, StructureBlock::new(0, 3, 7));
+        assert_eq!(hit.face, BlockFace::Left);
+        assert!((hit.distance - 9.5).abs() < 0.001);
+    }
+
+    #[test]
+    fn hits_block_on_rotated_structure() {
+        let structure = structure_with_block(5, 20, 30);
+
+        let transform = GlobalTransform::from(
+            Transform::from_xyz(100.0, -50.0, 25.0)
+                .with_rotation(Quat::from_rotation_y(std::f32::consts::FRAC_PI_2)),
+        );
+
+        // Cast down onto the block's top face from above it, in world space
+        let block_world = transform.transform_point(structure.block_relative_position(5, 20, 30));
+        let up = transform.compute_transform().rotation * Vec3::Y;
+
+        let hit = structure
+            .raycast(block_world + up * 8.0, -up, 20.0, &transform)
+            .expect("Should hit block");
+
+        assert_eq!(hit.block, StructureBlock::new(5, 20, 30));
+        assert_eq!(hit.face, BlockFace::Top);
+        assert!((hit.distance - 7.5).abs() < 0.001);
+
+        // A ray along the world's z axis travels along the structure's x axis
+        let hit = structure
+            .raycast(block_world - Vec3::Z * 6.0, Vec3::Z, 20.0, &transform)
+            .expect("Should hit block");
+
+        assert_eq!(hit.block, StructureBlock::new(5, 20, 30));
+        assert_eq!(hit.face, BlockFace::Right);
+        assert!((hit.distance - 5.5).abs() < 0.001);
+    }
+}
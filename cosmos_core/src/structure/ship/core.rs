@@ -1,36 +1,102 @@
 //! Ship core handler
+//!
+//! When a ship's core is removed, the ship starts melting down. If a new core is placed before
+//! the meltdown finishes, the meltdown is stopped.
 
 use bevy::{
-    prelude::{App, Commands, Component, EventReader, IntoSystemConfig, OnUpdate, Res, States},
+    prelude::{
+        App, Commands, Component, Entity, EventReader, IntoSystemConfig, OnUpdate, Query, Res,
+        States, SystemSet, With,
+    },
     reflect::{FromReflect, Reflect},
+    utils::HashMap,
 };
+use serde::{Deserialize, Serialize};
 
 use crate::{
     block::Block,
-    events::block_events::BlockChangedEvent,
+    events::block_events::{BlockChangedBatchEvent, BlockChangedEvent},
     registry::{identifiable::Identifiable, Registry},
+    structure::systems::SystemsOffline,
 };
 
-#[derive(Component, Default, FromReflect, Reflect, Debug, Copy, Clone)]
-/// Represents the time since the last block was broken
+use super::Ship;
+
+#[derive(Component, Default, FromReflect, Reflect, Debug, Copy, Clone, Serialize, Deserialize)]
+/// Added to a ship once its core is destroyed, and removed if a new core is placed.
+///
+/// While a ship is melting down its systems are offline and its blocks are destroyed over time,
+/// until it finally explodes.
+///
+/// Contains how many seconds this ship has been melting down for.
 pub struct MeltingDown(pub f32);
 
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Systems that keep track of ship cores
+pub enum ShipCoreSet {
+    /// Starts & stops ships melting down.
+    ///
+    /// This uses commands, so [`MeltingDown`] is only up to date once they are applied after this set.
+    MonitorCores,
+}
+
+fn is_ship_core(block_id: u16, blocks: &Registry<Block>) -> bool {
+    blocks.from_numeric_id(block_id).unlocalized_name() == "cosmos:ship_core"
+}
+
+/// Starts melting down ships that lost their core, and stops melting down ships that got a new one.
+///
+/// Block changes are handled in order, so the last core change for a ship decides if it is melting down.
 fn monitor_block_events(
     mut commands: Commands,
     blocks: Res<Registry<Block>>,
     mut event_reader: EventReader<BlockChangedEvent>,
+    mut batch_event_reader: EventReader<BlockChangedBatchEvent>,
+    ship_query: Query<Option<&MeltingDown>, With<Ship>>,
 ) {
-    for ev in event_reader.iter() {
-        let block = blocks.from_numeric_id(ev.old_block);
+    let core_changes = event_reader
+        .iter()
+        .map(|ev| (ev.structure_entity, ev.old_block, ev.new_block))
+        .chain(batch_event_reader.iter().flat_map(|ev| {
+            ev.changes
+                .iter()
+                .map(move |change| (ev.structure_entity, change.old_block, change.new_block))
+        }))
+        .filter_map(|(structure_entity, old_block, new_block)| {
+            if is_ship_core(new_block, &blocks) {
+                Some((structure_entity, false))
+            } else if is_ship_core(old_block, &blocks) {
+                Some((structure_entity, true))
+            } else {
+                None
+            }
+        });
+
+    let melting_down = core_changes.collect::<HashMap<Entity, bool>>();
 
-        if block.unlocalized_name() == "cosmos:ship_core" {
+    for (structure_entity, melting) in melting_down {
+        let Ok(current) = ship_query.get(structure_entity) else {
+            continue;
+        };
+
+        if melting && current.is_none() {
+            commands
+                .entity(structure_entity)
+                .insert((MeltingDown::default(), SystemsOffline));
+        } else if !melting && current.is_some() {
             commands
-                .entity(ev.structure_entity)
-                .insert(MeltingDown::default());
+                .entity(structure_entity)
+                .remove::<MeltingDown>()
+                .remove::<SystemsOffline>();
         }
     }
 }
 
 pub(super) fn register<T: States + Clone + Copy>(app: &mut App, playing_state: T) {
-    app.add_system(monitor_block_events.in_set(OnUpdate(playing_state)));
+    app.add_system(
+        monitor_block_events
+            .in_set(OnUpdate(playing_state))
+            .in_set(ShipCoreSet::MonitorCores),
+    )
+    .register_type::<MeltingDown>();
 }
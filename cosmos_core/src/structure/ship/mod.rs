@@ -13,6 +13,11 @@ pub mod pilot;
 pub mod ship_builder;
 pub mod ship_movement;
 
+/// The number of empty chunks a ship keeps between its blocks and its edges.
+///
+/// Ships grow whenever a block is placed closer to the edge than this, so players never run out of room to build.
+pub const SHIP_CHUNK_PADDING: usize = 1;
+
 #[derive(Component, Debug, Reflect, FromReflect)]
 /// A structure that has this component is a ship
 pub struct Ship;
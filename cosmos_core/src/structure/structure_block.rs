@@ -7,14 +7,14 @@ use bevy::{
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    block::{Block, BlockFace},
+    block::{Block, BlockRotation},
     registry::Registry,
 };
 
 use super::{chunk::CHUNK_DIMENSIONS, Structure};
 
 #[derive(
-    Clone, Debug, FromReflect, Reflect, Copy, PartialEq, Eq, Default, Serialize, Deserialize,
+    Clone, Debug, FromReflect, Reflect, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Hash,
 )]
 /// A block that is a part of a structure
 ///
@@ -63,8 +63,8 @@ impl StructureBlock {
     }
 
     #[inline]
-    /// Returns this block's top facing face
-    pub fn block_up(&self, structure: &Structure) -> BlockFace {
+    /// Returns this block's rotation
+    pub fn block_rotation(&self, structure: &Structure) -> BlockRotation {
         structure.block_rotation(self.x, self.y, self.z)
     }
 
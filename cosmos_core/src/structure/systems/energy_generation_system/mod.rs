@@ -2,9 +2,11 @@
 
 use bevy::{prelude::*, utils::HashMap};
 
+use serde::Deserialize;
+
 use crate::{
-    block::Block,
-    events::block_events::BlockChangedEvent,
+    block::{definitions::BlockDefinition, Block},
+    events::block_events::{BlockChangedBatchEvent, BlockChangedEvent},
     registry::{identifiable::Identifiable, Registry},
     structure::{
         events::StructureLoadedEvent, systems::energy_storage_system::EnergyStorageSystem,
@@ -12,9 +14,10 @@ use crate::{
     },
 };
 
-use super::{StructureSystem, Systems};
+use super::{StructureSystem, Systems, SystemsOffline};
 
-#[derive(Default, FromReflect, Reflect, Clone, Copy)]
+#[derive(Debug, Default, FromReflect, Reflect, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
 /// Any block that can generate energy will have this property.
 pub struct EnergyGenerationProperty {
     /// How much energy is generated
@@ -56,29 +59,22 @@ impl EnergyGenerationSystem {
 
 fn register_energy_blocks(
     blocks: Res<Registry<Block>>,
+    definitions: Res<Registry<BlockDefinition>>,
     mut generation: ResMut<EnergyGenerationBlocks>,
 ) {
-    if let Some(block) = blocks.from_id("cosmos:reactor") {
-        generation.insert(
-            block,
-            EnergyGenerationProperty {
-                generation_rate: 1000.0,
-            },
-        );
-    }
-
-    if let Some(block) = blocks.from_id("cosmos:ship_core") {
-        generation.insert(
-            block,
-            EnergyGenerationProperty {
-                generation_rate: 100.0,
-            },
-        )
+    for definition in definitions.iter() {
+        if let (Some(generation_property), Some(block)) = (
+            definition.energy_generation,
+            blocks.from_id(definition.unlocalized_name()),
+        ) {
+            generation.insert(block, generation_property);
+        }
     }
 }
 
 fn block_update_system(
     mut event: EventReader<BlockChangedEvent>,
+    mut batch_event: EventReader<BlockChangedBatchEvent>,
     energy_generation_blocks: Res<EnergyGenerationBlocks>,
     blocks: Res<Registry<Block>>,
     mut system_query: Query<&mut EnergyGenerationSystem>,
@@ -101,10 +97,30 @@ fn block_update_system(
             }
         }
     }
+
+    for ev in batch_event.iter() {
+        if let Ok(systems) = systems_query.get(ev.structure_entity) {
+            if let Ok(mut system) = systems.query_mut(&mut system_query) {
+                for change in ev.changes.iter() {
+                    if let Some(prop) =
+                        energy_generation_blocks.get(blocks.from_numeric_id(change.old_block))
+                    {
+                        system.block_removed(prop);
+                    }
+
+                    if let Some(prop) =
+                        energy_generation_blocks.get(blocks.from_numeric_id(change.new_block))
+                    {
+                        system.block_added(prop);
+                    }
+                }
+            }
+        }
+    }
 }
 
 fn update_energy(
-    sys_query: Query<&Systems>,
+    sys_query: Query<&Systems, Without<SystemsOffline>>,
     e_gen_query: Query<(&EnergyGenerationSystem, &StructureSystem)>,
     mut e_storage_query: Query<&mut EnergyStorageSystem>,
     time: Res<Time>,
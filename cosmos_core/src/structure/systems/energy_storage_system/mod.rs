@@ -9,16 +9,19 @@ use bevy::{
     utils::HashMap,
 };
 
+use serde::Deserialize;
+
 use crate::{
-    block::Block,
-    events::block_events::BlockChangedEvent,
+    block::{definitions::BlockDefinition, Block},
+    events::block_events::{BlockChangedBatchEvent, BlockChangedEvent},
     registry::{identifiable::Identifiable, Registry},
     structure::{events::StructureLoadedEvent, Structure},
 };
 
 use super::Systems;
 
-#[derive(Default, FromReflect, Reflect, Clone, Copy)]
+#[derive(Debug, Default, FromReflect, Reflect, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
 /// Every block that can store energy should have this property
 pub struct EnergyStorageProperty {
     /// How much energy this block can store
@@ -79,18 +82,24 @@ impl EnergyStorageSystem {
     }
 }
 
-fn register_energy_blocks(blocks: Res<Registry<Block>>, mut storage: ResMut<EnergyStorageBlocks>) {
-    if let Some(block) = blocks.from_id("cosmos:energy_cell") {
-        storage.insert(block, EnergyStorageProperty { capacity: 10000.0 });
-    }
-
-    if let Some(block) = blocks.from_id("cosmos:ship_core") {
-        storage.insert(block, EnergyStorageProperty { capacity: 1000.0 })
+fn register_energy_blocks(
+    blocks: Res<Registry<Block>>,
+    definitions: Res<Registry<BlockDefinition>>,
+    mut storage: ResMut<EnergyStorageBlocks>,
+) {
+    for definition in definitions.iter() {
+        if let (Some(storage_property), Some(block)) = (
+            definition.energy_storage,
+            blocks.from_id(definition.unlocalized_name()),
+        ) {
+            storage.insert(block, storage_property);
+        }
     }
 }
 
 fn block_update_system(
     mut event: EventReader<BlockChangedEvent>,
+    mut batch_event: EventReader<BlockChangedBatchEvent>,
     energy_storage_blocks: Res<EnergyStorageBlocks>,
     blocks: Res<Registry<Block>>,
     mut system_query: Query<&mut EnergyStorageSystem>,
@@ -111,6 +120,26 @@ fn block_update_system(
             }
         }
     }
+
+    for ev in batch_event.iter() {
+        if let Ok(systems) = systems_query.get(ev.structure_entity) {
+            if let Ok(mut system) = systems.query_mut(&mut system_query) {
+                for change in ev.changes.iter() {
+                    if let Some(prop) =
+                        energy_storage_blocks.get(blocks.from_numeric_id(change.old_block))
+                    {
+                        system.block_removed(prop);
+                    }
+
+                    if let Some(prop) =
+                        energy_storage_blocks.get(blocks.from_numeric_id(change.new_block))
+                    {
+                        system.block_added(prop);
+                    }
+                }
+            }
+        }
+    }
 }
 
 fn structure_loaded_event(
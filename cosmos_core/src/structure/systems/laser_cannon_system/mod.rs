@@ -11,16 +11,22 @@ use bevy::{
     utils::HashMap,
 };
 
+use serde::Deserialize;
+
 use crate::{
-    block::{Block, BlockFace},
-    events::block_events::BlockChangedEvent,
+    block::{definitions::BlockDefinition, Block, BlockFace, BlockRotation},
+    events::block_events::{BlockChangedBatchEvent, BlockChangedEvent},
     registry::{identifiable::Identifiable, Registry},
-    structure::{events::StructureLoadedEvent, Structure, StructureBlock},
+    structure::{
+        events::{StructureLoadedEvent, StructureResizedEvent},
+        Structure, StructureBlock,
+    },
 };
 
 use super::Systems;
 
-#[derive(Default, FromReflect, Reflect, Clone, Copy)]
+#[derive(Debug, Default, FromReflect, Reflect, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
 /// Every block that is a laser cannon should have this property
 pub struct LaserCannonProperty {
     /// How much energy is consumed per shot
@@ -228,9 +234,14 @@ impl LaserCannonSystem {
         }
     }
 
-    fn block_added(&mut self, prop: &LaserCannonProperty, block: &StructureBlock) {
-        // Always assume +z direction (for now)
-        let block_direction = BlockFace::Front; // eventually take this as argument
+    fn block_added(
+        &mut self,
+        prop: &LaserCannonProperty,
+        block: &StructureBlock,
+        rotation: BlockRotation,
+    ) {
+        // Lasers are fired out of the front of the cannon
+        let block_direction = rotation.front();
 
         let mut found_line = None;
         let mut link_to = None;
@@ -326,24 +337,41 @@ impl LaserCannonSystem {
     }
 }
 
-fn register_laser_blocks(blocks: Res<Registry<Block>>, mut cannon: ResMut<LaserCannonBlocks>) {
-    if let Some(block) = blocks.from_id("cosmos:laser_cannon") {
-        cannon.insert(
-            block,
-            LaserCannonProperty {
-                energy_per_shot: 100.0,
-            },
-        )
+fn register_laser_blocks(
+    blocks: Res<Registry<Block>>,
+    definitions: Res<Registry<BlockDefinition>>,
+    mut cannon: ResMut<LaserCannonBlocks>,
+) {
+    for definition in definitions.iter() {
+        if let (Some(cannon_property), Some(block)) = (
+            definition.laser_cannon,
+            blocks.from_id(definition.unlocalized_name()),
+        ) {
+            cannon.insert(block, cannon_property);
+        }
     }
 }
 
 fn block_update_system(
     mut event: EventReader<BlockChangedEvent>,
+    mut batch_event: EventReader<BlockChangedBatchEvent>,
+    mut resized_event: EventReader<StructureResizedEvent>,
     laser_cannon_blocks: Res<LaserCannonBlocks>,
     blocks: Res<Registry<Block>>,
     mut system_query: Query<&mut LaserCannonSystem>,
     systems_query: Query<&Systems>,
 ) {
+    // Shift the lines first, since any block changes sent after a resize use the new coordinates
+    for ev in resized_event.iter() {
+        if let Ok(systems) = systems_query.get(ev.structure_entity) {
+            if let Ok(mut system) = systems.query_mut(&mut system_query) {
+                for line in system.lines.iter_mut() {
+                    line.start = ev.shift_block(line.start);
+                }
+            }
+        }
+    }
+
     for ev in event.iter() {
         if let Ok(systems) = systems_query.get(ev.structure_entity) {
             if let Ok(mut system) = systems.query_mut(&mut system_query) {
@@ -357,7 +385,28 @@ fn block_update_system(
                 if let Some(property) =
                     laser_cannon_blocks.get(blocks.from_numeric_id(ev.new_block))
                 {
-                    system.block_added(property, &ev.block);
+                    system.block_added(property, &ev.block, ev.new_block_rotation);
+                }
+            }
+        }
+    }
+
+    for ev in batch_event.iter() {
+        if let Ok(systems) = systems_query.get(ev.structure_entity) {
+            if let Ok(mut system) = systems.query_mut(&mut system_query) {
+                for change in ev.changes.iter() {
+                    if laser_cannon_blocks
+                        .get(blocks.from_numeric_id(change.old_block))
+                        .is_some()
+                    {
+                        system.block_removed(&change.block);
+                    }
+
+                    if let Some(property) =
+                        laser_cannon_blocks.get(blocks.from_numeric_id(change.new_block))
+                    {
+                        system.block_added(property, &change.block, change.new_block_rotation);
+                    }
                 }
             }
         }
@@ -377,7 +426,7 @@ fn structure_loaded_event(
 
             for block in structure.all_blocks_iter(false) {
                 if let Some(prop) = laser_cannon_blocks.get(block.block(structure, &blocks)) {
-                    system.block_added(prop, &block);
+                    system.block_added(prop, &block, block.block_rotation(structure));
                 }
             }
 
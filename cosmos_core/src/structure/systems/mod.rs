@@ -14,6 +14,7 @@ use super::{ship::Ship, Structure};
 pub mod energy_generation_system;
 pub mod energy_storage_system;
 pub mod laser_cannon_system;
+pub mod repair_system;
 pub mod thruster_system;
 
 #[derive(Component)]
@@ -22,6 +23,12 @@ pub mod thruster_system;
 /// (ie laser cannons firing)
 pub struct SystemActive;
 
+#[derive(Component, Debug)]
+#[component(storage = "SparseSet")]
+/// While a structure has this, none of its systems do anything
+/// (ie a ship melting down)
+pub struct SystemsOffline;
+
 #[derive(Component)]
 /// Used to tell if a system has a specified controller
 /// This does not need to be provided if no controller is used
@@ -155,4 +162,5 @@ pub(super) fn register<T: States + Clone + Copy>(
     energy_generation_system::register(app, post_loading_state, playing_state);
     thruster_system::register(app, post_loading_state, playing_state);
     laser_cannon_system::register(app, post_loading_state, playing_state);
+    repair_system::register(app, post_loading_state, playing_state);
 }
@@ -0,0 +1,174 @@
+//! Represents all the repair modules on a structure, which restore the health of damaged blocks over time
+
+use bevy::{
+    prelude::{
+        App, Commands, Component, EventReader, IntoSystemAppConfig, IntoSystemConfig, OnEnter,
+        OnUpdate, Query, Res, ResMut, Resource, States,
+    },
+    reflect::{FromReflect, Reflect},
+    utils::HashMap,
+};
+
+use serde::Deserialize;
+
+use crate::{
+    block::{definitions::BlockDefinition, Block},
+    events::block_events::{BlockChangedBatchEvent, BlockChangedEvent},
+    registry::{identifiable::Identifiable, Registry},
+    structure::{events::StructureLoadedEvent, Structure},
+};
+
+use super::Systems;
+
+#[derive(Debug, Default, FromReflect, Reflect, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+/// Every block that repairs other blocks should have this property
+pub struct RepairProperty {
+    /// How much health this block restores every second
+    pub repair_rate: f32,
+    /// How much energy this block consumes every second while it is repairing
+    pub energy_consumption: f32,
+}
+
+#[derive(Default, Resource)]
+/// All the repair blocks - register them here.
+pub struct RepairBlocks {
+    blocks: HashMap<u16, RepairProperty>,
+}
+
+impl RepairBlocks {
+    /// Inserts a block with a property
+    pub fn insert(&mut self, block: &Block, repair_property: RepairProperty) {
+        self.blocks.insert(block.id(), repair_property);
+    }
+
+    /// Gets the property for that block if it has one
+    pub fn get(&self, block: &Block) -> Option<&RepairProperty> {
+        self.blocks.get(&block.id())
+    }
+}
+
+#[derive(Component, Default, Reflect, FromReflect)]
+/// Represents all the repair modules of a structure
+pub struct RepairSystem {
+    repair_rate: f32,
+    energy_consumption: f32,
+}
+
+impl RepairSystem {
+    fn block_added(&mut self, prop: &RepairProperty) {
+        self.repair_rate += prop.repair_rate;
+        self.energy_consumption += prop.energy_consumption;
+    }
+
+    fn block_removed(&mut self, prop: &RepairProperty) {
+        self.repair_rate -= prop.repair_rate;
+        self.energy_consumption -= prop.energy_consumption;
+    }
+
+    /// Gets how much health this system can restore every second
+    pub fn repair_rate(&self) -> f32 {
+        self.repair_rate
+    }
+
+    /// Gets how much energy it costs to restore this much health
+    pub fn energy_cost(&self, health: f32) -> f32 {
+        if self.repair_rate <= 0.0 {
+            0.0
+        } else {
+            health / self.repair_rate * self.energy_consumption
+        }
+    }
+}
+
+fn register_repair_blocks(
+    blocks: Res<Registry<Block>>,
+    definitions: Res<Registry<BlockDefinition>>,
+    mut repair: ResMut<RepairBlocks>,
+) {
+    for definition in definitions.iter() {
+        if let (Some(repair_property), Some(block)) = (
+            definition.repair,
+            blocks.from_id(definition.unlocalized_name()),
+        ) {
+            repair.insert(block, repair_property);
+        }
+    }
+}
+
+fn block_update_system(
+    mut event: EventReader<BlockChangedEvent>,
+    mut batch_event: EventReader<BlockChangedBatchEvent>,
+    repair_blocks: Res<RepairBlocks>,
+    blocks: Res<Registry<Block>>,
+    mut system_query: Query<&mut RepairSystem>,
+    systems_query: Query<&Systems>,
+) {
+    for ev in event.iter() {
+        if let Ok(systems) = systems_query.get(ev.structure_entity) {
+            if let Ok(mut system) = systems.query_mut(&mut system_query) {
+                if let Some(prop) = repair_blocks.get(blocks.from_numeric_id(ev.old_block)) {
+                    system.block_removed(prop);
+                }
+
+                if let Some(prop) = repair_blocks.get(blocks.from_numeric_id(ev.new_block)) {
+                    system.block_added(prop);
+                }
+            }
+        }
+    }
+
+    for ev in batch_event.iter() {
+        if let Ok(systems) = systems_query.get(ev.structure_entity) {
+            if let Ok(mut system) = systems.query_mut(&mut system_query) {
+                for change in ev.changes.iter() {
+                    if let Some(prop) = repair_blocks.get(blocks.from_numeric_id(change.old_block))
+                    {
+                        system.block_removed(prop);
+                    }
+
+                    if let Some(prop) = repair_blocks.get(blocks.from_numeric_id(change.new_block))
+                    {
+                        system.block_added(prop);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn structure_loaded_event(
+    mut event_reader: EventReader<StructureLoadedEvent>,
+    mut structure_query: Query<(&Structure, &mut Systems)>,
+    blocks: Res<Registry<Block>>,
+    mut commands: Commands,
+    repair_blocks: Res<RepairBlocks>,
+) {
+    for ev in event_reader.iter() {
+        if let Ok((structure, mut systems)) = structure_query.get_mut(ev.structure_entity) {
+            let mut system = RepairSystem::default();
+
+            for block in structure.all_blocks_iter(false) {
+                if let Some(prop) = repair_blocks.get(block.block(structure, &blocks)) {
+                    system.block_added(prop);
+                }
+            }
+
+            systems.add_system(&mut commands, system);
+        }
+    }
+}
+
+pub(super) fn register<T: States + Clone + Copy>(
+    app: &mut App,
+    post_loading_state: T,
+    playing_state: T,
+) {
+    app.insert_resource(RepairBlocks::default())
+        .add_systems((
+            register_repair_blocks.in_schedule(OnEnter(post_loading_state)),
+            structure_loaded_event.in_set(OnUpdate(playing_state)),
+            block_update_system.in_set(OnUpdate(playing_state)),
+        ))
+        .register_type::<RepairSystem>();
+}
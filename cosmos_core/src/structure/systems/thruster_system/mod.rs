@@ -5,7 +5,7 @@ use std::ops::Mul;
 use bevy::{
     prelude::{
         App, Commands, Component, EventReader, IntoSystemAppConfig, IntoSystemConfig, OnEnter,
-        OnUpdate, Quat, Query, Res, ResMut, Resource, States, Transform, Vec3, With,
+        OnUpdate, Quat, Query, Res, ResMut, Resource, States, Transform, Vec3, With, Without,
     },
     reflect::{FromReflect, Reflect},
     time::Time,
@@ -13,9 +13,11 @@ use bevy::{
 };
 use bevy_rapier3d::prelude::{ExternalImpulse, ReadMassProperties, Velocity};
 
+use serde::Deserialize;
+
 use crate::{
-    block::Block,
-    events::block_events::BlockChangedEvent,
+    block::{definitions::BlockDefinition, Block},
+    events::block_events::{BlockChangedBatchEvent, BlockChangedEvent},
     registry::{identifiable::Identifiable, Registry},
     structure::{
         events::StructureLoadedEvent,
@@ -25,16 +27,19 @@ use crate::{
     },
 };
 
-use super::{StructureSystem, Systems};
+use super::{StructureSystem, Systems, SystemsOffline};
 
 const MAX_SHIP_SPEED: f32 = 150.0;
 const MAX_BRAKE_DELTA_PER_THRUST: f32 = 300.0;
 
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
 /// A block that is a thruster will have a thruster property
 pub struct ThrusterProperty {
     /// How much thrust this block generates
     pub strength: f32,
     /// How much energy this block consumes
+    #[serde(rename = "energy_consumption")]
     pub energy_consupmtion: f32,
 }
 
@@ -72,30 +77,24 @@ impl ThrusterSystem {
     }
 }
 
-fn register_thruster_blocks(blocks: Res<Registry<Block>>, mut storage: ResMut<ThrusterBlocks>) {
-    if let Some(block) = blocks.from_id("cosmos:thruster") {
-        storage.insert(
-            block,
-            ThrusterProperty {
-                strength: 5.0,
-                energy_consupmtion: 100.0,
-            },
-        );
-    }
-
-    if let Some(block) = blocks.from_id("cosmos:ship_core") {
-        storage.insert(
-            block,
-            ThrusterProperty {
-                strength: 1.0,
-                energy_consupmtion: 100.0,
-            },
-        )
+fn register_thruster_blocks(
+    blocks: Res<Registry<Block>>,
+    definitions: Res<Registry<BlockDefinition>>,
+    mut storage: ResMut<ThrusterBlocks>,
+) {
+    for definition in definitions.iter() {
+        if let (Some(thruster), Some(block)) = (
+            definition.thruster,
+            blocks.from_id(definition.unlocalized_name()),
+        ) {
+            storage.insert(block, thruster);
+        }
     }
 }
 
 fn block_update_system(
     mut event: EventReader<BlockChangedEvent>,
+    mut batch_event: EventReader<BlockChangedBatchEvent>,
     energy_storage_blocks: Res<ThrusterBlocks>,
     blocks: Res<Registry<Block>>,
     mut system_query: Query<&mut ThrusterSystem>,
@@ -116,6 +115,26 @@ fn block_update_system(
             }
         }
     }
+
+    for ev in batch_event.iter() {
+        if let Ok(systems) = systems_query.get(ev.structure_entity) {
+            if let Ok(mut system) = systems.query_mut(&mut system_query) {
+                for change in ev.changes.iter() {
+                    if let Some(prop) =
+                        energy_storage_blocks.get(blocks.from_numeric_id(change.old_block))
+                    {
+                        system.block_removed(prop);
+                    }
+
+                    if let Some(prop) =
+                        energy_storage_blocks.get(blocks.from_numeric_id(change.new_block))
+                    {
+                        system.block_added(prop);
+                    }
+                }
+            }
+        }
+    }
 }
 
 fn update_movement(
@@ -129,7 +148,7 @@ fn update_movement(
             &mut ExternalImpulse,
             &ReadMassProperties,
         ),
-        With<Pilot>,
+        (With<Pilot>, Without<SystemsOffline>),
     >,
     mut energy_query: Query<&mut EnergyStorageSystem>,
     time: Res<Time>,
@@ -0,0 +1,325 @@
+//! Lets blocks change over time, such as grass spreading or a plant growing.
+//!
+//! Blocks are ticked in two ways:
+//! - Scheduled ticks: A block asks to be ticked some number of world ticks from now via [`schedule_block_tick`].
+//!   These are stored on the block, so they are saved with its chunk & forgotten if the block is changed.
+//! - Random ticks: Every world tick, [`RANDOM_TICKS_PER_CHUNK`] random blocks in every loaded chunk are ticked.
+//!
+//! Only blocks that have a [`BlockTicker`] registered are ticked. To do something when a block is ticked,
+//! read the [`BlockTickEvent`]s in a system in the [`BlockTickSet::HandleTicks`] set.
+
+use bevy::prelude::{
+    resource_changed, App, Entity, EventWriter, IntoSystemConfig, IntoSystemConfigs,
+    IntoSystemSetConfigs, OnUpdate, Query, Res, ResMut, SystemSet,
+};
+use cosmos_core::{
+    block::Block,
+    netty::world_tick::WorldTick,
+    registry::{self, identifiable::Identifiable, Registry},
+    structure::{
+        block_data::{BlockDataType, PrivateBlockData},
+        chunk::{Chunk, CHUNK_DIMENSIONS},
+        structure_block::StructureBlock,
+        Structure,
+    },
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::state::GameState;
+
+/// The number of random blocks in each loaded chunk that are ticked every world tick
+pub const RANDOM_TICKS_PER_CHUNK: usize = 3;
+
+#[derive(Debug, Serialize, Deserialize)]
+/// The world tick a block should next be ticked on
+struct ScheduledTick(u64);
+
+impl BlockDataType for ScheduledTick {
+    const DATA_ID: &'static str = "cosmos:scheduled_tick";
+}
+
+#[derive(Debug)]
+/// Marks that a block should be ticked.
+///
+/// Register one of these for a block to have it receive [`BlockTickEvent`]s.
+pub struct BlockTicker {
+    id: u16,
+    unlocalized_name: String,
+
+    random_ticks: bool,
+}
+
+impl BlockTicker {
+    /// Creates a ticker for that block.
+    ///
+    /// * `random_ticks` If true, this block will also receive random ticks. Scheduled ticks are always received.
+    ///
+    /// This still needs to be registered!
+    pub fn new(block: &Block, random_ticks: bool) -> Self {
+        Self {
+            id: 0,
+            unlocalized_name: block.unlocalized_name().to_owned(),
+            random_ticks,
+        }
+    }
+
+    /// Returns true if this block receives random ticks
+    pub fn random_ticks(&self) -> bool {
+        self.random_ticks
+    }
+}
+
+impl Identifiable for BlockTicker {
+    fn id(&self) -> u16 {
+        self.id
+    }
+
+    fn set_numeric_id(&mut self, id: u16) {
+        self.id = id;
+    }
+
+    fn unlocalized_name(&self) -> &str {
+        &self.unlocalized_name
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Why a block was ticked
+pub enum BlockTickKind {
+    /// The block scheduled this tick
+    Scheduled,
+    /// The block was randomly chosen
+    Random,
+}
+
+#[derive(Debug)]
+/// Sent whenever a block with a [`BlockTicker`] is ticked
+pub struct BlockTickEvent {
+    /// The structure the block is on
+    pub structure_entity: Entity,
+    /// The block that was ticked
+    pub block: StructureBlock,
+    /// The id of the block that was ticked
+    pub block_id: u16,
+    /// Why the block was ticked
+    pub kind: BlockTickKind,
+}
+
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The order block ticks are sent & handled in
+pub enum BlockTickSet {
+    /// Sends every [`BlockTickEvent`] for this world tick
+    SendTicks,
+    /// Put systems that handle [`BlockTickEvent`]s here
+    HandleTicks,
+}
+
+/// Schedules the block at these coordinates to be ticked `delay` world ticks from now.
+///
+/// A block can only have one scheduled tick. If it already has an earlier one, that one is kept instead.
+/// Nothing happens if there is no block at these coordinates.
+pub fn schedule_block_tick(
+    structure: &mut Structure,
+    block: StructureBlock,
+    delay: u64,
+    world_tick: &WorldTick,
+) {
+    let tick = world_tick.get() + delay.max(1);
+
+    if let Some(ScheduledTick(scheduled)) = structure.block_data(block.x, block.y, block.z) {
+        if scheduled <= tick {
+            return;
+        }
+    }
+
+    structure.set_block_data(block.x, block.y, block.z, &ScheduledTick(tick), None);
+}
+
+/// Removes every scheduled tick that is due by this world tick, returning the blocks they were on
+fn take_due_ticks(structure: &mut Structure, world_tick: u64) -> Vec<StructureBlock> {
+    let due = structure
+        .all_block_data::<ScheduledTick>()
+        .filter(|(_, ScheduledTick(tick))| *tick <= world_tick)
+        .map(|(block, _)| block)
+        .collect::<Vec<StructureBlock>>();
+
+    for block in due.iter() {
+        structure.remove_block_data::<ScheduledTick>(block.x, block.y, block.z, None);
+    }
+
+    due
+}
+
+/// Gets the structure coordinates of a block given its coordinates within this chunk
+fn block_in_chunk(chunk: &Chunk, (x, y, z): (usize, usize, usize)) -> StructureBlock {
+    StructureBlock::new(
+        chunk.structure_x() * CHUNK_DIMENSIONS + x,
+        chunk.structure_y() * CHUNK_DIMENSIONS + y,
+        chunk.structure_z() * CHUNK_DIMENSIONS + z,
+    )
+}
+
+fn is_ticked(block_id: u16, blocks: &Registry<Block>, tickers: &Registry<BlockTicker>) -> bool {
+    tickers
+        .from_id(blocks.from_numeric_id(block_id).unlocalized_name())
+        .is_some()
+}
+
+fn send_scheduled_ticks(
+    mut query: Query<(Entity, &mut Structure)>,
+    world_tick: Res<WorldTick>,
+    blocks: Res<Registry<Block>>,
+    tickers: Res<Registry<BlockTicker>>,
+    mut event_writer: EventWriter<BlockTickEvent>,
+) {
+    for (structure_entity, mut structure) in query.iter_mut() {
+        for block in take_due_ticks(&mut structure, world_tick.get()) {
+            let block_id = structure.block_id_at(block.x, block.y, block.z);
+
+            if is_ticked(block_id, &blocks, &tickers) {
+                event_writer.send(BlockTickEvent {
+                    structure_entity,
+                    block,
+                    block_id,
+                    kind: BlockTickKind::Scheduled,
+                });
+            }
+        }
+    }
+}
+
+fn send_random_ticks(
+    query: Query<(Entity, &Structure)>,
+    blocks: Res<Registry<Block>>,
+    tickers: Res<Registry<BlockTicker>>,
+    mut event_writer: EventWriter<BlockTickEvent>,
+) {
+    let mut rng = rand::thread_rng();
+
+    for (structure_entity, structure) in query.iter() {
+        // Only non-empty chunks are stored, so empty chunks are never ticked
+        for chunk in structure.chunks().values() {
+            for _ in 0..RANDOM_TICKS_PER_CHUNK {
+                let (x, y, z) = (
+                    rng.gen_range(0..CHUNK_DIMENSIONS),
+                    rng.gen_range(0..CHUNK_DIMENSIONS),
+                    rng.gen_range(0..CHUNK_DIMENSIONS),
+                );
+
+                let block_id = chunk.block_at(x, y, z);
+
+                let random_ticks = tickers
+                    .from_id(blocks.from_numeric_id(block_id).unlocalized_name())
+                    .map(|ticker| ticker.random_ticks())
+                    .unwrap_or(false);
+
+                if random_ticks {
+                    event_writer.send(BlockTickEvent {
+                        structure_entity,
+                        block: block_in_chunk(chunk, (x, y, z)),
+                        block_id,
+                        kind: BlockTickKind::Random,
+                    });
+                }
+            }
+        }
+    }
+}
+
+fn make_scheduled_ticks_private(mut private_data: ResMut<PrivateBlockData>) {
+    private_data.make_private::<ScheduledTick>();
+}
+
+pub(super) fn register(app: &mut App) {
+    registry::create_registry::<BlockTicker>(app);
+
+    app.add_event::<BlockTickEvent>()
+        .add_startup_system(make_scheduled_ticks_private)
+        .configure_sets((BlockTickSet::SendTicks, BlockTickSet::HandleTicks).chain())
+        .add_systems(
+            (
+                send_scheduled_ticks.run_if(resource_changed::<WorldTick>()),
+                send_random_ticks.run_if(resource_changed::<WorldTick>()),
+            )
+                .in_set(BlockTickSet::SendTicks)
+                .in_set(OnUpdate(GameState::Playing)),
+        );
+}
+
+#[cfg(test)]
+mod test {
+    use cosmos_core::block::BlockRotation;
+
+    use super::*;
+
+    fn structure_with_block() -> (Structure, StructureBlock) {
+        let mut blocks = Registry::<Block>::new();
+        blocks.register(Block::new(&vec![], 0, "cosmos:air".into(), 0.0));
+        blocks.register(Block::new(&vec![], 0, "cosmos:test".into(), 1.0));
+
+        let mut structure = Structure::new(1, 1, 1);
+        let block = StructureBlock::new(1, 2, 3);
+
+        structure.set_block_at(
+            block.x,
+            block.y,
+            block.z,
+            blocks.from_id("cosmos:test").unwrap(),
+            BlockRotation::IDENTITY,
+            &blocks,
+            None,
+        );
+
+        (structure, block)
+    }
+
+    #[test]
+    fn earlier_scheduled_tick_wins() {
+        let (mut structure, block) = structure_with_block();
+
+        schedule_block_tick(&mut structure, block, 10, &WorldTick::new(100));
+        schedule_block_tick(&mut structure, block, 20, &WorldTick::new(100));
+
+        assert!(take_due_ticks(&mut structure, 109).is_empty());
+
+        schedule_block_tick(&mut structure, block, 5, &WorldTick::new(100));
+
+        assert_eq!(take_due_ticks(&mut structure, 105), vec![block]);
+    }
+
+    #[test]
+    fn scheduled_tick_is_removed_after_firing() {
+        let (mut structure, block) = structure_with_block();
+
+        schedule_block_tick(&mut structure, block, 10, &WorldTick::new(100));
+
+        assert_eq!(take_due_ticks(&mut structure, 110), vec![block]);
+        assert!(structure
+            .block_data::<ScheduledTick>(block.x, block.y, block.z)
+            .is_none());
+        assert!(take_due_ticks(&mut structure, 111).is_empty());
+    }
+
+    #[test]
+    fn random_ticks_use_structure_coordinates() {
+        let chunk = Chunk::new(1, 2, 3);
+
+        assert_eq!(
+            block_in_chunk(&chunk, (4, 5, 6)),
+            StructureBlock::new(
+                CHUNK_DIMENSIONS + 4,
+                2 * CHUNK_DIMENSIONS + 5,
+                3 * CHUNK_DIMENSIONS + 6,
+            )
+        );
+        assert_eq!(
+            block_in_chunk(&chunk, (CHUNK_DIMENSIONS - 1, 0, 0)),
+            StructureBlock::new(
+                2 * CHUNK_DIMENSIONS - 1,
+                2 * CHUNK_DIMENSIONS,
+                3 * CHUNK_DIMENSIONS
+            )
+        );
+    }
+}
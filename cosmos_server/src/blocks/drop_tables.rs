@@ -0,0 +1,381 @@
+//! Drop tables decide which items a block gives when it is broken.
+//!
+//! Every block that has an item drops one of that item by default. To change what a block drops,
+//! register a [`DropTable`] for it before [`GameState::PostLoading`] ends.
+
+use std::ops::RangeInclusive;
+
+use bevy::prelude::{App, Entity, IntoSystemAppConfig, OnExit, Res, ResMut};
+use cosmos_core::{
+    block::Block,
+    blockitems::BlockItems,
+    inventory::itemstack::ItemStack,
+    item::Item,
+    registry::{self, identifiable::Identifiable, Registry},
+};
+use rand::Rng;
+
+use crate::state::GameState;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// What caused a block to drop its items
+pub enum DropSource {
+    /// A player broke the block
+    Player(Entity),
+    /// The block took too much damage, such as from a laser or explosion
+    Destroyed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Limits what can cause a drop to happen
+pub enum DropCondition {
+    /// Only when a player breaks the block
+    BrokenByPlayer,
+    /// Only when the block took too much damage
+    Destroyed,
+}
+
+impl DropCondition {
+    /// Checks if this condition is met for this source
+    pub fn is_met(&self, source: DropSource) -> bool {
+        matches!(
+            (self, source),
+            (Self::BrokenByPlayer, DropSource::Player(_))
+                | (Self::Destroyed, DropSource::Destroyed)
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+/// One of the possible outputs of a drop table
+pub struct DropEntry {
+    /// The item's id, or None if this entry drops nothing
+    pub item_id: Option<u16>,
+    /// How likely this entry is to be picked compared to the other entries
+    pub weight: u32,
+    /// How many of the item are dropped
+    pub quantity: RangeInclusive<u16>,
+    /// If this is Some, this entry can only be picked if the condition is met
+    pub condition: Option<DropCondition>,
+}
+
+impl DropEntry {
+    /// Creates an entry that drops this item
+    ///
+    /// If the end of `quantity` is less than its start, exactly the start is dropped.
+    pub fn new(item: &Item, weight: u32, quantity: RangeInclusive<u16>) -> Self {
+        let (start, end) = quantity.into_inner();
+
+        Self {
+            item_id: Some(item.id()),
+            weight,
+            quantity: start..=end.max(start),
+            condition: None,
+        }
+    }
+
+    /// Creates an entry that drops nothing, making the other entries less likely
+    pub fn nothing(weight: u32) -> Self {
+        Self {
+            item_id: None,
+            weight,
+            quantity: 0..=0,
+            condition: None,
+        }
+    }
+
+    /// Makes this entry only possible if the condition is met
+    pub fn with_condition(mut self, condition: DropCondition) -> Self {
+        self.condition = Some(condition);
+        self
+    }
+}
+
+#[derive(Debug)]
+/// The items a block drops when it is broken
+///
+/// Each roll picks one entry based on their weights, so a table with 2 rolls drops up to 2 entries.
+pub struct DropTable {
+    id: u16,
+    unlocalized_name: String,
+
+    rolls: u32,
+    entries: Vec<DropEntry>,
+}
+
+impl DropTable {
+    /// Creates an empty drop table for that block that rolls once.
+    ///
+    /// This still needs to be registered!
+    pub fn new(block: &Block) -> Self {
+        Self {
+            id: 0,
+            unlocalized_name: block.unlocalized_name().to_owned(),
+            rolls: 1,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Sets how many times this table is rolled every time the block is broken
+    pub fn with_rolls(mut self, rolls: u32) -> Self {
+        self.rolls = rolls;
+        self
+    }
+
+    /// Adds an entry to this table
+    pub fn with_entry(mut self, entry: DropEntry) -> Self {
+        self.entries.push(entry);
+        self
+    }
+
+    /// Rolls this table, returning every item dropped.
+    ///
+    /// Entries whose condition isn't met for this source are skipped. Quantities bigger than the item's
+    /// max stack size are split into several stacks.
+    pub fn roll(
+        &self,
+        source: DropSource,
+        items: &Registry<Item>,
+        rng: &mut impl Rng,
+    ) -> Vec<ItemStack> {
+        let entries = self
+            .entries
+            .iter()
+            .filter(|entry| entry.condition.map(|c| c.is_met(source)).unwrap_or(true))
+            .collect::<Vec<&DropEntry>>();
+
+        let total_weight = entries.iter().map(|entry| entry.weight).sum::<u32>();
+
+        if total_weight == 0 {
+            return Vec::new();
+        }
+
+        let mut drops = Vec::new();
+
+        for _ in 0..self.rolls {
+            let mut picked = rng.gen_range(0..total_weight);
+
+            let Some(entry) = entries.iter().find(|entry| {
+                if picked < entry.weight {
+                    true
+                } else {
+                    picked -= entry.weight;
+                    false
+                }
+            }) else {
+                continue;
+            };
+
+            let Some(item_id) = entry.item_id else {
+                continue;
+            };
+
+            // `quantity` is public, so it may not have gone through `DropEntry::new`
+            let (start, end) = (*entry.quantity.start(), *entry.quantity.end());
+            let mut quantity = rng.gen_range(start..=end.max(start));
+
+            let item = items.from_numeric_id(item_id);
+
+            // A roll can drop more than fits in one stack
+            while quantity > 0 {
+                let stack_quantity = quantity.min(item.max_stack_size().max(1));
+
+                drops.push(ItemStack::with_quantity(item, stack_quantity));
+
+                quantity -= stack_quantity;
+            }
+        }
+
+        drops
+    }
+}
+
+impl Identifiable for DropTable {
+    fn id(&self) -> u16 {
+        self.id
+    }
+
+    fn set_numeric_id(&mut self, id: u16) {
+        self.id = id;
+    }
+
+    fn unlocalized_name(&self) -> &str {
+        &self.unlocalized_name
+    }
+}
+
+/// Rolls the drop table of that block, returning every item dropped.
+///
+/// Blocks without a drop table drop nothing.
+pub fn roll_drops(
+    block: &Block,
+    source: DropSource,
+    drop_tables: &Registry<DropTable>,
+    items: &Registry<Item>,
+    rng: &mut impl Rng,
+) -> Vec<ItemStack> {
+    drop_tables
+        .from_id(block.unlocalized_name())
+        .map(|table| table.roll(source, items, rng))
+        .unwrap_or_default()
+}
+
+fn add_default_drop_tables(
+    blocks: Res<Registry<Block>>,
+    items: Res<Registry<Item>>,
+    block_items: Res<BlockItems>,
+    mut drop_tables: ResMut<Registry<DropTable>>,
+) {
+    for block in blocks.iter() {
+        if drop_tables.from_id(block.unlocalized_name()).is_some() {
+            continue;
+        }
+
+        if let Some(item_id) = block_items.item_from_block(block) {
+            drop_tables.register(DropTable::new(block).with_entry(DropEntry::new(
+                items.from_numeric_id(item_id),
+                1,
+                1..=1,
+            )));
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    registry::create_registry::<DropTable>(app);
+
+    app.add_system(add_default_drop_tables.in_schedule(OnExit(GameState::PostLoading)));
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    fn items() -> Registry<Item> {
+        let mut items = Registry::<Item>::new();
+        items.register(Item::new("cosmos:stone".into(), 10));
+        items.register(Item::new("cosmos:dirt".into(), 10));
+        items
+    }
+
+    fn table(rolls: u32, entries: Vec<DropEntry>) -> DropTable {
+        let block = Block::new(&vec![], 0, "cosmos:stone".into(), 1.0);
+
+        entries
+            .into_iter()
+            .fold(DropTable::new(&block).with_rolls(rolls), |table, entry| {
+                table.with_entry(entry)
+            })
+    }
+
+    /// Rolls the table that many times, counting how many of each item dropped
+    fn roll_many(
+        table: &DropTable,
+        source: DropSource,
+        times: usize,
+        items: &Registry<Item>,
+    ) -> HashMap<u16, usize> {
+        let mut rng = ChaCha8Rng::seed_from_u64(1234);
+        let mut counts = HashMap::new();
+
+        for _ in 0..times {
+            for item_stack in table.roll(source, items, &mut rng) {
+                *counts.entry(item_stack.item_id()).or_default() += item_stack.quantity() as usize;
+            }
+        }
+
+        counts
+    }
+
+    #[test]
+    fn entries_are_picked_by_weight() {
+        let items = items();
+        let (stone, dirt) = (
+            items.from_id("cosmos:stone").unwrap(),
+            items.from_id("cosmos:dirt").unwrap(),
+        );
+
+        let table = table(
+            1,
+            vec![
+                DropEntry::new(stone, 3, 1..=1),
+                DropEntry::new(dirt, 1, 1..=1),
+            ],
+        );
+
+        let counts = roll_many(&table, DropSource::Destroyed, 4000, &items);
+
+        let (stones, dirts) = (counts[&stone.id()], counts[&dirt.id()]);
+
+        assert_eq!(stones + dirts, 4000);
+        assert!((2800..3200).contains(&stones), "{stones} stone dropped");
+    }
+
+    #[test]
+    fn nothing_entries_drop_nothing() {
+        let items = items();
+        let stone = items.from_id("cosmos:stone").unwrap();
+
+        let only_nothing = table(3, vec![DropEntry::nothing(5)]);
+        assert!(roll_many(&only_nothing, DropSource::Destroyed, 100, &items).is_empty());
+
+        let half_nothing = table(
+            1,
+            vec![DropEntry::new(stone, 1, 1..=1), DropEntry::nothing(1)],
+        );
+        let stones = roll_many(&half_nothing, DropSource::Destroyed, 2000, &items)[&stone.id()];
+
+        assert!((900..1100).contains(&stones), "{stones} stone dropped");
+    }
+
+    #[test]
+    fn conditions_filter_entries() {
+        let items = items();
+        let (stone, dirt) = (
+            items.from_id("cosmos:stone").unwrap(),
+            items.from_id("cosmos:dirt").unwrap(),
+        );
+
+        let table = table(
+            1,
+            vec![
+                DropEntry::new(stone, 1, 1..=1).with_condition(DropCondition::BrokenByPlayer),
+                DropEntry::new(dirt, 1, 1..=1).with_condition(DropCondition::Destroyed),
+            ],
+        );
+
+        let by_player = roll_many(&table, DropSource::Player(Entity::from_raw(0)), 100, &items);
+        assert_eq!(by_player.get(&stone.id()), Some(&100));
+        assert_eq!(by_player.get(&dirt.id()), None);
+
+        let destroyed = roll_many(&table, DropSource::Destroyed, 100, &items);
+        assert_eq!(destroyed.get(&stone.id()), None);
+        assert_eq!(destroyed.get(&dirt.id()), Some(&100));
+    }
+
+    #[test]
+    fn big_or_inverted_quantities() {
+        let items = items();
+        let stone = items.from_id("cosmos:stone").unwrap();
+        let mut rng = ChaCha8Rng::seed_from_u64(1234);
+
+        let inverted = table(1, vec![DropEntry::new(stone, 1, 5..=2)]);
+        let drops = inverted.roll(DropSource::Destroyed, &items, &mut rng);
+        assert_eq!(
+            drops.iter().map(|is| is.quantity()).collect::<Vec<u16>>(),
+            vec![5]
+        );
+
+        let big = table(1, vec![DropEntry::new(stone, 1, 25..=25)]);
+        let drops = big.roll(DropSource::Destroyed, &items, &mut rng);
+        assert_eq!(
+            drops.iter().map(|is| is.quantity()).collect::<Vec<u16>>(),
+            vec![10, 10, 5]
+        );
+    }
+}
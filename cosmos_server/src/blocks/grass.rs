@@ -0,0 +1,128 @@
+//! Grass slowly spreads onto nearby dirt that isn't covered up, and dies back into dirt once it is covered.
+
+use bevy::prelude::{
+    App, EventReader, EventWriter, IntoSystemAppConfig, IntoSystemConfig, OnEnter, OnUpdate, Query,
+    Res, ResMut,
+};
+use cosmos_core::{
+    block::{blocks::AIR_BLOCK_ID, Block},
+    events::block_events::BlockChangedEvent,
+    registry::{identifiable::Identifiable, Registry},
+    structure::{structure_block::StructureBlock, Structure},
+};
+use rand::Rng;
+
+use crate::state::GameState;
+
+use super::block_ticks::{BlockTickEvent, BlockTickSet, BlockTicker};
+
+const DIRECTIONS: [(i32, i32, i32); 6] = [
+    (1, 0, 0),
+    (-1, 0, 0),
+    (0, 1, 0),
+    (0, -1, 0),
+    (0, 0, 1),
+    (0, 0, -1),
+];
+
+/// Gets the block next to this one in that direction, if it is within the structure
+fn neighbour(
+    structure: &Structure,
+    block: StructureBlock,
+    (dx, dy, dz): (i32, i32, i32),
+) -> Option<StructureBlock> {
+    let (x, y, z) = (
+        block.x as i32 + dx,
+        block.y as i32 + dy,
+        block.z as i32 + dz,
+    );
+
+    if x < 0 || y < 0 || z < 0 || !structure.is_within_blocks(x as usize, y as usize, z as usize) {
+        return None;
+    }
+
+    Some(StructureBlock::new(x as usize, y as usize, z as usize))
+}
+
+/// Planets can be built on from any side, so a block is uncovered if any side of it is touching air
+fn is_uncovered(structure: &Structure, block: StructureBlock) -> bool {
+    DIRECTIONS.iter().any(|direction| {
+        neighbour(structure, block, *direction)
+            .map(|b| structure.block_id_at(b.x, b.y, b.z) == AIR_BLOCK_ID)
+            .unwrap_or(true)
+    })
+}
+
+fn grow_grass(
+    mut event_reader: EventReader<BlockTickEvent>,
+    mut query: Query<&mut Structure>,
+    blocks: Res<Registry<Block>>,
+    mut event_writer: EventWriter<BlockChangedEvent>,
+) {
+    let (Some(grass), Some(dirt)) = (
+        blocks.from_id("cosmos:grass"),
+        blocks.from_id("cosmos:dirt"),
+    ) else {
+        return;
+    };
+
+    let mut rng = rand::thread_rng();
+
+    for ev in event_reader.iter().filter(|ev| ev.block_id == grass.id()) {
+        let Ok(mut structure) = query.get_mut(ev.structure_entity) else {
+            continue;
+        };
+
+        let (x, y, z) = (ev.block.x, ev.block.y, ev.block.z);
+
+        // An earlier tick this frame may have already changed this block
+        if structure.block_id_at(x, y, z) != grass.id() {
+            continue;
+        }
+
+        if !is_uncovered(&structure, ev.block) {
+            let rotation = structure.block_rotation(x, y, z);
+
+            structure.set_block_at(x, y, z, dirt, rotation, &blocks, Some(&mut event_writer));
+
+            continue;
+        }
+
+        let direction = DIRECTIONS[rng.gen_range(0..DIRECTIONS.len())];
+
+        let Some(target) = neighbour(&structure, ev.block, direction) else {
+            continue;
+        };
+
+        if structure.block_id_at(target.x, target.y, target.z) == dirt.id()
+            && is_uncovered(&structure, target)
+        {
+            let rotation = structure.block_rotation(target.x, target.y, target.z);
+
+            structure.set_block_at(
+                target.x,
+                target.y,
+                target.z,
+                grass,
+                rotation,
+                &blocks,
+                Some(&mut event_writer),
+            );
+        }
+    }
+}
+
+fn register_grass_ticker(blocks: Res<Registry<Block>>, mut tickers: ResMut<Registry<BlockTicker>>) {
+    if let Some(grass) = blocks.from_id("cosmos:grass") {
+        tickers.register(BlockTicker::new(grass, true));
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_system(register_grass_ticker.in_schedule(OnEnter(GameState::PostLoading)))
+        .add_system(
+            grow_grass
+                .in_set(BlockTickSet::HandleTicks)
+                .in_set(OnUpdate(GameState::Playing)),
+        );
+}
@@ -1,7 +1,10 @@
 use bevy::prelude::{App, EventReader, EventWriter, IntoSystemConfig, OnUpdate, Query, Res, With};
 use cosmos_core::{
     block::Block,
-    events::structure::change_pilot_event::ChangePilotEvent,
+    events::{
+        structure::change_pilot_event::ChangePilotEvent,
+        wrappers::cancellable_event::{CancellableEvent, CancellableEventSet},
+    },
     registry::{identifiable::Identifiable, Registry},
     structure::{
         ship::{pilot::Pilot, Ship},
@@ -13,7 +16,7 @@ use crate::{events::blocks::block_events::BlockInteractEvent, state::GameState};
 
 fn handle_block_event(
     mut interact_events: EventReader<BlockInteractEvent>,
-    mut change_pilot_event: EventWriter<ChangePilotEvent>,
+    mut change_pilot_event: EventWriter<CancellableEvent<ChangePilotEvent>>,
     s_query: Query<&Structure, With<Ship>>,
     pilot_query: Query<&Pilot>,
     blocks: Res<Registry<Block>>,
@@ -30,10 +33,10 @@ fn handle_block_event(
                 // Only works on ships (maybe replace this with pilotable component instead of only checking ships)
                 // Cannot pilot a ship that already has a pilot
                 if pilot_query.get(ev.structure_entity).is_err() {
-                    change_pilot_event.send(ChangePilotEvent {
+                    change_pilot_event.send(CancellableEvent::new(ChangePilotEvent {
                         structure_entity: ev.structure_entity,
                         pilot_entity: Some(ev.interactor),
-                    });
+                    }));
                 }
             }
         }
@@ -41,5 +44,9 @@ fn handle_block_event(
 }
 
 pub(super) fn register(app: &mut App) {
-    app.add_system(handle_block_event.in_set(OnUpdate(GameState::Playing)));
+    app.add_system(
+        handle_block_event
+            .in_set(OnUpdate(GameState::Playing))
+            .in_set(CancellableEventSet::Send),
+    );
 }
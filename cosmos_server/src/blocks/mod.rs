@@ -2,8 +2,18 @@
 
 use bevy::prelude::App;
 
+pub mod block_ticks;
+pub mod drop_tables;
+mod grass;
 pub mod interactable;
+pub mod placement_rules;
+mod ship_core;
 
 pub(super) fn register(app: &mut App) {
+    block_ticks::register(app);
+    drop_tables::register(app);
+    grass::register(app);
     interactable::register(app);
+    placement_rules::register(app);
+    ship_core::register(app);
 }
@@ -0,0 +1,455 @@
+//! Rules that decide where blocks can be placed.
+//!
+//! Every block placed by a player is checked against the [`PlacementRules`] before it is placed.
+//! Rules can apply to every block or to a specific block, and more can be added by any system
+//! once the blocks are registered.
+
+use std::fmt::Display;
+
+use bevy::{
+    prelude::{App, IntoSystemAppConfig, OnEnter, Res, ResMut, Resource},
+    utils::HashMap,
+};
+use cosmos_core::{
+    block::{Block, BlockFace},
+    registry::{identifiable::Identifiable, Registry},
+    structure::Structure,
+};
+
+use crate::state::GameState;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A rule a block must follow to be placed
+pub enum PlacementRule {
+    /// The block can only be placed on ships
+    ShipOnly,
+    /// The block can only be placed on planets
+    PlanetOnly,
+    /// A structure can have at most this many of the block
+    MaxPerStructure(usize),
+    /// The block must be placed next to at least one of these blocks (by unlocalized name)
+    RequiresAdjacent(Vec<String>),
+    /// The block must be placed next to another block, so it isn't floating
+    MustBeAttached,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Why a block couldn't be placed
+pub enum PlacementRejection {
+    /// The block can only be placed on ships
+    NotOnShip,
+    /// The block can only be placed on planets
+    NotOnPlanet,
+    /// The structure already has the most of this block it can have
+    TooMany {
+        /// The most of this block a structure can have
+        max: usize,
+    },
+    /// The block isn't next to any of the blocks it needs to be next to
+    MissingAdjacent {
+        /// The blocks it could be next to
+        blocks: Vec<String>,
+    },
+    /// The block isn't next to any other block
+    NotAttached,
+}
+
+impl Display for PlacementRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotOnShip => write!(f, "This block can only be placed on a ship"),
+            Self::NotOnPlanet => write!(f, "This block can only be placed on a planet"),
+            Self::TooMany { max } => write!(f, "A structure can only have {max} of this block"),
+            Self::MissingAdjacent { blocks } => {
+                write!(
+                    f,
+                    "This block must be placed next to {}",
+                    blocks.join(" or ")
+                )
+            }
+            Self::NotAttached => write!(f, "This block must be placed next to another block"),
+        }
+    }
+}
+
+/// Everything about a block that's about to be placed
+pub struct Placement<'a> {
+    /// The structure the block is being placed on
+    pub structure: &'a Structure,
+    /// True if the structure is a ship
+    pub is_ship: bool,
+    /// True if the structure is a planet
+    pub is_planet: bool,
+    /// The block being placed
+    pub block: &'a Block,
+    /// The block's coordinates, which must be within the structure
+    pub coords: (usize, usize, usize),
+}
+
+impl<'a> Placement<'a> {
+    /// Iterates over the ids of the blocks touching this one's faces.
+    ///
+    /// Coordinates outside the structure are skipped.
+    fn adjacent_block_ids(&self) -> impl Iterator<Item = u16> + '_ {
+        let (x, y, z) = self.coords;
+
+        (0..6).filter_map(move |i| {
+            let (dx, dy, dz) = BlockFace::from_index(i).direction();
+
+            let (ax, ay, az) = (x as i32 + dx, y as i32 + dy, z as i32 + dz);
+
+            if ax < 0 || ay < 0 || az < 0 {
+                return None;
+            }
+
+            let (ax, ay, az) = (ax as usize, ay as usize, az as usize);
+
+            if self.structure.is_within_blocks(ax, ay, az) {
+                Some(self.structure.block_id_at(ax, ay, az))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+impl PlacementRule {
+    /// Checks if this placement follows this rule
+    pub fn check(
+        &self,
+        placement: &Placement,
+        blocks: &Registry<Block>,
+    ) -> Result<(), PlacementRejection> {
+        match self {
+            Self::ShipOnly => {
+                if !placement.is_ship {
+                    return Err(PlacementRejection::NotOnShip);
+                }
+            }
+            Self::PlanetOnly => {
+                if !placement.is_planet {
+                    return Err(PlacementRejection::NotOnPlanet);
+                }
+            }
+            Self::MaxPerStructure(max) => {
+                let count = placement
+                    .structure
+                    .all_blocks_iter(false)
+                    .filter(|block| block.block_id(placement.structure) == placement.block.id())
+                    .count();
+
+                if count >= *max {
+                    return Err(PlacementRejection::TooMany { max: *max });
+                }
+            }
+            Self::RequiresAdjacent(names) => {
+                let found = placement.adjacent_block_ids().any(|id| {
+                    names
+                        .iter()
+                        .any(|name| blocks.from_numeric_id(id).unlocalized_name() == name)
+                });
+
+                if !found {
+                    return Err(PlacementRejection::MissingAdjacent {
+                        blocks: names.clone(),
+                    });
+                }
+            }
+            Self::MustBeAttached => {
+                let attached = placement
+                    .adjacent_block_ids()
+                    .any(|id| !blocks.from_numeric_id(id).is_empty());
+
+                if !attached {
+                    return Err(PlacementRejection::NotAttached);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+/// Every rule blocks must follow to be placed
+pub struct PlacementRules {
+    global: Vec<PlacementRule>,
+    per_block: HashMap<u16, Vec<PlacementRule>>,
+}
+
+impl PlacementRules {
+    /// Adds a rule that every block must follow
+    pub fn add_global_rule(&mut self, rule: PlacementRule) {
+        self.global.push(rule);
+    }
+
+    /// Adds a rule that only this block must follow
+    pub fn add_rule(&mut self, block: &Block, rule: PlacementRule) {
+        self.per_block.entry(block.id()).or_default().push(rule);
+    }
+
+    /// Iterates over every rule this block must follow
+    pub fn rules_for<'a>(&'a self, block: &Block) -> impl Iterator<Item = &'a PlacementRule> {
+        self.global
+            .iter()
+            .chain(self.per_block.get(&block.id()).into_iter().flatten())
+    }
+
+    /// Checks if this placement follows every rule for the block being placed.
+    ///
+    /// Returns the reason for the first rule it breaks.
+    pub fn check(
+        &self,
+        placement: &Placement,
+        blocks: &Registry<Block>,
+    ) -> Result<(), PlacementRejection> {
+        self.rules_for(placement.block)
+            .try_for_each(|rule| rule.check(placement, blocks))
+    }
+}
+
+fn add_default_rules(blocks: Res<Registry<Block>>, mut rules: ResMut<PlacementRules>) {
+    rules.add_global_rule(PlacementRule::MustBeAttached);
+
+    for block in blocks.iter().filter(|block| block.is_ship_only()) {
+        rules.add_rule(block, PlacementRule::ShipOnly);
+    }
+
+    if let Some(ship_core) = blocks.from_id("cosmos:ship_core") {
+        rules.add_rule(ship_core, PlacementRule::MaxPerStructure(1));
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.insert_resource(PlacementRules::default())
+        .add_system(add_default_rules.in_schedule(OnEnter(GameState::PostLoading)));
+}
+
+#[cfg(test)]
+mod test {
+    use cosmos_core::{
+        block::{BlockProperty, BlockRotation},
+        structure::chunk::CHUNK_DIMENSIONS,
+    };
+
+    use super::*;
+
+    fn blocks() -> Registry<Block> {
+        let mut blocks = Registry::<Block>::new();
+
+        blocks.register(Block::new(
+            &vec![BlockProperty::Empty],
+            0,
+            "cosmos:air".into(),
+            0.0,
+        ));
+
+        for name in ["cosmos:ship_core", "cosmos:hull", "cosmos:glass"] {
+            blocks.register(Block::new(&vec![], 0, name.into(), 1.0));
+        }
+
+        blocks
+    }
+
+    fn place(
+        structure: &mut Structure,
+        (x, y, z): (usize, usize, usize),
+        name: &str,
+        blocks: &Registry<Block>,
+    ) {
+        structure.set_block_at(
+            x,
+            y,
+            z,
+            blocks.from_id(name).unwrap(),
+            BlockRotation::IDENTITY,
+            blocks,
+            None,
+        );
+    }
+
+    fn placement<'a>(
+        structure: &'a Structure,
+        block: &'a Block,
+        coords: (usize, usize, usize),
+    ) -> Placement<'a> {
+        Placement {
+            structure,
+            is_ship: true,
+            is_planet: false,
+            block,
+            coords,
+        }
+    }
+
+    #[test]
+    fn ship_only() {
+        let blocks = blocks();
+        let structure = Structure::new(1, 1, 1);
+        let hull = blocks.from_id("cosmos:hull").unwrap();
+
+        let mut on_ship = placement(&structure, hull, (1, 1, 1));
+        assert_eq!(PlacementRule::ShipOnly.check(&on_ship, &blocks), Ok(()));
+
+        on_ship.is_ship = false;
+        on_ship.is_planet = true;
+        assert_eq!(
+            PlacementRule::ShipOnly.check(&on_ship, &blocks),
+            Err(PlacementRejection::NotOnShip)
+        );
+    }
+
+    #[test]
+    fn planet_only() {
+        let blocks = blocks();
+        let structure = Structure::new(1, 1, 1);
+        let hull = blocks.from_id("cosmos:hull").unwrap();
+
+        let mut on_planet = placement(&structure, hull, (1, 1, 1));
+        assert_eq!(
+            PlacementRule::PlanetOnly.check(&on_planet, &blocks),
+            Err(PlacementRejection::NotOnPlanet)
+        );
+
+        on_planet.is_ship = false;
+        on_planet.is_planet = true;
+        assert_eq!(PlacementRule::PlanetOnly.check(&on_planet, &blocks), Ok(()));
+    }
+
+    #[test]
+    fn only_one_ship_core() {
+        let blocks = blocks();
+        let mut structure = Structure::new(1, 1, 1);
+        let ship_core = blocks.from_id("cosmos:ship_core").unwrap();
+        let rule = PlacementRule::MaxPerStructure(1);
+
+        // Other blocks don't count towards the limit
+        place(&mut structure, (5, 5, 5), "cosmos:hull", &blocks);
+        assert_eq!(
+            rule.check(&placement(&structure, ship_core, (5, 5, 6)), &blocks),
+            Ok(())
+        );
+
+        place(&mut structure, (5, 5, 6), "cosmos:ship_core", &blocks);
+        assert_eq!(
+            rule.check(&placement(&structure, ship_core, (5, 5, 7)), &blocks),
+            Err(PlacementRejection::TooMany { max: 1 })
+        );
+
+        let hull = blocks.from_id("cosmos:hull").unwrap();
+        assert_eq!(
+            rule.check(&placement(&structure, hull, (5, 5, 7)), &blocks),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn requires_adjacent() {
+        let blocks = blocks();
+        let mut structure = Structure::new(1, 1, 1);
+        let glass = blocks.from_id("cosmos:glass").unwrap();
+        let rule = PlacementRule::RequiresAdjacent(vec!["cosmos:hull".into()]);
+
+        place(&mut structure, (5, 5, 5), "cosmos:ship_core", &blocks);
+        assert_eq!(
+            rule.check(&placement(&structure, glass, (5, 6, 5)), &blocks),
+            Err(PlacementRejection::MissingAdjacent {
+                blocks: vec!["cosmos:hull".into()]
+            })
+        );
+
+        place(&mut structure, (5, 7, 5), "cosmos:hull", &blocks);
+        assert_eq!(
+            rule.check(&placement(&structure, glass, (5, 6, 5)), &blocks),
+            Ok(())
+        );
+
+        // Diagonal blocks aren't adjacent
+        assert_eq!(
+            rule.check(&placement(&structure, glass, (6, 6, 6)), &blocks),
+            Err(PlacementRejection::MissingAdjacent {
+                blocks: vec!["cosmos:hull".into()]
+            })
+        );
+    }
+
+    #[test]
+    fn must_be_attached() {
+        let blocks = blocks();
+        let mut structure = Structure::new(1, 1, 1);
+        let hull = blocks.from_id("cosmos:hull").unwrap();
+        let rule = PlacementRule::MustBeAttached;
+
+        assert_eq!(
+            rule.check(&placement(&structure, hull, (5, 5, 5)), &blocks),
+            Err(PlacementRejection::NotAttached)
+        );
+
+        place(&mut structure, (5, 5, 4), "cosmos:ship_core", &blocks);
+        assert_eq!(
+            rule.check(&placement(&structure, hull, (5, 5, 5)), &blocks),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn must_be_attached_at_structure_edges() {
+        let blocks = blocks();
+        let mut structure = Structure::new(1, 1, 1);
+        let hull = blocks.from_id("cosmos:hull").unwrap();
+        let rule = PlacementRule::MustBeAttached;
+
+        let last = CHUNK_DIMENSIONS - 1;
+
+        // Nothing outside the structure counts as attached
+        for corner in [(0, 0, 0), (last, last, last)] {
+            assert_eq!(
+                rule.check(&placement(&structure, hull, corner), &blocks),
+                Err(PlacementRejection::NotAttached)
+            );
+        }
+
+        place(&mut structure, (1, 0, 0), "cosmos:hull", &blocks);
+        place(
+            &mut structure,
+            (last, last - 1, last),
+            "cosmos:hull",
+            &blocks,
+        );
+
+        for corner in [(0, 0, 0), (last, last, last)] {
+            assert_eq!(
+                rule.check(&placement(&structure, hull, corner), &blocks),
+                Ok(())
+            );
+        }
+    }
+
+    #[test]
+    fn first_broken_rule_is_returned() {
+        let blocks = blocks();
+        let mut structure = Structure::new(1, 1, 1);
+        let ship_core = blocks.from_id("cosmos:ship_core").unwrap();
+
+        let mut rules = PlacementRules::default();
+        rules.add_global_rule(PlacementRule::MustBeAttached);
+        rules.add_rule(ship_core, PlacementRule::MaxPerStructure(1));
+
+        place(&mut structure, (5, 5, 5), "cosmos:ship_core", &blocks);
+
+        assert_eq!(
+            rules.check(&placement(&structure, ship_core, (9, 9, 9)), &blocks),
+            Err(PlacementRejection::NotAttached)
+        );
+        assert_eq!(
+            rules.check(&placement(&structure, ship_core, (5, 5, 6)), &blocks),
+            Err(PlacementRejection::TooMany { max: 1 })
+        );
+
+        let hull = blocks.from_id("cosmos:hull").unwrap();
+        assert_eq!(
+            rules.check(&placement(&structure, hull, (5, 5, 6)), &blocks),
+            Ok(())
+        );
+    }
+}
@@ -0,0 +1,48 @@
+//! Rules for the ship core block
+
+use bevy::prelude::{App, EventReader, IntoSystemConfig, OnUpdate, Query, Res};
+use cosmos_core::{
+    block::Block,
+    events::wrappers::cancellable_event::{CancellableEvent, CancellableEventSet},
+    registry::{identifiable::Identifiable, Registry},
+    structure::Structure,
+};
+
+use crate::{events::blocks::block_events::BlockBreakEvent, state::GameState};
+
+/// Do not allow a player to mine a ship core if any other block exists on the ship
+fn prevent_breaking_ship_core(
+    mut event_reader: EventReader<CancellableEvent<BlockBreakEvent>>,
+    query: Query<&Structure>,
+    blocks: Res<Registry<Block>>,
+) {
+    for ev in event_reader.iter() {
+        let Ok(structure) = query.get(ev.structure_entity) else {
+            continue;
+        };
+
+        if ev
+            .structure_block
+            .block(structure, &blocks)
+            .unlocalized_name()
+            != "cosmos:ship_core"
+        {
+            continue;
+        }
+
+        let mut itr = structure.all_blocks_iter(false);
+
+        // ship core               some other block
+        if itr.next().is_some() && itr.next().is_some() {
+            ev.cancel();
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_system(
+        prevent_breaking_ship_core
+            .in_set(OnUpdate(GameState::Playing))
+            .in_set(CancellableEventSet::Cancel),
+    );
+}
@@ -2,8 +2,11 @@
 
 use bevy::prelude::{App, Commands, Entity, EventReader, EventWriter, Query, Res, ResMut, With};
 use cosmos_core::{
+    block::Block,
     ecs::NeedsDespawned,
+    item::Item,
     physics::location::Location,
+    registry::Registry,
     structure::{planet::Planet, ship::Ship, Structure},
 };
 
@@ -73,6 +76,8 @@ fn cosmos_command_listener(
     mut commands: Commands,
     mut command_events: EventReader<CosmosCommandSent>,
     cosmos_commands: Res<CosmosCommands>,
+    blocks: Res<Registry<Block>>,
+    items: Res<Registry<Item>>,
 
     mut structure_loaded_delayed: EventWriter<SendDelayedStructureLoadEvent>,
 
@@ -143,6 +148,8 @@ fn cosmos_command_listener(
                         ev.args[0].as_str(),
                         structure_type,
                         spawn_at,
+                        &blocks,
+                        &items,
                         &mut commands,
                         &mut structure_loaded_delayed,
                     );
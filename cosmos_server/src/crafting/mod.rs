@@ -0,0 +1,13 @@
+//! Crafting items, either by hand or at crafting stations
+
+use bevy::prelude::App;
+
+mod netty;
+pub mod requests;
+pub mod stations;
+
+pub(super) fn register(app: &mut App) {
+    netty::register(app);
+    requests::register(app);
+    stations::register(app);
+}
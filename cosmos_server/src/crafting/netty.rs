@@ -0,0 +1,59 @@
+//! Receives the crafting messages clients send
+
+use bevy::prelude::*;
+use bevy_renet::renet::RenetServer;
+use cosmos_core::{
+    crafting::{
+        netty::{CraftingClientMessages, CraftingServerMessages},
+        recipes::Recipe,
+    },
+    netty::{cosmos_encoder, NettyChannel},
+    registry::Registry,
+};
+
+use crate::{netty::network_helpers::ServerLobby, state::GameState};
+
+use super::requests::CraftRequest;
+
+pub(super) fn listen_for_crafting_messages(
+    mut server: ResMut<RenetServer>,
+    lobby: Res<ServerLobby>,
+    recipes: Res<Registry<Recipe>>,
+    mut craft_writer: EventWriter<CraftRequest>,
+) {
+    for client_id in server.clients_id().into_iter() {
+        while let Some(message) = server.receive_message(client_id, NettyChannel::Crafting.id()) {
+            let Some(player_entity) = lobby.player_from_id(client_id) else {
+                continue;
+            };
+
+            let Ok(msg) = cosmos_encoder::deserialize::<CraftingClientMessages>(&message) else {
+                warn!("Received an invalid crafting message from client {client_id}");
+                continue;
+            };
+
+            match msg {
+                CraftingClientMessages::RequestRecipes => {
+                    server.send_message(
+                        client_id,
+                        NettyChannel::Crafting.id(),
+                        cosmos_encoder::serialize(&CraftingServerMessages::Recipes {
+                            recipes: recipes.iter().cloned().collect(),
+                        }),
+                    );
+                }
+                CraftingClientMessages::Craft { recipe_id, station } => {
+                    craft_writer.send(CraftRequest {
+                        player_entity,
+                        recipe_id,
+                        station,
+                    });
+                }
+            }
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_system(listen_for_crafting_messages.in_set(OnUpdate(GameState::Playing)));
+}
@@ -0,0 +1,187 @@
+//! Validates the crafts players ask for, then either crafts them right away or queues them at their station
+
+use std::fmt;
+
+use bevy::prelude::*;
+use bevy_renet::renet::RenetServer;
+use cosmos_core::{
+    crafting::{
+        netty::CraftingServerMessages,
+        recipes::{CraftingError, Recipe},
+    },
+    entities::player::Player,
+    inventory::Inventory,
+    item::Item,
+    netty::{cosmos_encoder, NettyChannel},
+    registry::Registry,
+    structure::{structure_block::StructureBlock, Structure},
+};
+
+use crate::{inventory::container::OpenContainer, state::GameState};
+
+use super::{netty::listen_for_crafting_messages, stations::CraftingQueue};
+
+/// Sent when a player asks to craft something
+pub struct CraftRequest {
+    /// The player crafting
+    pub player_entity: Entity,
+    /// The recipe's id
+    pub recipe_id: u16,
+    /// The station they want to craft it at, if any
+    pub station: Option<(Entity, StructureBlock)>,
+}
+
+#[derive(Debug)]
+/// Why a craft was not done
+enum CraftRejection {
+    /// There is no recipe with that id
+    UnknownRecipe,
+    /// The recipe must be crafted at a station, which the player must have open
+    NeedsStation,
+    /// The station can't take any more jobs
+    QueueFull,
+    /// The player's inventory couldn't be used for the recipe
+    Crafting(CraftingError),
+}
+
+impl fmt::Display for CraftRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownRecipe => write!(f, "That recipe does not exist"),
+            Self::NeedsStation => write!(f, "That must be crafted at its station"),
+            Self::QueueFull => write!(f, "That station is busy"),
+            Self::Crafting(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for CraftRejection {}
+
+/// Queues the recipe at the station the player asked for, taking its inputs from their inventory
+fn queue_at_station(
+    recipe: &Recipe,
+    station_id: u16,
+    station: Option<(Entity, StructureBlock)>,
+    open_container: Option<&OpenContainer>,
+    inventory: &mut Inventory,
+    structure_query: &mut Query<&mut Structure>,
+) -> Result<(), CraftRejection> {
+    let Some((structure_entity, block)) = station else {
+        return Err(CraftRejection::NeedsStation);
+    };
+
+    let is_open = open_container
+        .map(|open| open.structure_entity == structure_entity && open.block == block)
+        .unwrap_or(false);
+
+    if !is_open {
+        return Err(CraftRejection::NeedsStation);
+    }
+
+    let Ok(mut structure) = structure_query.get_mut(structure_entity) else {
+        return Err(CraftRejection::NeedsStation);
+    };
+
+    if block.block_id(&structure) != station_id {
+        return Err(CraftRejection::NeedsStation);
+    }
+
+    let (x, y, z) = (block.x, block.y, block.z);
+
+    let mut queue = structure
+        .block_data::<CraftingQueue>(x, y, z)
+        .unwrap_or_default();
+
+    if queue.is_full() {
+        return Err(CraftRejection::QueueFull);
+    }
+
+    recipe
+        .take_inputs(inventory)
+        .map_err(CraftRejection::Crafting)?;
+
+    queue.push(recipe);
+    structure.set_block_data(x, y, z, &queue, None);
+
+    Ok(())
+}
+
+fn craft(
+    request: &CraftRequest,
+    open_container: Option<&OpenContainer>,
+    inventory: &mut Inventory,
+    structure_query: &mut Query<&mut Structure>,
+    recipes: &Registry<Recipe>,
+    items: &Registry<Item>,
+) -> Result<(), CraftRejection> {
+    let Some(recipe) = recipes.try_from_numeric_id(request.recipe_id) else {
+        return Err(CraftRejection::UnknownRecipe);
+    };
+
+    match recipe.station() {
+        Some(station_id) => queue_at_station(
+            recipe,
+            station_id,
+            request.station,
+            open_container,
+            inventory,
+            structure_query,
+        ),
+        None => recipe
+            .craft(inventory, items)
+            .map_err(CraftRejection::Crafting),
+    }
+}
+
+fn process_craft_requests(
+    mut event_reader: EventReader<CraftRequest>,
+    mut player_query: Query<(&Player, &mut Inventory, Option<&OpenContainer>)>,
+    mut structure_query: Query<&mut Structure>,
+    recipes: Res<Registry<Recipe>>,
+    items: Res<Registry<Item>>,
+    mut server: ResMut<RenetServer>,
+) {
+    for ev in event_reader.iter() {
+        let Ok((player, mut inventory, open_container)) = player_query.get_mut(ev.player_entity) else {
+            continue;
+        };
+
+        // Only touch the player's inventory if the craft works, so it isn't resent for nothing
+        let mut after = inventory.clone();
+
+        let message = match craft(
+            ev,
+            open_container,
+            &mut after,
+            &mut structure_query,
+            &recipes,
+            &items,
+        ) {
+            Ok(()) => {
+                *inventory = after;
+
+                CraftingServerMessages::CraftAccepted {
+                    recipe_id: ev.recipe_id,
+                }
+            }
+            Err(rejection) => CraftingServerMessages::CraftRejected {
+                recipe_id: ev.recipe_id,
+                reason: rejection.to_string(),
+            },
+        };
+
+        server.send_message(
+            player.id(),
+            NettyChannel::Crafting.id(),
+            cosmos_encoder::serialize(&message),
+        );
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_event::<CraftRequest>().add_system(
+        process_craft_requests
+            .after(listen_for_crafting_messages)
+            .in_set(OnUpdate(GameState::Playing)),
+    );
+}
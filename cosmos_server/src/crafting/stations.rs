@@ -0,0 +1,147 @@
+//! Crafting stations work through a queue of jobs over time, putting what they make into their own inventory.
+//!
+//! The queue is stored as block data on the station, so it is saved with its structure. The items a job used
+//! are taken when it is queued, so breaking a station loses any jobs it hadn't finished.
+
+use std::{collections::VecDeque, time::Duration};
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use cosmos_core::{
+    block::Block,
+    crafting::recipes::Recipe,
+    events::block_events::BlockDataChangedEvent,
+    inventory::{container::ContainerBlocks, Inventory},
+    item::Item,
+    registry::{identifiable::Identifiable, Registry},
+    structure::{
+        block_data::{BlockDataType, PrivateBlockData},
+        Structure,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::state::GameState;
+
+/// How often crafting stations make progress on their jobs
+const CRAFTING_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The most jobs a station can have queued at once
+pub const MAX_QUEUED_JOBS: usize = 8;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CraftingJob {
+    /// The recipe's unlocalized name, since numeric ids can change between loads
+    recipe: String,
+    /// How many seconds have been spent on this job
+    progress: f32,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+/// The jobs a crafting station has been given, oldest first
+pub struct CraftingQueue {
+    jobs: VecDeque<CraftingJob>,
+}
+
+impl BlockDataType for CraftingQueue {
+    const DATA_ID: &'static str = "cosmos:crafting_queue";
+}
+
+impl CraftingQueue {
+    /// Checks if this queue can't take any more jobs
+    pub fn is_full(&self) -> bool {
+        self.jobs.len() >= MAX_QUEUED_JOBS
+    }
+
+    /// Adds a job for this recipe to the end of the queue.
+    ///
+    /// The recipe's inputs should already have been taken.
+    pub fn push(&mut self, recipe: &Recipe) {
+        self.jobs.push_back(CraftingJob {
+            recipe: recipe.unlocalized_name().to_owned(),
+            progress: 0.0,
+        });
+    }
+}
+
+/// Advances every station's current job, finishing as many as the time allows.
+///
+/// A finished job whose outputs don't fit in the station waits until there is room.
+fn run_crafting_stations(
+    mut structure_query: Query<&mut Structure>,
+    recipes: Res<Registry<Recipe>>,
+    items: Res<Registry<Item>>,
+    blocks: Res<Registry<Block>>,
+    containers: Res<ContainerBlocks>,
+    mut event_writer: EventWriter<BlockDataChangedEvent>,
+) {
+    let seconds = CRAFTING_INTERVAL.as_secs_f32();
+
+    for mut structure in structure_query.iter_mut() {
+        let stations = structure
+            .all_block_data::<CraftingQueue>()
+            .collect::<Vec<_>>();
+
+        for (block, mut queue) in stations {
+            let (x, y, z) = (block.x, block.y, block.z);
+
+            let inventory = structure.block_data::<Inventory>(x, y, z).or_else(|| {
+                containers
+                    .get(block.block(&structure, &blocks))
+                    .map(|container| Inventory::new(container.slots))
+            });
+
+            let Some(mut inventory) = inventory else {
+                continue;
+            };
+
+            let mut time_left = seconds;
+            let mut made_something = false;
+
+            while let Some(job) = queue.jobs.front_mut() {
+                // The recipe was removed since this job was queued
+                let Some(recipe) = recipes.from_id(&job.recipe) else {
+                    queue.jobs.pop_front();
+                    continue;
+                };
+
+                job.progress += time_left;
+
+                if job.progress < recipe.craft_time() {
+                    break;
+                }
+
+                time_left = job.progress - recipe.craft_time();
+                job.progress = recipe.craft_time();
+
+                if recipe.give_outputs(&mut inventory, &items).is_err() {
+                    break;
+                }
+
+                queue.jobs.pop_front();
+                made_something = true;
+            }
+
+            if made_something {
+                structure.set_block_data(x, y, z, &inventory, Some(&mut event_writer));
+            }
+
+            if queue.jobs.is_empty() {
+                structure.remove_block_data::<CraftingQueue>(x, y, z, None);
+            } else {
+                structure.set_block_data(x, y, z, &queue, None);
+            }
+        }
+    }
+}
+
+fn make_queues_private(mut private_data: ResMut<PrivateBlockData>) {
+    private_data.make_private::<CraftingQueue>();
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_startup_system(make_queues_private).add_system(
+        run_crafting_stations
+            .run_if(on_timer(CRAFTING_INTERVAL))
+            .in_set(OnUpdate(GameState::Playing)),
+    );
+}
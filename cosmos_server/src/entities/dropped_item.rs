@@ -0,0 +1,284 @@
+//! Spawns, syncs, saves & picks up items dropped in the world
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{Collider, ReadMassProperties, RigidBody, Velocity};
+use bevy_renet::renet::RenetServer;
+use cosmos_core::{
+    ecs::NeedsDespawned,
+    entities::{
+        dropped_item::{DroppedItem, DROPPED_ITEM_SIZE},
+        player::Player,
+    },
+    inventory::{itemstack::ItemStack, Inventory},
+    item::Item,
+    netty::{
+        cosmos_encoder, netty_rigidbody::NettyRigidBody,
+        server_reliable_messages::ServerReliableMessages, NettyChannel,
+    },
+    persistence::LoadingDistance,
+    physics::location::Location,
+    registry::{id_map::IdMap, Registry},
+};
+
+use crate::{
+    netty::sync::entities::RequestedEntityEvent,
+    persistence::{
+        item_ids::{load_item_id_remap, save_item_ids},
+        loading::{begin_loading, done_loading, NeedsLoaded},
+        saving::{begin_saving, delete_save_file, done_saving, NeedsSaved},
+        SaveFileIdentifier, SectorsCache, SerializedData,
+    },
+    state::GameState,
+};
+
+/// How close a player has to be to a dropped item to pick it up
+const PICKUP_DISTANCE: f32 = 1.5;
+
+fn dropped_item_bundle(
+    location: Location,
+    velocity: Velocity,
+    dropped_item: DroppedItem,
+) -> impl Bundle {
+    let half_size = DROPPED_ITEM_SIZE / 2.0;
+
+    (
+        dropped_item,
+        location,
+        velocity,
+        RigidBody::Dynamic,
+        Collider::cuboid(half_size, half_size, half_size),
+        ReadMassProperties::default(),
+        LoadingDistance::new(2, 3),
+    )
+}
+
+/// Spawns a dropped item holding these items at that location
+pub fn spawn_dropped_item(
+    commands: &mut Commands,
+    location: Location,
+    velocity: Velocity,
+    item_stack: ItemStack,
+) -> Entity {
+    commands
+        .spawn(dropped_item_bundle(
+            location,
+            velocity,
+            DroppedItem::new(item_stack),
+        ))
+        .id()
+}
+
+/// Moves as much of the dropped item as fits into this inventory, keeping the stack's data.
+///
+/// Returns how many items were picked up.
+fn pick_up(dropped_item: &mut DroppedItem, inventory: &mut Inventory) -> u16 {
+    let item_stack = dropped_item.item_stack_mut();
+    let quantity = item_stack.quantity();
+
+    match inventory.insert_itemstack(item_stack.clone()) {
+        Some(leftover) => {
+            *item_stack = leftover;
+
+            quantity - item_stack.quantity()
+        }
+        None => {
+            item_stack.decrease_quantity(quantity);
+
+            quantity
+        }
+    }
+}
+
+/// Moves dropped items into the inventory of any player close enough to pick them up
+fn pick_up_items(
+    mut commands: Commands,
+    mut dropped_items: Query<
+        (
+            Entity,
+            &Location,
+            &Transform,
+            &Velocity,
+            &mut DroppedItem,
+            Option<&SaveFileIdentifier>,
+        ),
+        Without<NeedsDespawned>,
+    >,
+    mut players: Query<(&Location, &mut Inventory), With<Player>>,
+    mut server: ResMut<RenetServer>,
+    mut sectors_cache: ResMut<SectorsCache>,
+) {
+    for (entity, location, transform, velocity, mut dropped_item, save_file_identifier) in
+        dropped_items.iter_mut()
+    {
+        let mut picked_up = false;
+
+        for (player_location, mut inventory) in players.iter_mut() {
+            if location.distance_sqrd(player_location) > PICKUP_DISTANCE * PICKUP_DISTANCE {
+                continue;
+            }
+
+            // Only mark the inventory as changed if something went into it, otherwise a full inventory
+            // would be sent to every client each frame its player stands next to an item
+            if pick_up(
+                dropped_item.bypass_change_detection(),
+                inventory.bypass_change_detection(),
+            ) == 0
+            {
+                continue;
+            }
+
+            inventory.set_changed();
+            dropped_item.set_changed();
+            picked_up = true;
+
+            if dropped_item.item_stack().is_empty() {
+                commands.entity(entity).insert(NeedsDespawned);
+
+                // Otherwise it would come back the next time its sector is loaded
+                if let Some(save_file_identifier) = save_file_identifier {
+                    delete_save_file(save_file_identifier, &mut sectors_cache);
+                }
+
+                server.broadcast_message(
+                    NettyChannel::Reliable.id(),
+                    cosmos_encoder::serialize(&ServerReliableMessages::DroppedItemRemove {
+                        entity,
+                    }),
+                );
+
+                break;
+            }
+        }
+
+        // Some of the items are still left, so clients + the save file need the new quantity
+        if picked_up && !dropped_item.item_stack().is_empty() {
+            commands.entity(entity).insert(NeedsSaved);
+
+            server.broadcast_message(
+                NettyChannel::Reliable.id(),
+                cosmos_encoder::serialize(&ServerReliableMessages::DroppedItem {
+                    entity,
+                    body: NettyRigidBody::new(velocity, transform.rotation, *location),
+                    item_stack: dropped_item.item_stack().clone(),
+                }),
+            );
+        }
+    }
+}
+
+fn on_request_dropped_item(
+    mut event_reader: EventReader<RequestedEntityEvent>,
+    query: Query<(&DroppedItem, &Transform, &Location, &Velocity)>,
+    mut server: ResMut<RenetServer>,
+) {
+    for ev in event_reader.iter() {
+        if let Ok((dropped_item, transform, location, velocity)) = query.get(ev.entity) {
+            server.send_message(
+                ev.client_id,
+                NettyChannel::Reliable.id(),
+                cosmos_encoder::serialize(&ServerReliableMessages::DroppedItem {
+                    entity: ev.entity,
+                    body: NettyRigidBody::new(velocity, transform.rotation, *location),
+                    item_stack: dropped_item.item_stack().clone(),
+                }),
+            );
+        }
+    }
+}
+
+fn on_save_dropped_item(
+    mut query: Query<(&mut SerializedData, &DroppedItem), With<NeedsSaved>>,
+    items: Res<Registry<Item>>,
+) {
+    if query.is_empty() {
+        return;
+    }
+
+    let item_ids = IdMap::from_registry(&items);
+
+    for (mut s_data, dropped_item) in query.iter_mut() {
+        s_data.serialize_data("cosmos:dropped_item", dropped_item);
+        save_item_ids(&mut s_data, &item_ids);
+    }
+}
+
+fn on_load_dropped_item(
+    query: Query<(Entity, &SerializedData), With<NeedsLoaded>>,
+    items: Res<Registry<Item>>,
+    mut commands: Commands,
+) {
+    for (entity, s_data) in query.iter() {
+        let Some(mut dropped_item) = s_data.deserialize_data::<DroppedItem>("cosmos:dropped_item") else {
+            continue;
+        };
+
+        if let Some(remap) = load_item_id_remap(s_data, &items, "Dropped item") {
+            dropped_item.item_stack_mut().remap_item_id(&remap);
+        }
+
+        let location = s_data
+            .deserialize_data("cosmos:location")
+            .expect("Every dropped item should have a location when saved!");
+
+        let velocity = s_data
+            .deserialize_data("cosmos:velocity")
+            .unwrap_or(Velocity::zero());
+
+        commands
+            .entity(entity)
+            .insert(dropped_item_bundle(location, velocity, dropped_item));
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems((
+        pick_up_items.in_set(OnUpdate(GameState::Playing)),
+        on_request_dropped_item,
+        on_save_dropped_item.after(begin_saving).before(done_saving),
+        on_load_dropped_item
+            .after(begin_loading)
+            .before(done_loading),
+    ));
+}
+
+#[cfg(test)]
+mod test {
+    use cosmos_core::inventory::itemstack::ItemStackData;
+
+    use super::*;
+
+    #[test]
+    fn picked_up_items_keep_their_data() {
+        let mut items = Registry::<Item>::new();
+        items.register(Item::new("cosmos:drill".into(), 1).with_durability(100));
+        items.register(Item::new("cosmos:stone".into(), 64));
+
+        let drill = items.from_id("cosmos:drill").unwrap();
+        let stone = items.from_id("cosmos:stone").unwrap();
+
+        let data = ItemStackData {
+            durability: Some(37),
+            custom_name: Some("Old Faithful".into()),
+            stored_energy: None,
+        };
+
+        let mut dropped_item =
+            DroppedItem::new(ItemStack::with_quantity(drill, 1).with_data(Some(data.clone())));
+
+        // Nothing fits in a full inventory
+        let mut full = Inventory::new(1);
+        full.insert(stone, 64);
+
+        assert_eq!(pick_up(&mut dropped_item, &mut full), 0);
+        assert_eq!(dropped_item.item_stack().data(), Some(&data));
+
+        let mut inventory = Inventory::new(1);
+
+        assert_eq!(pick_up(&mut dropped_item, &mut inventory), 1);
+        assert!(dropped_item.item_stack().is_empty());
+        assert_eq!(
+            inventory.itemstack_at(0).and_then(|is| is.data()),
+            Some(&data)
+        );
+    }
+}
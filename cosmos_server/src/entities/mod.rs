@@ -1,3 +1,10 @@
 //! Contains all server information about various entities
 
+use bevy::prelude::App;
+
+pub mod dropped_item;
 pub mod player;
+
+pub(super) fn register(app: &mut App) {
+    dropped_item::register(app);
+}
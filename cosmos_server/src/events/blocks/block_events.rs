@@ -1,22 +1,40 @@
 //! Contains the various types of block events
 
 use bevy::prelude::*;
+use bevy_rapier3d::prelude::Velocity;
 use bevy_renet::renet::RenetServer;
 use cosmos_core::{
-    block::{Block, BlockFace},
+    block::{Block, BlockRotation},
     blockitems::BlockItems,
     entities::player::Player,
-    events::block_events::BlockChangedEvent,
+    events::{
+        block_events::{BlockChangedBatchEvent, BlockChangedEvent, BlockDataChangedEvent},
+        wrappers::cancellable_event::{CancellableEvent, CancellableEventSet},
+    },
     inventory::Inventory,
     item::Item,
     netty::{cosmos_encoder, server_reliable_messages::ServerReliableMessages, NettyChannel},
-    registry::{identifiable::Identifiable, Registry},
-    structure::{structure_block::StructureBlock, Structure},
+    physics::location::Location,
+    registry::Registry,
+    structure::{
+        block_data::PrivateBlockData, block_edit_batch::BlockEdit, events::StructureResizedEvent,
+        planet::Planet, ship::Ship, structure_block::StructureBlock, Structure,
+    },
 };
 
-use crate::GameState;
+use crate::{
+    blocks::{
+        drop_tables::{roll_drops, DropSource, DropTable},
+        placement_rules::{Placement, PlacementRules},
+    },
+    entities::dropped_item::spawn_dropped_item,
+    inventory::container::drop_container_contents,
+    GameState,
+};
 
 /// This is sent whenever a player breaks a block
+///
+/// This is sent as a [`CancellableEvent`], so any system may stop the block from being broken.
 pub struct BlockBreakEvent {
     /// The entity that was targeted
     pub structure_entity: Entity,
@@ -37,6 +55,8 @@ pub struct BlockInteractEvent {
 }
 
 /// This is sent whenever a player places a block
+///
+/// This is sent as a [`CancellableEvent`], so any system may stop the block from being placed.
 pub struct BlockPlaceEvent {
     /// The structure the block was placed on
     pub structure_entity: Entity,
@@ -44,8 +64,8 @@ pub struct BlockPlaceEvent {
     pub structure_block: StructureBlock,
     /// The placed block's id
     pub block_id: u16,
-    /// The block's top face
-    pub block_up: BlockFace,
+    /// The block's rotation
+    pub block_rotation: BlockRotation,
     /// The inventory slot this block came from
     pub inventory_slot: usize,
     /// The player who placed this block
@@ -53,63 +73,78 @@ pub struct BlockPlaceEvent {
 }
 
 fn handle_block_break_events(
-    mut query: Query<&mut Structure>,
-    mut event_reader: EventReader<BlockBreakEvent>,
+    mut query: Query<(&mut Structure, &GlobalTransform, &Location)>,
+    mut event_reader: EventReader<CancellableEvent<BlockBreakEvent>>,
     blocks: Res<Registry<Block>>,
     items: Res<Registry<Item>>,
-    block_items: Res<BlockItems>, // TODO: Replace this with drop table
+    drop_tables: Res<Registry<DropTable>>,
     mut inventory_query: Query<&mut Inventory>,
     mut event_writer: EventWriter<BlockChangedEvent>,
+    mut commands: Commands,
 ) {
-    for ev in event_reader.iter() {
-        if let Ok(mut structure) = query.get_mut(ev.structure_entity) {
-            let block = ev.structure_block.block(&structure, &blocks);
+    let mut rng = rand::thread_rng();
 
-            // Eventually seperate this into another event lsitener that some how interacts with this one
-            // Idk if bevy supports this yet without some hacky stuff?
-            if block.unlocalized_name() == "cosmos:ship_core" {
-                let mut itr = structure.all_blocks_iter(false);
+    for ev in event_reader.iter().filter(|ev| !ev.is_cancelled()) {
+        if let Ok((mut structure, g_trans, location)) = query.get_mut(ev.structure_entity) {
+            let (x, y, z) = (
+                ev.structure_block.x,
+                ev.structure_block.y,
+                ev.structure_block.z,
+            );
 
-                // ship core               some other block
-                if itr.next().is_some() && itr.next().is_some() {
-                    // Do not allow player to mine ship core if another block exists on the ship
-                    return;
-                }
-            }
+            let block = ev.structure_block.block(&structure, &blocks);
 
-            let block_id = ev.structure_block.block_id(&structure);
+            let drops = roll_drops(
+                block,
+                DropSource::Player(ev.breaker),
+                &drop_tables,
+                &items,
+                &mut rng,
+            );
 
-            if let Ok(mut inventory) = inventory_query.get_mut(ev.breaker) {
-                let block = blocks.from_numeric_id(block_id);
+            let mut inventory = inventory_query.get_mut(ev.breaker).ok();
 
-                if let Some(item_id) = block_items.item_from_block(block) {
-                    let item = items.from_numeric_id(item_id);
+            for item_stack in drops {
+                let leftover = match inventory.as_mut() {
+                    Some(inventory) => inventory.insert_itemstack(item_stack),
+                    None => Some(item_stack),
+                };
 
-                    inventory.insert(item, 1);
+                // Whatever doesn't fit in the player's inventory is left where the block was
+                if let Some(leftover) = leftover {
+                    spawn_dropped_item(
+                        &mut commands,
+                        structure.block_world_location(x, y, z, g_trans, location),
+                        Velocity::zero(),
+                        leftover,
+                    );
                 }
             }
 
-            structure.remove_block_at(
-                ev.structure_block.x,
-                ev.structure_block.y,
-                ev.structure_block.z,
-                &blocks,
-                Some(&mut event_writer),
+            drop_container_contents(
+                &mut commands,
+                &structure,
+                ev.structure_block,
+                structure.block_world_location(x, y, z, g_trans, location),
             );
+
+            structure.remove_block_at(x, y, z, &blocks, Some(&mut event_writer));
         }
     }
 }
 
 fn handle_block_place_events(
-    mut query: Query<&mut Structure>,
-    mut event_reader: EventReader<BlockPlaceEvent>,
+    mut query: Query<(&mut Structure, Option<&Ship>, Option<&Planet>)>,
+    mut event_reader: EventReader<CancellableEvent<BlockPlaceEvent>>,
     mut event_writer: EventWriter<BlockChangedEvent>,
     mut inventory_query: Query<(&mut Inventory, &Player)>,
     items: Res<Registry<Item>>,
     blocks: Res<Registry<Block>>,
     block_items: Res<BlockItems>,
+    placement_rules: Res<PlacementRules>,
+    mut server: ResMut<RenetServer>,
 ) {
-    for ev in event_reader.iter() {
+    for ev in event_reader.iter().filter(|ev| !ev.is_cancelled()) {
         if let Ok((mut inv, player)) = inventory_query.get_mut(ev.placer) {
             if let Some(is) = inv.itemstack_at(ev.inventory_slot) {
                 let item = items.from_numeric_id(is.item_id());
@@ -125,15 +160,52 @@ fn handle_block_place_events(
 
                     let block = blocks.from_numeric_id(block_id);
 
-                    if let Ok(mut structure) = query.get_mut(ev.structure_entity) {
-                        inv.decrease_quantity_at(ev.inventory_slot, 1);
-
-                        structure.set_block_at(
+                    if let Ok((mut structure, ship, planet)) = query.get_mut(ev.structure_entity) {
+                        let (x, y, z) = (
                             ev.structure_block.x,
                             ev.structure_block.y,
                             ev.structure_block.z,
+                        );
+
+                        if !structure.is_within_blocks(x, y, z) {
+                            continue;
+                        }
+
+                        let placement = Placement {
+                            structure: &structure,
+                            is_ship: ship.is_some(),
+                            is_planet: planet.is_some(),
+                            block,
+                            coords: (x, y, z),
+                        };
+
+                        if let Err(rejection) = placement_rules.check(&placement, &blocks) {
+                            // The client already took the block out of its inventory, so it needs the real one again
+                            inv.set_changed();
+
+                            server.send_message(
+                                player.id(),
+                                NettyChannel::Reliable.id(),
+                                cosmos_encoder::serialize(
+                                    &ServerReliableMessages::BlockPlaceRejected {
+                                        structure_entity: ev.structure_entity,
+                                        reason: rejection.to_string(),
+                                    },
+                                ),
+                            );
+
+                            break;
+                        }
+
+                        inv.decrease_quantity_at(ev.inventory_slot, 1);
+
+                        // Ships grow to fit this block once every block event this frame has been handled
+                        structure.set_block_at(
+                            x,
+                            y,
+                            z,
                             block,
-                            ev.block_up,
+                            ev.block_rotation,
                             &blocks,
                             Some(&mut event_writer),
                         );
@@ -159,19 +231,105 @@ fn handle_block_changed_event(
                 y: ev.block.y() as u32,
                 z: ev.block.z() as u32,
                 block_id: ev.new_block,
-                block_up: ev.new_block_up,
+                block_rotation: ev.new_block_rotation,
+            }),
+        );
+    }
+}
+
+fn handle_structure_resized_event(
+    mut event_reader: EventReader<StructureResizedEvent>,
+    mut server: ResMut<RenetServer>,
+) {
+    for ev in event_reader.iter() {
+        let (nx, ny, nz) = ev.negative;
+        let (px, py, pz) = ev.positive;
+
+        server.broadcast_message(
+            NettyChannel::Reliable.id(),
+            cosmos_encoder::serialize(&ServerReliableMessages::StructureResized {
+                structure_entity: ev.structure_entity,
+                negative: (nx as u32, ny as u32, nz as u32),
+                positive: (px as u32, py as u32, pz as u32),
+            }),
+        );
+    }
+}
+
+fn handle_block_changed_batch_event(
+    mut event_reader: EventReader<BlockChangedBatchEvent>,
+    mut server: ResMut<RenetServer>,
+) {
+    for ev in event_reader.iter() {
+        server.broadcast_message(
+            NettyChannel::Reliable.id(),
+            cosmos_encoder::serialize(&ServerReliableMessages::BlockChangeBatch {
+                structure_entity: ev.structure_entity,
+                changes: ev
+                    .changes
+                    .iter()
+                    .map(|change| BlockEdit {
+                        block: change.block,
+                        block_id: change.new_block,
+                        block_rotation: change.new_block_rotation,
+                    })
+                    .collect(),
+            }),
+        );
+    }
+}
+
+fn handle_block_data_changed_event(
+    mut event_reader: EventReader<BlockDataChangedEvent>,
+    query: Query<&Structure>,
+    mut server: ResMut<RenetServer>,
+    private_data: Res<PrivateBlockData>,
+) {
+    for ev in event_reader
+        .iter()
+        .filter(|ev| !private_data.is_private(&ev.data_id))
+    {
+        let Ok(structure) = query.get(ev.structure_entity) else {
+            continue;
+        };
+
+        server.broadcast_message(
+            NettyChannel::Reliable.id(),
+            cosmos_encoder::serialize(&ServerReliableMessages::BlockDataChanged {
+                structure_entity: ev.structure_entity,
+                block: ev.block,
+                data_id: ev.data_id.clone(),
+                data: structure
+                    .raw_block_data(ev.block.x, ev.block.y, ev.block.z, &ev.data_id)
+                    .map(|data| data.to_vec()),
             }),
         );
     }
 }
 
 pub(super) fn register(app: &mut App) {
-    app.add_event::<BlockBreakEvent>()
-        .add_event::<BlockPlaceEvent>()
+    app.add_event::<CancellableEvent<BlockBreakEvent>>()
+        .add_event::<CancellableEvent<BlockPlaceEvent>>()
         .add_event::<BlockInteractEvent>()
         .add_systems((
-            handle_block_break_events.in_set(OnUpdate(GameState::Playing)),
-            handle_block_place_events.in_set(OnUpdate(GameState::Playing)),
+            handle_block_break_events
+                .in_set(OnUpdate(GameState::Playing))
+                .in_set(CancellableEventSet::Apply),
+            handle_block_place_events
+                .in_set(OnUpdate(GameState::Playing))
+                .in_set(CancellableEventSet::Apply),
+            // Ships grow at the end of a frame, so clients need to know about it before any of the next frame's
+            // block changes that use the new coordinates
+            handle_structure_resized_event
+                .in_set(OnUpdate(GameState::Playing))
+                .before(handle_block_changed_event)
+                .before(handle_block_changed_batch_event),
             handle_block_changed_event.in_set(OnUpdate(GameState::Playing)),
+            handle_block_changed_batch_event.in_set(OnUpdate(GameState::Playing)),
+            // Changing a block clears its data, so the data has to be sent after any block changes
+            handle_block_data_changed_event
+                .in_set(OnUpdate(GameState::Playing))
+                .after(handle_block_changed_event)
+                .after(handle_block_changed_batch_event),
         ));
 }
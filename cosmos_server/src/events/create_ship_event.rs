@@ -2,6 +2,7 @@
 
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::Velocity;
+use cosmos_core::events::wrappers::cancellable_event::{CancellableEvent, CancellableEventSet};
 use cosmos_core::physics::location::Location;
 use cosmos_core::structure::{ship::ship_builder::TShipBuilder, Structure};
 
@@ -9,6 +10,8 @@ use crate::structure::ship::{loading::ShipNeedsCreated, server_ship_builder::Ser
 use crate::GameState;
 
 /// This event is done when a ship is being created
+///
+/// This is sent as a [`CancellableEvent`], so any system may stop the ship from being created.
 pub struct CreateShipEvent {
     /// Starting location of the ship
     pub ship_location: Location,
@@ -16,8 +19,11 @@ pub struct CreateShipEvent {
     pub rotation: Quat,
 }
 
-fn event_reader(mut event_reader: EventReader<CreateShipEvent>, mut commands: Commands) {
-    for ev in event_reader.iter() {
+fn event_reader(
+    mut event_reader: EventReader<CancellableEvent<CreateShipEvent>>,
+    mut commands: Commands,
+) {
+    for ev in event_reader.iter().filter(|ev| !ev.is_cancelled()) {
         let mut entity = commands.spawn_empty();
 
         let mut structure = Structure::new(10, 10, 10);
@@ -36,6 +42,10 @@ fn event_reader(mut event_reader: EventReader<CreateShipEvent>, mut commands: Co
 }
 
 pub(super) fn register(app: &mut App) {
-    app.add_event::<CreateShipEvent>()
-        .add_system(event_reader.in_set(OnUpdate(GameState::Playing)));
+    app.add_event::<CancellableEvent<CreateShipEvent>>()
+        .add_system(
+            event_reader
+                .in_set(OnUpdate(GameState::Playing))
+                .in_set(CancellableEventSet::Apply),
+        );
 }
@@ -3,6 +3,8 @@
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 use bevy_renet::renet::{RenetServer, ServerEvent};
+use cosmos_core::block::Block;
+use cosmos_core::content::ContentPacks;
 use cosmos_core::ecs::NeedsDespawned;
 use cosmos_core::entities::player::render_distance::RenderDistance;
 use cosmos_core::inventory::Inventory;
@@ -11,6 +13,7 @@ use cosmos_core::netty::cosmos_encoder;
 use cosmos_core::netty::server_reliable_messages::ServerReliableMessages;
 use cosmos_core::physics::location::{Location, Sector};
 use cosmos_core::physics::player_world::WorldWithin;
+use cosmos_core::registry::id_map::IdMap;
 use cosmos_core::registry::Registry;
 use cosmos_core::structure::chunk::CHUNK_DIMENSIONSF;
 use cosmos_core::{
@@ -112,6 +115,8 @@ fn handle_events_system(
     )>,
     player_worlds: Query<(&Location, &WorldWithin, &PhysicsWorld), (With<Player>, Without<Parent>)>,
     items: Res<Registry<Item>>,
+    blocks: Res<Registry<Block>>,
+    packs: Res<ContentPacks>,
     mut visualizer: ResMut<RenetServerVisualizer<200>>,
     mut rapier_context: ResMut<RapierContext>,
 ) {
@@ -121,6 +126,17 @@ fn handle_events_system(
                 println!("Client {id} connected");
                 visualizer.add_client(*id);
 
+                // This has to be sent first, since the client needs it to understand any ids sent after it
+                server.send_message(
+                    *id,
+                    NettyChannel::Reliable.id(),
+                    cosmos_encoder::serialize(&ServerReliableMessages::RegistrySync {
+                        packs: packs.ids(),
+                        blocks: IdMap::from_registry(&blocks),
+                        items: IdMap::from_registry(&items),
+                    }),
+                );
+
                 for (entity, player, transform, location, velocity, inventory, render_distance) in
                     players.iter()
                 {
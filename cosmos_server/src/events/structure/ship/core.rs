@@ -1,62 +1,134 @@
-use bevy::prelude::*;
+//! Destroys ships that are melting down.
+//!
+//! A melting down ship loses its blocks over [`MeltdownSettings::duration`] seconds, then explodes & is removed for good.
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
 use bevy_renet::renet::RenetServer;
 use cosmos_core::{
     block::Block,
     ecs::NeedsDespawned,
-    events::{block_events::BlockChangedEvent, structure::change_pilot_event::ChangePilotEvent},
+    events::{
+        block_events::BlockChangedBatchEvent,
+        structure::change_pilot_event::ChangePilotEvent,
+        wrappers::cancellable_event::{CancellableEvent, CancellableEventSet},
+    },
     netty::{cosmos_encoder, server_reliable_messages::ServerReliableMessages, NettyChannel},
+    physics::{explosion::ExplosionEvent, location::Location},
     registry::Registry,
     structure::{
+        block_edit_batch::BlockEditBatch,
         ship::{core::MeltingDown, pilot::Pilot},
+        structure_block::StructureBlock,
         Structure,
     },
 };
+use rand::seq::SliceRandom;
+
+use crate::{
+    persistence::{saving::delete_save_file, SaveFileIdentifier, SectorsCache},
+    state::GameState,
+};
+
+/// How often a melting down ship loses blocks
+const MELTDOWN_STEP: Duration = Duration::from_millis(250);
 
-use crate::state::GameState;
+#[derive(Resource, Debug, Clone, Copy)]
+/// Controls how ships melt down once their core is destroyed
+pub struct MeltdownSettings {
+    /// How many seconds it takes for a ship to completely melt down
+    pub duration: f32,
+    /// The power of the explosion a ship makes once it has completely melted down
+    pub explosion_power: f32,
+}
+
+impl Default for MeltdownSettings {
+    fn default() -> Self {
+        Self {
+            duration: 20.0,
+            explosion_power: 400.0,
+        }
+    }
+}
 
 fn on_melting_down(
     mut commands: Commands,
-    mut query: Query<(Entity, &mut Structure, &mut MeltingDown)>,
-    mut event_writer: EventWriter<BlockChangedEvent>,
+    mut query: Query<(
+        Entity,
+        &mut Structure,
+        &mut MeltingDown,
+        &Location,
+        Option<&SaveFileIdentifier>,
+    )>,
+    mut event_writer: EventWriter<BlockChangedBatchEvent>,
+    mut explosion_event_writer: EventWriter<ExplosionEvent>,
     blocks: Res<Registry<Block>>,
-    time: Res<Time>,
+    settings: Res<MeltdownSettings>,
     pilot_query: Query<&Pilot>,
-    mut change_pilot_event: EventWriter<ChangePilotEvent>,
+    mut change_pilot_event: EventWriter<CancellableEvent<ChangePilotEvent>>,
+    mut sectors_cache: ResMut<SectorsCache>,
     mut server: ResMut<RenetServer>,
 ) {
-    for (entity, mut structure, mut melting_down) in query.iter_mut() {
+    let step = MELTDOWN_STEP.as_secs_f32();
+    let mut rng = rand::thread_rng();
+
+    for (entity, mut structure, mut melting_down, location, save_file_identifier) in
+        query.iter_mut()
+    {
         if pilot_query.contains(entity) {
-            change_pilot_event.send(ChangePilotEvent {
+            change_pilot_event.send(CancellableEvent::new(ChangePilotEvent {
                 structure_entity: entity,
                 pilot_entity: None,
-            });
+            }));
         }
 
-        if melting_down.0 >= 1.0 {
-            melting_down.0 -= 1.0;
-
-            if let Some(block) = structure.all_blocks_iter(false).next() {
-                structure.remove_block_at(
-                    block.x,
-                    block.y,
-                    block.z,
-                    &blocks,
-                    Some(&mut event_writer),
-                );
-            } else {
-                commands.entity(entity).insert(NeedsDespawned);
-
-                server.broadcast_message(
-                    NettyChannel::Reliable.id(),
-                    cosmos_encoder::serialize(&ServerReliableMessages::StructureRemove { entity }),
-                );
+        melting_down.0 += step;
+
+        if melting_down.0 < settings.duration {
+            let mut remaining = structure
+                .all_blocks_iter(false)
+                .collect::<Vec<StructureBlock>>();
+
+            // Spread the remaining blocks out evenly over the time left, so the last block goes right before the explosion
+            let steps_left = ((settings.duration - melting_down.0) / step).max(1.0);
+            let amount = (remaining.len() as f32 / steps_left).ceil() as usize;
+
+            let (destroyed, _) = remaining.partial_shuffle(&mut rng, amount);
+
+            let mut batch = BlockEditBatch::new();
+
+            for block in destroyed.iter() {
+                batch.remove_block_at(block.x, block.y, block.z);
             }
-        }
 
-        melting_down.0 += time.delta_seconds();
+            structure.apply_block_edits(batch, &blocks, Some(&mut event_writer));
+        } else {
+            explosion_event_writer.send(ExplosionEvent::new(
+                *location,
+                settings.explosion_power,
+                Some(entity),
+            ));
+
+            if let Some(save_file_identifier) = save_file_identifier {
+                delete_save_file(save_file_identifier, &mut sectors_cache);
+            }
+
+            commands.entity(entity).insert(NeedsDespawned);
+
+            server.broadcast_message(
+                NettyChannel::Reliable.id(),
+                cosmos_encoder::serialize(&ServerReliableMessages::StructureRemove { entity }),
+            );
+        }
     }
 }
 
 pub(super) fn register(app: &mut App) {
-    app.add_system(on_melting_down.in_set(OnUpdate(GameState::Playing)));
+    app.insert_resource(MeltdownSettings::default()).add_system(
+        on_melting_down
+            .run_if(on_timer(MELTDOWN_STEP))
+            .in_set(OnUpdate(GameState::Playing))
+            .in_set(CancellableEventSet::Send),
+    );
 }
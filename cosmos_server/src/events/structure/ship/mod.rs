@@ -3,7 +3,10 @@
 use bevy::prelude::{App, Entity, EventReader, IntoSystemConfig, OnUpdate, Query, ResMut};
 use bevy_renet::renet::RenetServer;
 use cosmos_core::{
-    events::structure::change_pilot_event::ChangePilotEvent,
+    events::{
+        structure::change_pilot_event::ChangePilotEvent,
+        wrappers::cancellable_event::{CancellableEvent, CancellableEventSet},
+    },
     netty::{
         cosmos_encoder, server_reliable_messages::ServerReliableMessages,
         server_unreliable_messages::ServerUnreliableMessages, NettyChannel,
@@ -13,7 +16,7 @@ use cosmos_core::{
 
 use crate::state::GameState;
 
-mod core;
+pub mod core;
 
 #[derive(Debug)]
 /// This event is sent when the ship's movement is set
@@ -45,10 +48,10 @@ fn monitor_set_movement_events(
 }
 
 fn monitor_pilot_changes(
-    mut event_reader: EventReader<ChangePilotEvent>,
+    mut event_reader: EventReader<CancellableEvent<ChangePilotEvent>>,
     mut server: ResMut<RenetServer>,
 ) {
-    for ev in event_reader.iter() {
+    for ev in event_reader.iter().filter(|ev| !ev.is_cancelled()) {
         server.broadcast_message(
             NettyChannel::Reliable.id(),
             cosmos_encoder::serialize(&ServerReliableMessages::PilotChange {
@@ -63,7 +66,9 @@ pub(super) fn register(app: &mut App) {
     core::register(app);
 
     app.add_event::<ShipSetMovementEvent>().add_systems((
-        monitor_pilot_changes.in_set(OnUpdate(GameState::Playing)),
+        monitor_pilot_changes
+            .in_set(OnUpdate(GameState::Playing))
+            .in_set(CancellableEventSet::Apply),
         monitor_set_movement_events.in_set(OnUpdate(GameState::Playing)),
     ));
 }
@@ -3,9 +3,14 @@
 use std::fs;
 
 use bevy::prelude::*;
-use cosmos_core::{netty::cosmos_encoder, utils::resource_wrapper::ResourceWrapper};
+use cosmos_core::{
+    netty::{cosmos_encoder, world_tick::WorldTick},
+    utils::resource_wrapper::ResourceWrapper,
+};
 use serde::{Deserialize, Serialize};
 
+use crate::persistence::saving::{begin_saving, done_saving, NeedsSaved};
+
 #[derive(Debug, Resource, Deref, Serialize, Deserialize, Clone, Copy)]
 /// This sets the seed the server uses to generate the universe
 pub struct ServerSeed(u64);
@@ -22,6 +27,18 @@ impl ServerSeed {
     }
 }
 
+/// Saved block ticks are scheduled for a specific world tick, so the world tick
+/// is saved whenever anything else is to keep them in sync.
+fn save_world_tick(query: Query<(), With<NeedsSaved>>, world_tick: Res<WorldTick>) {
+    if query.is_empty() {
+        return;
+    }
+
+    if let Err(e) = fs::write("./world/tick.dat", cosmos_encoder::serialize(&*world_tick)) {
+        eprintln!("Error writing file './world/tick.dat' - {e}");
+    }
+}
+
 pub(super) fn register(app: &mut App) {
     let server_seed = if let Ok(seed) = fs::read("./world/seed.dat") {
         cosmos_encoder::deserialize::<ServerSeed>(&seed)
@@ -36,8 +53,17 @@ pub(super) fn register(app: &mut App) {
         seed
     };
 
+    let world_tick = if let Ok(tick) = fs::read("./world/tick.dat") {
+        cosmos_encoder::deserialize::<WorldTick>(&tick)
+            .expect("Unable to understand './world/tick.dat' tick file. Is it corrupted?")
+    } else {
+        WorldTick::new(0)
+    };
+
     app.insert_resource(ResourceWrapper(noise::OpenSimplex::new(
         server_seed.as_u32(),
     )))
-    .insert_resource(server_seed);
+    .insert_resource(server_seed)
+    .insert_resource(world_tick)
+    .add_system(save_world_tick.after(begin_saving).before(done_saving));
 }
@@ -0,0 +1,194 @@
+//! Opens container blocks & keeps their inventories in sync with the players that have them open
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::Velocity;
+use bevy_renet::renet::RenetServer;
+use cosmos_core::{
+    block::Block,
+    entities::player::Player,
+    events::block_events::BlockDataChangedEvent,
+    inventory::{container::ContainerBlocks, netty::InventoryServerMessages, Inventory},
+    netty::{cosmos_encoder, NettyChannel},
+    physics::location::Location,
+    registry::Registry,
+    structure::{block_data::BlockDataType, structure_block::StructureBlock, Structure},
+};
+
+use crate::{
+    entities::dropped_item::spawn_dropped_item, events::blocks::block_events::BlockInteractEvent,
+    state::GameState,
+};
+
+use super::netty::listen_for_inventory_messages;
+
+/// How far away a player can be from a container before it is closed for them
+const MAX_OPEN_DISTANCE: f32 = 12.0;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+/// The container a player has open. Only players with this are sent the container's contents.
+pub struct OpenContainer {
+    /// The structure the container is on
+    pub structure_entity: Entity,
+    /// The container block
+    pub block: StructureBlock,
+}
+
+/// Spawns everything stored in the container at this block as dropped items at that location.
+///
+/// Call this before the container block is removed, since that removes its inventory too.
+pub fn drop_container_contents(
+    commands: &mut Commands,
+    structure: &Structure,
+    block: StructureBlock,
+    location: Location,
+) {
+    let Some(inventory) = structure.block_data::<Inventory>(block.x, block.y, block.z) else {
+        return;
+    };
+
+    for item_stack in inventory.iter().flatten() {
+        spawn_dropped_item(commands, location, Velocity::zero(), item_stack.clone());
+    }
+}
+
+fn open_containers(
+    mut interact_events: EventReader<BlockInteractEvent>,
+    mut structure_query: Query<&mut Structure>,
+    player_query: Query<&Player>,
+    blocks: Res<Registry<Block>>,
+    containers: Res<ContainerBlocks>,
+    mut server: ResMut<RenetServer>,
+    mut commands: Commands,
+) {
+    for ev in interact_events.iter() {
+        let Ok(mut structure) = structure_query.get_mut(ev.structure_entity) else {
+            continue;
+        };
+
+        let Some(container) = containers.get(ev.structure_block.block(&structure, &blocks)) else {
+            continue;
+        };
+
+        let Ok(player) = player_query.get(ev.interactor) else {
+            continue;
+        };
+
+        let (x, y, z) = (
+            ev.structure_block.x,
+            ev.structure_block.y,
+            ev.structure_block.z,
+        );
+
+        // Containers are given their inventory the first time they're opened
+        if structure
+            .raw_block_data(x, y, z, Inventory::DATA_ID)
+            .is_none()
+        {
+            structure.set_block_data(x, y, z, &Inventory::new(container.slots), None);
+        }
+
+        let Some(serialized_inventory) = structure.raw_block_data(x, y, z, Inventory::DATA_ID) else {
+            continue;
+        };
+
+        commands.entity(ev.interactor).insert(OpenContainer {
+            structure_entity: ev.structure_entity,
+            block: ev.structure_block,
+        });
+
+        server.send_message(
+            player.id(),
+            NettyChannel::Inventory.id(),
+            cosmos_encoder::serialize(&InventoryServerMessages::ContainerInventory {
+                structure_entity: ev.structure_entity,
+                block: ev.structure_block,
+                serialized_inventory: serialized_inventory.to_vec(),
+            }),
+        );
+    }
+}
+
+/// Sends a container's new contents to every player that has it open
+fn sync_open_containers(
+    mut event_reader: EventReader<BlockDataChangedEvent>,
+    structure_query: Query<&Structure>,
+    players: Query<(&Player, &OpenContainer)>,
+    mut server: ResMut<RenetServer>,
+) {
+    for ev in event_reader
+        .iter()
+        .filter(|ev| ev.data_id == Inventory::DATA_ID)
+    {
+        let Ok(structure) = structure_query.get(ev.structure_entity) else {
+            continue;
+        };
+
+        // The container was removed, which closes it for everyone
+        let Some(serialized_inventory) = structure.raw_block_data(ev.block.x, ev.block.y, ev.block.z, Inventory::DATA_ID) else {
+            continue;
+        };
+
+        let message = cosmos_encoder::serialize(&InventoryServerMessages::ContainerInventory {
+            structure_entity: ev.structure_entity,
+            block: ev.block,
+            serialized_inventory: serialized_inventory.to_vec(),
+        });
+
+        for (player, open_container) in players.iter() {
+            if open_container.structure_entity == ev.structure_entity
+                && open_container.block == ev.block
+            {
+                server.send_message(player.id(), NettyChannel::Inventory.id(), message.clone());
+            }
+        }
+    }
+}
+
+/// Closes containers that no longer exist or that their player has moved too far away from
+fn close_containers(
+    players: Query<(Entity, &Player, &Location, &OpenContainer)>,
+    structure_query: Query<(&Structure, &GlobalTransform, &Location)>,
+    mut server: ResMut<RenetServer>,
+    mut commands: Commands,
+) {
+    for (player_entity, player, player_location, open_container) in players.iter() {
+        let StructureBlock { x, y, z } = open_container.block;
+
+        let still_open = structure_query
+            .get(open_container.structure_entity)
+            .map(|(structure, g_trans, location)| {
+                structure
+                    .raw_block_data(x, y, z, Inventory::DATA_ID)
+                    .is_some()
+                    && structure
+                        .block_world_location(x, y, z, g_trans, location)
+                        .distance_sqrd(player_location)
+                        <= MAX_OPEN_DISTANCE * MAX_OPEN_DISTANCE
+            })
+            .unwrap_or(false);
+
+        if still_open {
+            continue;
+        }
+
+        commands.entity(player_entity).remove::<OpenContainer>();
+
+        server.send_message(
+            player.id(),
+            NettyChannel::Inventory.id(),
+            cosmos_encoder::serialize(&InventoryServerMessages::ContainerClosed {
+                structure_entity: open_container.structure_entity,
+                block: open_container.block,
+            }),
+        );
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_systems(
+        (open_containers, sync_open_containers, close_containers)
+            .chain()
+            .after(listen_for_inventory_messages)
+            .in_set(OnUpdate(GameState::Playing)),
+    );
+}
@@ -1,9 +1,15 @@
-//! Server inventory management
+//! Server inventory management, including container blocks
 
 use bevy::prelude::App;
 
+pub mod container;
+mod netty;
 mod sync;
+pub mod transactions;
 
 pub(super) fn register(app: &mut App) {
+    container::register(app);
+    netty::register(app);
     sync::register(app);
+    transactions::register(app);
 }
@@ -0,0 +1,49 @@
+//! Receives the inventory messages clients send
+
+use bevy::prelude::*;
+use bevy_renet::renet::RenetServer;
+use cosmos_core::{
+    inventory::netty::InventoryClientMessages,
+    netty::{cosmos_encoder, NettyChannel},
+};
+
+use crate::{netty::network_helpers::ServerLobby, state::GameState};
+
+use super::{container::OpenContainer, transactions::InventoryTransactionRequest};
+
+pub(super) fn listen_for_inventory_messages(
+    mut server: ResMut<RenetServer>,
+    lobby: Res<ServerLobby>,
+    mut commands: Commands,
+    mut transaction_writer: EventWriter<InventoryTransactionRequest>,
+) {
+    for client_id in server.clients_id().into_iter() {
+        while let Some(message) = server.receive_message(client_id, NettyChannel::Inventory.id()) {
+            let Some(player_entity) = lobby.player_from_id(client_id) else {
+                continue;
+            };
+
+            let Ok(msg) = cosmos_encoder::deserialize::<InventoryClientMessages>(&message) else {
+                warn!("Received an invalid inventory message from client {client_id}");
+                continue;
+            };
+
+            match msg {
+                InventoryClientMessages::CloseContainer => {
+                    commands.entity(player_entity).remove::<OpenContainer>();
+                }
+                InventoryClientMessages::Transaction { id, transaction } => {
+                    transaction_writer.send(InventoryTransactionRequest {
+                        player_entity,
+                        id,
+                        transaction,
+                    });
+                }
+            }
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_system(listen_for_inventory_messages.in_set(OnUpdate(GameState::Playing)));
+}
@@ -0,0 +1,275 @@
+//! Validates & applies the inventory transactions players ask for, then tells them what changed
+
+use std::fmt;
+
+use bevy::prelude::*;
+use bevy_renet::renet::RenetServer;
+use cosmos_core::{
+    entities::player::Player,
+    inventory::{
+        netty::InventoryServerMessages,
+        transaction::{InventoryIdentifier, InventoryTransaction, TransactionError},
+        Inventory,
+    },
+    netty::{cosmos_encoder, NettyChannel},
+    structure::{structure_block::StructureBlock, Structure},
+};
+
+use crate::state::GameState;
+
+use super::{container::OpenContainer, netty::listen_for_inventory_messages};
+
+/// Sent when a player asks to make an inventory transaction
+pub struct InventoryTransactionRequest {
+    /// The player making the transaction
+    pub player_entity: Entity,
+    /// The id the client gave this transaction
+    pub id: u32,
+    /// The change they want to make
+    pub transaction: InventoryTransaction,
+}
+
+#[derive(Debug)]
+/// Why a transaction was not applied
+enum TransactionRejection {
+    /// The player can only change their own inventory & the container they have open
+    NoAccess,
+    /// One of the inventories does not exist, such as if the container was just broken
+    MissingInventory,
+    /// The transaction itself couldn't be applied
+    Invalid(TransactionError),
+}
+
+impl fmt::Display for TransactionRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoAccess => write!(f, "You cannot access that inventory"),
+            Self::MissingInventory => write!(f, "That inventory does not exist"),
+            Self::Invalid(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for TransactionRejection {}
+
+fn is_open(
+    open_container: Option<&OpenContainer>,
+    structure_entity: Entity,
+    block: StructureBlock,
+) -> bool {
+    open_container
+        .map(|open| open.structure_entity == structure_entity && open.block == block)
+        .unwrap_or(false)
+}
+
+/// Checks if the player can change this inventory - only their own, or the container they have open
+fn can_access(
+    player_entity: Entity,
+    open_container: Option<&OpenContainer>,
+    inventory: InventoryIdentifier,
+) -> bool {
+    match inventory {
+        InventoryIdentifier::Entity(entity) => entity == player_entity,
+        InventoryIdentifier::Container {
+            structure_entity,
+            block,
+        } => is_open(open_container, structure_entity, block),
+    }
+}
+
+fn get_inventory(
+    inventory: InventoryIdentifier,
+    inventory_query: &Query<&mut Inventory>,
+    structure_query: &Query<&mut Structure>,
+) -> Result<Inventory, TransactionRejection> {
+    match inventory {
+        InventoryIdentifier::Entity(entity) => inventory_query.get(entity).ok().cloned(),
+        InventoryIdentifier::Container {
+            structure_entity,
+            block,
+        } => structure_query
+            .get(structure_entity)
+            .ok()
+            .and_then(|structure| structure.block_data::<Inventory>(block.x, block.y, block.z)),
+    }
+    .ok_or(TransactionRejection::MissingInventory)
+}
+
+/// Stores the changed inventory, and sends the slots that changed to every player that can see this inventory
+fn set_inventory(
+    identifier: InventoryIdentifier,
+    inventory: Inventory,
+    before: &Inventory,
+    inventory_query: &mut Query<&mut Inventory>,
+    structure_query: &mut Query<&mut Structure>,
+    players: &Query<(&Player, Option<&OpenContainer>)>,
+    server: &mut RenetServer,
+) {
+    let slots = inventory.changed_slots(before);
+
+    if slots.is_empty() {
+        return;
+    }
+
+    let message = cosmos_encoder::serialize(&InventoryServerMessages::SlotsChanged {
+        inventory: identifier,
+        slots,
+    });
+
+    match identifier {
+        InventoryIdentifier::Entity(entity) => {
+            if let Ok(mut current) = inventory_query.get_mut(entity) {
+                // Only the changed slots are sent, rather than the whole inventory
+                *current.bypass_change_detection() = inventory;
+
+                // Every player knows about every other player's inventory
+                server.broadcast_message(NettyChannel::Inventory.id(), message);
+            }
+        }
+        InventoryIdentifier::Container {
+            structure_entity,
+            block,
+        } => {
+            if let Ok(mut structure) = structure_query.get_mut(structure_entity) {
+                structure.set_block_data(block.x, block.y, block.z, &inventory, None);
+
+                for (player, _) in players
+                    .iter()
+                    .filter(|(_, open)| is_open(*open, structure_entity, block))
+                {
+                    server.send_message(player.id(), NettyChannel::Inventory.id(), message.clone());
+                }
+            }
+        }
+    }
+}
+
+fn apply_transaction(
+    request: &InventoryTransactionRequest,
+    open_container: Option<&OpenContainer>,
+    inventory_query: &mut Query<&mut Inventory>,
+    structure_query: &mut Query<&mut Structure>,
+    players: &Query<(&Player, Option<&OpenContainer>)>,
+    server: &mut RenetServer,
+) -> Result<(), TransactionRejection> {
+    let from = request.transaction.from().inventory;
+    let to = request.transaction.to_inventory();
+
+    if !can_access(request.player_entity, open_container, from)
+        || !can_access(request.player_entity, open_container, to)
+    {
+        return Err(TransactionRejection::NoAccess);
+    }
+
+    let from_before = get_inventory(from, inventory_query, structure_query)?;
+    let to_before = if to != from {
+        Some(get_inventory(to, inventory_query, structure_query)?)
+    } else {
+        None
+    };
+
+    let mut from_after = from_before.clone();
+    let mut to_after = to_before.clone();
+
+    request
+        .transaction
+        .apply(&mut from_after, to_after.as_mut())
+        .map_err(TransactionRejection::Invalid)?;
+
+    set_inventory(
+        from,
+        from_after,
+        &from_before,
+        inventory_query,
+        structure_query,
+        players,
+        server,
+    );
+
+    if let (Some(to_after), Some(to_before)) = (to_after, to_before) {
+        set_inventory(
+            to,
+            to_after,
+            &to_before,
+            inventory_query,
+            structure_query,
+            players,
+            server,
+        );
+    }
+
+    Ok(())
+}
+
+/// Gets the current contents of every inventory this transaction uses that the player can access
+fn current_inventories(
+    request: &InventoryTransactionRequest,
+    open_container: Option<&OpenContainer>,
+    inventory_query: &Query<&mut Inventory>,
+    structure_query: &Query<&mut Structure>,
+) -> Vec<(InventoryIdentifier, Inventory)> {
+    let from = request.transaction.from().inventory;
+    let to = request.transaction.to_inventory();
+
+    [Some(from), (to != from).then_some(to)]
+        .into_iter()
+        .flatten()
+        .filter(|identifier| can_access(request.player_entity, open_container, *identifier))
+        .filter_map(|identifier| {
+            get_inventory(identifier, inventory_query, structure_query)
+                .ok()
+                .map(|inventory| (identifier, inventory))
+        })
+        .collect()
+}
+
+fn process_transactions(
+    mut requests: EventReader<InventoryTransactionRequest>,
+    players: Query<(&Player, Option<&OpenContainer>)>,
+    mut inventory_query: Query<&mut Inventory>,
+    mut structure_query: Query<&mut Structure>,
+    mut server: ResMut<RenetServer>,
+) {
+    for ev in requests.iter() {
+        let Ok((player, open_container)) = players.get(ev.player_entity) else {
+            continue;
+        };
+
+        let reply = match apply_transaction(
+            ev,
+            open_container,
+            &mut inventory_query,
+            &mut structure_query,
+            &players,
+            &mut server,
+        ) {
+            Ok(()) => InventoryServerMessages::TransactionAccepted { id: ev.id },
+            // Other players may have changed these inventories since the client made this transaction,
+            // so its snapshots can't be trusted to undo it
+            Err(rejection) => InventoryServerMessages::TransactionRejected {
+                id: ev.id,
+                reason: rejection.to_string(),
+                inventories: current_inventories(
+                    ev,
+                    open_container,
+                    &inventory_query,
+                    &structure_query,
+                ),
+            },
+        };
+
+        server.send_message(
+            player.id(),
+            NettyChannel::Inventory.id(),
+            cosmos_encoder::serialize(&reply),
+        );
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_event::<InventoryTransactionRequest>().add_system(
+        process_transactions
+            .after(listen_for_inventory_messages)
+            .in_set(OnUpdate(GameState::Playing)),
+    );
+}
@@ -15,6 +15,7 @@ use state::GameState;
 
 pub mod blocks;
 pub mod commands;
+pub mod crafting;
 pub mod entities;
 pub mod events;
 pub mod init;
@@ -10,16 +10,16 @@ use cosmos_core::physics::location::Location;
 use cosmos_core::structure::systems::{SystemActive, Systems};
 use cosmos_core::{
     entities::player::Player,
-    events::structure::change_pilot_event::ChangePilotEvent,
+    events::{
+        structure::change_pilot_event::ChangePilotEvent,
+        wrappers::cancellable_event::{CancellableEvent, CancellableEventSet},
+    },
     netty::{
         client_reliable_messages::ClientReliableMessages,
         client_unreliable_messages::ClientUnreliableMessages,
         server_reliable_messages::ServerReliableMessages, NettyChannel,
     },
-    structure::{
-        ship::pilot::Pilot,
-        {structure_block::StructureBlock, Structure},
-    },
+    structure::{block_data::PrivateBlockData, chunk::codec, ship::pilot::Pilot, Structure},
 };
 
 use crate::entities::player::PlayerLooking;
@@ -29,10 +29,21 @@ use crate::events::{
     structure::ship::ShipSetMovementEvent,
 };
 use crate::structure::planet::generation::planet_generator::RequestChunkEvent;
+use crate::structure::ship::growth::{block_from_client, ResizeHistory};
 
 use super::network_helpers::ServerLobby;
 use super::sync::entities::RequestedEntityEvent;
 
+fn resize_history<'a>(
+    structure_query: &'a Query<(&Structure, Option<&ResizeHistory>)>,
+    structure_entity: Entity,
+) -> Option<&'a ResizeHistory> {
+    structure_query
+        .get(structure_entity)
+        .ok()
+        .and_then(|(_, history)| history)
+}
+
 /// Bevy system that listens to almost all the messages received from the client
 ///
 /// Eventually this should be broken down into more specific functions
@@ -40,15 +51,15 @@ pub fn server_listen_messages(
     mut commands: Commands,
     mut server: ResMut<RenetServer>,
     lobby: ResMut<ServerLobby>,
-    structure_query: Query<&Structure>,
+    structure_query: Query<(&Structure, Option<&ResizeHistory>)>,
     mut systems_query: Query<&mut Systems>,
-    mut break_block_event: EventWriter<BlockBreakEvent>,
+    mut break_block_event: EventWriter<CancellableEvent<BlockBreakEvent>>,
     mut block_interact_event: EventWriter<BlockInteractEvent>,
-    mut place_block_event: EventWriter<BlockPlaceEvent>,
-    mut create_ship_event_writer: EventWriter<CreateShipEvent>,
+    mut place_block_event: EventWriter<CancellableEvent<BlockPlaceEvent>>,
+    mut create_ship_event_writer: EventWriter<CancellableEvent<CreateShipEvent>>,
 
     mut ship_movement_event_writer: EventWriter<ShipSetMovementEvent>,
-    mut pilot_change_event_writer: EventWriter<ChangePilotEvent>,
+    mut pilot_change_event_writer: EventWriter<CancellableEvent<ChangePilotEvent>>,
     pilot_query: Query<&Pilot>,
     mut change_player_query: Query<
         (
@@ -61,6 +72,7 @@ pub fn server_listen_messages(
     >,
     mut requested_entities_writer: EventWriter<RequestedEntityEvent>,
     mut request_chunk_event_writer: EventWriter<RequestChunkEvent>,
+    private_data: Res<PrivateBlockData>,
 ) {
     for client_id in server.clients_id().into_iter() {
         while let Some(message) = server.receive_message(client_id, NettyChannel::Unreliable.id()) {
@@ -118,14 +130,17 @@ pub fn server_listen_messages(
             match command {
                 ClientReliableMessages::PlayerDisconnect => {}
                 ClientReliableMessages::SendAllChunks { server_entity } => {
-                    if let Ok(structure) = structure_query.get(server_entity) {
+                    if let Ok((structure, _)) = structure_query.get(server_entity) {
                         for (_, chunk) in structure.chunks() {
                             server.send_message(
                                 client_id,
                                 NettyChannel::Reliable.id(),
                                 cosmos_encoder::serialize(&ServerReliableMessages::ChunkData {
                                     structure_entity: server_entity,
-                                    serialized_chunk: cosmos_encoder::serialize(chunk),
+                                    serialized_chunk: codec::encode_for_clients(
+                                        chunk,
+                                        &private_data,
+                                    ),
                                 }),
                             );
                         }
@@ -146,15 +161,18 @@ pub fn server_listen_messages(
                     x,
                     y,
                     z,
+                    structure_size,
                 } => {
                     if let Some(player_entity) = lobby.player_from_id(client_id) {
-                        break_block_event.send(BlockBreakEvent {
+                        break_block_event.send(CancellableEvent::new(BlockBreakEvent {
                             structure_entity,
                             breaker: player_entity,
-                            structure_block: StructureBlock::new(
-                                x as usize, y as usize, z as usize,
+                            structure_block: block_from_client(
+                                resize_history(&structure_query, structure_entity),
+                                structure_size,
+                                (x, y, z),
                             ),
-                        });
+                        }));
                     }
                 }
                 ClientReliableMessages::PlaceBlock {
@@ -163,20 +181,23 @@ pub fn server_listen_messages(
                     y,
                     z,
                     block_id,
-                    block_up,
+                    block_rotation,
                     inventory_slot,
+                    structure_size,
                 } => {
                     if let Some(player_entity) = lobby.player_from_id(client_id) {
-                        place_block_event.send(BlockPlaceEvent {
+                        place_block_event.send(CancellableEvent::new(BlockPlaceEvent {
                             structure_entity,
-                            structure_block: StructureBlock::new(
-                                x as usize, y as usize, z as usize,
+                            structure_block: block_from_client(
+                                resize_history(&structure_query, structure_entity),
+                                structure_size,
+                                (x, y, z),
                             ),
                             block_id,
-                            block_up,
+                            block_rotation,
                             inventory_slot: inventory_slot as usize,
                             placer: player_entity,
-                        });
+                        }));
                     }
                 }
                 ClientReliableMessages::InteractWithBlock {
@@ -184,10 +205,15 @@ pub fn server_listen_messages(
                     x,
                     y,
                     z,
+                    structure_size,
                 } => {
                     block_interact_event.send(BlockInteractEvent {
                         structure_entity,
-                        structure_block: StructureBlock::new(x as usize, y as usize, z as usize),
+                        structure_block: block_from_client(
+                            resize_history(&structure_query, structure_entity),
+                            structure_size,
+                            (x, y, z),
+                        ),
                         interactor: lobby.player_from_id(client_id).unwrap(),
                     });
                 }
@@ -197,10 +223,10 @@ pub fn server_listen_messages(
                             let ship_location =
                                 *location + looking.rotation.mul_vec3(Vec3::new(0.0, 0.0, 4.0));
 
-                            create_ship_event_writer.send(CreateShipEvent {
+                            create_ship_event_writer.send(CancellableEvent::new(CreateShipEvent {
                                 ship_location,
                                 rotation: looking.rotation,
-                            });
+                            }));
                         }
                     }
                 }
@@ -222,10 +248,12 @@ pub fn server_listen_messages(
                 ClientReliableMessages::StopPiloting => {
                     if let Some(player_entity) = lobby.player_from_id(client_id) {
                         if let Ok(piloting) = pilot_query.get(player_entity) {
-                            pilot_change_event_writer.send(ChangePilotEvent {
-                                structure_entity: piloting.entity,
-                                pilot_entity: None,
-                            });
+                            pilot_change_event_writer.send(CancellableEvent::new(
+                                ChangePilotEvent {
+                                    structure_entity: piloting.entity,
+                                    pilot_entity: None,
+                                },
+                            ));
                         }
                     }
                 }
@@ -252,5 +280,5 @@ pub fn server_listen_messages(
 }
 
 pub(super) fn register(app: &mut App) {
-    app.add_system(server_listen_messages);
+    app.add_system(server_listen_messages.in_set(CancellableEventSet::Send));
 }
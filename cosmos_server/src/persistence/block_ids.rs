@@ -0,0 +1,61 @@
+//! Block ids depend on the order blocks are registered in, so they can change between versions.
+//!
+//! Anything saved that contains block ids (such as a ship or planet chunk) also saves which block each id belonged to.
+//! When it's loaded, those ids are translated into the current ones, and any blocks that no longer exist are
+//! replaced with [`MISSING_BLOCK_NAME`].
+
+use bevy::prelude::warn;
+use cosmos_core::{
+    block::{blocks::MISSING_BLOCK_NAME, Block},
+    registry::{
+        id_map::{IdMap, IdRemap},
+        Registry,
+    },
+};
+
+use super::SerializedData;
+
+/// The data id the block id table is saved under
+pub const BLOCK_IDS_DATA_ID: &str = "cosmos:block_ids";
+
+/// Saves the block id table, so the block ids saved alongside it can be remapped when loaded
+pub fn save_block_ids(s_data: &mut SerializedData, block_ids: &IdMap) {
+    s_data.serialize_data(BLOCK_IDS_DATA_ID, block_ids);
+}
+
+/// Gets how to translate the block ids in this saved data into the current ones.
+///
+/// Returns None if the ids don't need to be changed. Data saved before block id tables existed is assumed to already use the current ids.
+///
+/// * `what` What is being loaded, used for the warning if any blocks are missing
+pub fn load_block_id_remap(
+    s_data: &SerializedData,
+    blocks: &Registry<Block>,
+    what: &str,
+) -> Option<IdRemap> {
+    s_data
+        .deserialize_data::<IdMap>(BLOCK_IDS_DATA_ID)
+        .and_then(|block_ids| block_id_remap(&block_ids, blocks, what))
+}
+
+/// Gets how to translate block ids saved with this table into the current ones.
+///
+/// Returns None if the ids don't need to be changed.
+///
+/// * `what` What is being loaded, used for the warning if any blocks are missing
+pub fn block_id_remap(block_ids: &IdMap, blocks: &Registry<Block>, what: &str) -> Option<IdRemap> {
+    let remap = block_ids.remap_for(blocks, MISSING_BLOCK_NAME);
+
+    if !remap.missing().is_empty() {
+        warn!(
+            "{what} contains blocks that no longer exist - they will be replaced with {MISSING_BLOCK_NAME}: {}",
+            remap.missing().join(", ")
+        );
+    }
+
+    if remap.is_identity() {
+        None
+    } else {
+        Some(remap)
+    }
+}
@@ -0,0 +1,62 @@
+//! Item ids depend on the order items are registered in, so they can change between versions.
+//!
+//! Anything saved that contains item ids (such as a dropped item or the inventories of a ship's containers) also saves
+//! which item each id belonged to. When it's loaded, those ids are translated into the current ones, and any items
+//! that no longer exist are replaced with [`MISSING_BLOCK_NAME`].
+
+use bevy::prelude::warn;
+use cosmos_core::{
+    block::blocks::MISSING_BLOCK_NAME,
+    item::Item,
+    registry::{
+        id_map::{IdMap, IdRemap},
+        Registry,
+    },
+};
+
+use super::SerializedData;
+
+/// The data id the item id table is saved under
+pub const ITEM_IDS_DATA_ID: &str = "cosmos:item_ids";
+
+/// Saves the item id table, so the item ids saved alongside it can be remapped when loaded
+pub fn save_item_ids(s_data: &mut SerializedData, item_ids: &IdMap) {
+    s_data.serialize_data(ITEM_IDS_DATA_ID, item_ids);
+}
+
+/// Gets how to translate the item ids in this saved data into the current ones.
+///
+/// Returns None if the ids don't need to be changed. Data saved before item id tables existed is assumed to already use the current ids.
+///
+/// * `what` What is being loaded, used for the warning if any items are missing
+pub fn load_item_id_remap(
+    s_data: &SerializedData,
+    items: &Registry<Item>,
+    what: &str,
+) -> Option<IdRemap> {
+    s_data
+        .deserialize_data::<IdMap>(ITEM_IDS_DATA_ID)
+        .and_then(|item_ids| item_id_remap(&item_ids, items, what))
+}
+
+/// Gets how to translate item ids saved with this table into the current ones.
+///
+/// Returns None if the ids don't need to be changed.
+///
+/// * `what` What is being loaded, used for the warning if any items are missing
+pub fn item_id_remap(item_ids: &IdMap, items: &Registry<Item>, what: &str) -> Option<IdRemap> {
+    let remap = item_ids.remap_for(items, MISSING_BLOCK_NAME);
+
+    if !remap.missing().is_empty() {
+        warn!(
+            "{what} contains items that no longer exist - they will be replaced with {MISSING_BLOCK_NAME}: {}",
+            remap.missing().join(", ")
+        );
+    }
+
+    if remap.is_identity() {
+        None
+    } else {
+        Some(remap)
+    }
+}
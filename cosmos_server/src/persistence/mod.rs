@@ -15,6 +15,8 @@ use cosmos_core::{
     physics::location::{Location, Sector},
 };
 
+pub mod block_ids;
+pub mod item_ids;
 pub mod loading;
 pub mod player_loading;
 pub mod saving;
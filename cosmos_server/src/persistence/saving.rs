@@ -83,16 +83,7 @@ pub fn done_saving(
         };
 
         if let Some(save_file_identifier) = save_file_identifier {
-            let path = save_file_identifier.get_save_file_path();
-            if fs::try_exists(&path).unwrap_or(false) {
-                fs::remove_file(path).expect("Error deleting old save file!");
-
-                if let SaveFileIdentifierType::Base((entity_id, Some(sector), load_distance)) =
-                    &save_file_identifier.identifier_type
-                {
-                    sectors_cache.remove(entity_id, *sector, *load_distance);
-                }
-            }
+            delete_save_file(save_file_identifier, &mut sectors_cache);
         }
 
         if !sd.should_save() {
@@ -135,6 +126,25 @@ pub fn done_saving(
     }
 }
 
+/// Deletes this save file if it exists, and removes it from the sectors cache.
+///
+/// Use this when something is destroyed, so it will never be loaded again.
+pub fn delete_save_file(
+    save_file_identifier: &SaveFileIdentifier,
+    sectors_cache: &mut SectorsCache,
+) {
+    let path = save_file_identifier.get_save_file_path();
+    if fs::try_exists(&path).unwrap_or(false) {
+        fs::remove_file(path).expect("Error deleting old save file!");
+
+        if let SaveFileIdentifierType::Base((entity_id, Some(sector), load_distance)) =
+            &save_file_identifier.identifier_type
+        {
+            sectors_cache.remove(entity_id, *sector, *load_distance);
+        }
+    }
+}
+
 fn write_file(save_identifier: &SaveFileIdentifier, serialized: &[u8]) -> io::Result<()> {
     let path = save_identifier.get_save_file_path();
 
@@ -0,0 +1,181 @@
+//! Damages the blocks & pushes the rigid bodies caught in explosions
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{ExternalImpulse, RigidBody};
+use cosmos_core::{
+    block::{hardness::BlockHardness, Block},
+    physics::{explosion::ExplosionEvent, location::Location},
+    registry::{identifiable::Identifiable, Registry},
+    structure::{
+        block_health::{
+            block_destroyed_event::BlockDestroyedEvent,
+            block_health_changed_event::BlockHealthChangedEvent,
+        },
+        structure_block::StructureBlock,
+        Structure,
+    },
+};
+
+use crate::state::GameState;
+
+/// How much impulse a rigid body receives per point of explosion power that reaches it
+const IMPULSE_PER_POWER: f32 = 0.5;
+
+/// Damages every block within the explosion's radius on this structure.
+///
+/// Blocks closer to the center take more damage, and harder blocks can take more damage before they break.
+/// Every block that is destroyed is returned instead of sending an event for each one.
+fn explode_structure(
+    explosion: &ExplosionEvent,
+    structure: &mut Structure,
+    structure_location: &Location,
+    global_transform: &GlobalTransform,
+    blocks: &Registry<Block>,
+    hardness_registry: &Registry<BlockHardness>,
+    health_event_writer: &mut EventWriter<BlockHealthChangedEvent>,
+) -> Vec<StructureBlock> {
+    let radius = explosion.radius();
+
+    let rotation = Quat::from_affine3(&global_transform.affine());
+    let local_center = rotation
+        .inverse()
+        .mul_vec3(structure_location.relative_coords_to(&explosion.location));
+
+    let half_size = Vec3::new(
+        structure.blocks_width() as f32,
+        structure.blocks_height() as f32,
+        structure.blocks_length() as f32,
+    ) / 2.0;
+
+    // The explosion can't reach this structure at all
+    if (local_center.abs() - half_size).max_element() > radius {
+        return Vec::new();
+    }
+
+    let (min_x, min_y, min_z) = structure.relative_coords_to_local_coords(
+        local_center.x - radius,
+        local_center.y - radius,
+        local_center.z - radius,
+    );
+    let (max_x, max_y, max_z) = structure.relative_coords_to_local_coords(
+        local_center.x + radius,
+        local_center.y + radius,
+        local_center.z + radius,
+    );
+
+    let (min_x, min_y, min_z) = (
+        min_x.max(0) as usize,
+        min_y.max(0) as usize,
+        min_z.max(0) as usize,
+    );
+    let (max_x, max_y, max_z) = (
+        (max_x.max(0) as usize).min(structure.blocks_width().saturating_sub(1)),
+        (max_y.max(0) as usize).min(structure.blocks_height().saturating_sub(1)),
+        (max_z.max(0) as usize).min(structure.blocks_length().saturating_sub(1)),
+    );
+
+    let mut destroyed = Vec::new();
+
+    for z in min_z..=max_z {
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                if !structure.has_block_at(x, y, z) {
+                    continue;
+                }
+
+                let distance = structure
+                    .block_relative_position(x, y, z)
+                    .distance(local_center);
+
+                let damage = explosion.power_at(distance);
+
+                if damage <= 0.0 {
+                    continue;
+                }
+
+                let block = structure.block_at(x, y, z, blocks);
+
+                let Some(hardness) = hardness_registry.from_id(block.unlocalized_name()) else {
+                    warn!("Missing block hardness for {}", block.unlocalized_name());
+                    continue;
+                };
+
+                if structure.block_take_damage(
+                    x,
+                    y,
+                    z,
+                    hardness,
+                    damage,
+                    None,
+                    Some(health_event_writer),
+                ) {
+                    destroyed.push(StructureBlock::new(x, y, z));
+                }
+            }
+        }
+    }
+
+    destroyed
+}
+
+fn handle_explosions(
+    mut event_reader: EventReader<ExplosionEvent>,
+    mut structure_query: Query<(Entity, &mut Structure, &Location, &GlobalTransform)>,
+    mut rigid_bodies: Query<(Entity, &Location, &RigidBody, Option<&mut ExternalImpulse>)>,
+    blocks: Res<Registry<Block>>,
+    hardness_registry: Res<Registry<BlockHardness>>,
+    mut destroyed_event_writer: EventWriter<BlockDestroyedEvent>,
+    mut health_event_writer: EventWriter<BlockHealthChangedEvent>,
+    mut commands: Commands,
+) {
+    for explosion in event_reader.iter() {
+        for (structure_entity, mut structure, structure_location, global_transform) in
+            structure_query.iter_mut()
+        {
+            let destroyed = explode_structure(
+                explosion,
+                &mut structure,
+                structure_location,
+                global_transform,
+                &blocks,
+                &hardness_registry,
+                &mut health_event_writer,
+            );
+
+            destroyed_event_writer.send_batch(destroyed.into_iter().map(|block| {
+                BlockDestroyedEvent {
+                    structure_entity,
+                    block,
+                }
+            }));
+        }
+
+        for (entity, location, rigid_body, external_impulse) in rigid_bodies.iter_mut() {
+            if *rigid_body != RigidBody::Dynamic {
+                continue;
+            }
+
+            let offset = explosion.location.relative_coords_to(location);
+            let power = explosion.power_at(offset.length());
+
+            if power <= 0.0 {
+                continue;
+            }
+
+            let impulse = offset.normalize_or_zero() * power * IMPULSE_PER_POWER;
+
+            if let Some(mut external_impulse) = external_impulse {
+                external_impulse.impulse += impulse;
+            } else if let Some(mut entity) = commands.get_entity(entity) {
+                entity.insert(ExternalImpulse {
+                    impulse,
+                    ..Default::default()
+                });
+            }
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_system(handle_explosions.in_set(OnUpdate(GameState::Playing)));
+}
@@ -14,6 +14,8 @@ use cosmos_core::{
 
 use crate::state::GameState;
 
+mod explosion;
+
 const WORLD_SWITCH_DISTANCE: f32 = SECTOR_DIMENSIONS / 2.0;
 const WORLD_SWITCH_DISTANCE_SQRD: f32 = WORLD_SWITCH_DISTANCE * WORLD_SWITCH_DISTANCE;
 
@@ -379,6 +381,8 @@ fn sync_transforms_and_locations(
 }
 
 pub(super) fn register(app: &mut App) {
+    explosion::register(app);
+
     app.add_systems(
         (move_players_between_worlds, move_non_players_between_worlds)
             .chain()
@@ -3,7 +3,7 @@
 use bevy::prelude::Plugin;
 
 use crate::{
-    blocks, commands, events,
+    blocks, commands, crafting, entities, events,
     init::{self, init_server},
     inventory, netty, persistence, physics, projectiles, structure, universe,
 };
@@ -25,8 +25,10 @@ impl Plugin for ServerPlugin {
         events::register(app);
         physics::register(app);
         blocks::register(app);
+        entities::register(app);
         structure::register(app);
         inventory::register(app);
+        crafting::register(app);
         super::register(app);
         projectiles::register(app);
         persistence::register(app);
@@ -4,7 +4,13 @@ use cosmos_core::{
     events::block_events::BlockChangedEvent,
     projectiles::laser::{Laser, LaserCollideEvent},
     registry::{identifiable::Identifiable, Registry},
-    structure::{block_health::block_destroyed_event::BlockDestroyedEvent, Structure},
+    structure::{
+        block_health::{
+            block_destroyed_event::BlockDestroyedEvent,
+            block_health_changed_event::BlockHealthChangedEvent,
+        },
+        Structure,
+    },
 };
 
 use crate::{
@@ -19,17 +25,22 @@ use crate::{
 fn on_laser_hit_structure(
     structure: &mut Structure,
     local_position_hit: Vec3,
+    local_direction: Vec3,
     blocks: &Registry<Block>,
     block_change_event_writer: &mut EventWriter<BlockChangedEvent>,
     block_destroy_event_writer: &mut EventWriter<BlockDestroyedEvent>,
+    block_health_event_writer: &mut EventWriter<BlockHealthChangedEvent>,
     hardness_registry: &Registry<BlockHardness>,
     strength: f32,
 ) {
-    if let Ok((bx, by, bz)) = structure.relative_coords_to_local_coords_checked(
-        local_position_hit.x,
-        local_position_hit.y,
-        local_position_hit.z,
+    // The hit position can land just outside the block it hit, so walk the laser's path back into the structure
+    if let Some(hit) = structure.raycast_local(
+        local_position_hit - local_direction * 0.5,
+        local_direction,
+        1.0,
     ) {
+        let (bx, by, bz) = (hit.block.x, hit.block.y, hit.block.z);
+
         let block = structure.block_at(bx, by, bz, blocks);
 
         if let Some(hardness) = hardness_registry.from_id(block.unlocalized_name()) {
@@ -40,6 +51,7 @@ fn on_laser_hit_structure(
                 hardness,
                 strength,
                 Some(block_destroy_event_writer),
+                Some(block_health_event_writer),
             );
         } else {
             println!(
@@ -60,20 +72,21 @@ fn respond_laser_hit_event(
     blocks: Res<Registry<Block>>,
     mut block_change_event_writer: EventWriter<BlockChangedEvent>,
     mut block_destroy_event_writer: EventWriter<BlockDestroyedEvent>,
+    mut block_health_event_writer: EventWriter<BlockHealthChangedEvent>,
     hardness_registry: Res<Registry<BlockHardness>>,
 ) {
     for ev in reader.iter() {
         let entity_hit = ev.entity_hit();
         if let Ok(parent) = parent_query.get(entity_hit) {
             if let Ok(mut structure) = structure_query.get_mut(parent.get()) {
-                let local_position_hit = ev.local_position_hit();
-
                 on_laser_hit_structure(
                     &mut structure,
-                    local_position_hit,
+                    ev.local_position_hit(),
+                    ev.local_direction(),
                     &blocks,
                     &mut block_change_event_writer,
                     &mut block_destroy_event_writer,
+                    &mut block_health_event_writer,
                     &hardness_registry,
                     ev.laser_strength(),
                 );
@@ -7,7 +7,7 @@ use bevy::{
     utils::HashMap,
 };
 use cosmos_core::{
-    block::{Block, BlockFace},
+    block::{Block, BlockRotation},
     physics::location::Location,
     registry::Registry,
     structure::{
@@ -149,7 +149,7 @@ fn start_generating_asteroid(
                                 y % CHUNK_DIMENSIONS,
                                 z % CHUNK_DIMENSIONS,
                                 stone,
-                                BlockFace::Top,
+                                BlockRotation::IDENTITY,
                             )
                         }
                     }
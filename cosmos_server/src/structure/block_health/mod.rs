@@ -1,34 +1,304 @@
-//! This handles what to do when a block is destroyed
+//! This handles what to do when a block is destroyed, regenerates damaged blocks, and sends block health changes to clients
 
-use bevy::prelude::{App, EventReader, EventWriter, IntoSystemConfig, OnUpdate, Query, Res};
+use std::time::Duration;
+
+use bevy::{
+    prelude::{
+        App, Commands, Entity, EventReader, EventWriter, GlobalTransform, IntoSystemConfig,
+        OnUpdate, Query, Res, ResMut,
+    },
+    time::common_conditions::on_timer,
+    utils::{HashMap, HashSet},
+};
+use bevy_rapier3d::prelude::Velocity;
+use bevy_renet::renet::RenetServer;
 use cosmos_core::{
-    block::Block,
-    events::block_events::BlockChangedEvent,
-    registry::Registry,
-    structure::{block_health::block_destroyed_event::BlockDestroyedEvent, Structure},
+    block::{hardness::BlockHardness, Block},
+    entities::player::Player,
+    events::block_events::BlockChangedBatchEvent,
+    inventory::itemstack::ItemStack,
+    item::Item,
+    netty::{cosmos_encoder, server_reliable_messages::ServerReliableMessages, NettyChannel},
+    persistence::LoadingDistance,
+    physics::location::Location,
+    registry::{identifiable::Identifiable, Registry},
+    structure::{
+        block_edit_batch::BlockEditBatch,
+        block_health::{
+            block_destroyed_event::BlockDestroyedEvent,
+            block_health_changed_event::BlockHealthChangedEvent,
+        },
+        structure_block::StructureBlock,
+        Structure,
+    },
 };
 
-use crate::state::GameState;
+use crate::{
+    blocks::drop_tables::{roll_drops, DropSource, DropTable},
+    entities::dropped_item::spawn_dropped_item,
+    inventory::container::drop_container_contents,
+    state::GameState,
+};
+
+/// How often damaged blocks regenerate their health
+const REGENERATION_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The most block health changes sent in one message.
+///
+/// Each change is 28 bytes before compression, so a full message stays under the reliable channel's
+/// max message size even if it doesn't compress at all.
+const MAX_HEALTH_CHANGES_PER_MESSAGE: usize = 400;
 
+/// Adds these items to the pile, topping up stacks of the same items before starting new ones at this location.
+///
+/// New stacks never hold more than the item's max stack size.
+fn add_to_pile(
+    pile: &mut Vec<(Location, ItemStack)>,
+    location: Location,
+    mut item_stack: ItemStack,
+) {
+    for (_, is) in pile
+        .iter_mut()
+        .filter(|(_, is)| !is.is_full() && is.can_stack_with(&item_stack))
+    {
+        let leftover = is.increase_quantity(item_stack.quantity());
+        item_stack.decrease_quantity(item_stack.quantity() - leftover);
+
+        if item_stack.is_empty() {
+            return;
+        }
+    }
+
+    while !item_stack.is_empty() {
+        let mut new_stack = item_stack.clone();
+        new_stack.decrease_quantity(new_stack.quantity());
+
+        let leftover = new_stack.increase_quantity(item_stack.quantity());
+        item_stack.decrease_quantity(item_stack.quantity() - leftover);
+
+        pile.push((location, new_stack));
+    }
+}
+
+/// Removes every destroyed block, leaving behind whatever its drop table gives.
+///
+/// Blocks destroyed on the same structure in the same frame (such as by an explosion) are removed as one batch,
+/// and their drops are merged into as few dropped items as possible so an explosion doesn't spawn one for every block.
 fn monitor_block_destroyed(
     mut event_reader: EventReader<BlockDestroyedEvent>,
-    mut structure_query: Query<&mut Structure>,
-    mut event_writer: EventWriter<BlockChangedEvent>,
+    mut structure_query: Query<(&mut Structure, &GlobalTransform, &Location)>,
+    mut event_writer: EventWriter<BlockChangedBatchEvent>,
     blocks: Res<Registry<Block>>,
+    items: Res<Registry<Item>>,
+    drop_tables: Res<Registry<DropTable>>,
+    mut commands: Commands,
 ) {
+    let mut batches = HashMap::<Entity, BlockEditBatch>::default();
+    let mut destroyed = HashSet::<(Entity, StructureBlock)>::default();
+
     for ev in event_reader.iter() {
-        if let Ok(mut structure) = structure_query.get_mut(ev.structure_entity) {
-            structure.remove_block_at(
-                ev.block.x,
-                ev.block.y,
-                ev.block.z,
-                &blocks,
+        // The same block can be destroyed more than once in a frame, but it should only drop its items once
+        if !destroyed.insert((ev.structure_entity, ev.block)) {
+            continue;
+        }
+
+        batches
+            .entry(ev.structure_entity)
+            .or_default()
+            .remove_block_at(ev.block.x, ev.block.y, ev.block.z);
+    }
+
+    let mut rng = rand::thread_rng();
+
+    for (structure_entity, batch) in batches {
+        if let Ok((mut structure, g_trans, location)) = structure_query.get_mut(structure_entity) {
+            let mut pile = Vec::<(Location, ItemStack)>::new();
+
+            for edit in batch.iter() {
+                let (x, y, z) = (edit.block.x, edit.block.y, edit.block.z);
+
+                if !structure.has_block_at(x, y, z) {
+                    continue;
+                }
+
+                let block = edit.block.block(&structure, &blocks);
+                let block_location = structure.block_world_location(x, y, z, g_trans, location);
+
+                for item_stack in
+                    roll_drops(block, DropSource::Destroyed, &drop_tables, &items, &mut rng)
+                {
+                    add_to_pile(&mut pile, block_location, item_stack);
+                }
+
+                drop_container_contents(&mut commands, &structure, edit.block, block_location);
+            }
+
+            for (block_location, item_stack) in pile {
+                spawn_dropped_item(&mut commands, block_location, Velocity::zero(), item_stack);
+            }
+
+            structure.apply_block_edits(batch, &blocks, Some(&mut event_writer));
+        }
+    }
+}
+
+/// Heals every damaged block that regenerates, based on its [`BlockHardness::regeneration`]
+fn regenerate_blocks(
+    mut structure_query: Query<&mut Structure>,
+    blocks: Res<Registry<Block>>,
+    hardness: Res<Registry<BlockHardness>>,
+    mut event_writer: EventWriter<BlockHealthChangedEvent>,
+) {
+    let seconds = REGENERATION_INTERVAL.as_secs_f32();
+
+    for mut structure in structure_query.iter_mut() {
+        let regenerating = structure
+            .all_damaged_blocks()
+            .filter_map(|(block, health)| {
+                let block_hardness =
+                    hardness.from_id(block.block(&structure, &blocks).unlocalized_name())?;
+
+                (block_hardness.regeneration() > 0.0).then_some((block, health, block_hardness))
+            })
+            .collect::<Vec<(StructureBlock, f32, &BlockHardness)>>();
+
+        for (block, health, block_hardness) in regenerating {
+            structure.set_block_health(
+                block.x,
+                block.y,
+                block.z,
+                block_hardness,
+                health + block_hardness.regeneration() * seconds,
                 Some(&mut event_writer),
             );
         }
     }
 }
 
+/// Sends every block health change this frame to the players close enough to have that structure loaded.
+///
+/// Changes are grouped by structure, so a structure taking a lot of damage at once is only a few messages per player.
+fn send_block_health_changes(
+    mut event_reader: EventReader<BlockHealthChangedEvent>,
+    structure_query: Query<(&Location, Option<&LoadingDistance>)>,
+    players: Query<(&Player, &Location)>,
+    mut server: ResMut<RenetServer>,
+) {
+    let mut changes = HashMap::<Entity, Vec<(StructureBlock, f32)>>::default();
+
+    for ev in event_reader.iter() {
+        changes
+            .entry(ev.structure_entity)
+            .or_default()
+            .push((ev.block, ev.new_health));
+    }
+
+    for (structure_entity, changes) in changes {
+        let Ok((structure_location, loading_distance)) = structure_query.get(structure_entity) else {
+            continue;
+        };
+
+        let messages = changes
+            .chunks(MAX_HEALTH_CHANGES_PER_MESSAGE)
+            .map(|changes| {
+                cosmos_encoder::serialize(&ServerReliableMessages::BlockHealthChanged {
+                    structure_entity,
+                    changes: changes.to_vec(),
+                })
+            })
+            .collect::<Vec<Vec<u8>>>();
+
+        for (player, player_location) in players.iter() {
+            let in_range = loading_distance
+                .map(|loading_distance| {
+                    structure_location
+                        .relative_coords_to(player_location)
+                        .abs()
+                        .max_element()
+                        < loading_distance.load_block_distance()
+                })
+                .unwrap_or(true);
+
+            if in_range {
+                for message in messages.iter() {
+                    server.send_message(player.id(), NettyChannel::Reliable.id(), message.clone());
+                }
+            }
+        }
+    }
+}
+
 pub(super) fn register(app: &mut App) {
-    app.add_system(monitor_block_destroyed.in_set(OnUpdate(GameState::Playing)));
+    app.add_system(monitor_block_destroyed.in_set(OnUpdate(GameState::Playing)))
+        .add_system(
+            regenerate_blocks
+                .run_if(on_timer(REGENERATION_INTERVAL))
+                .in_set(OnUpdate(GameState::Playing)),
+        )
+        .add_system(send_block_health_changes.in_set(OnUpdate(GameState::Playing)));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn full_health_message_fits_in_channel() {
+        let changes = (0..MAX_HEALTH_CHANGES_PER_MESSAGE)
+            .map(|i| {
+                (
+                    StructureBlock::new(usize::MAX - i, i * 7919, i * 104729),
+                    i as f32 * 0.618,
+                )
+            })
+            .collect();
+
+        let message = cosmos_encoder::serialize(&ServerReliableMessages::BlockHealthChanged {
+            structure_entity: Entity::from_raw(0),
+            changes,
+        });
+
+        assert!(message.len() < 12000, "{} bytes", message.len());
+    }
+
+    #[test]
+    fn drops_are_merged_into_full_stacks() {
+        let mut items = Registry::<Item>::new();
+        items.register(Item::new("cosmos:stone".into(), 10));
+        items.register(Item::new("cosmos:dirt".into(), 10));
+
+        let stone = items.from_id("cosmos:stone").unwrap();
+        let dirt = items.from_id("cosmos:dirt").unwrap();
+
+        let mut pile = Vec::new();
+
+        for _ in 0..6 {
+            add_to_pile(
+                &mut pile,
+                Location::default(),
+                ItemStack::with_quantity(stone, 3),
+            );
+        }
+        add_to_pile(
+            &mut pile,
+            Location::default(),
+            ItemStack::with_quantity(dirt, 25),
+        );
+
+        let quantities = pile
+            .iter()
+            .map(|(_, is)| (is.item_id(), is.quantity()))
+            .collect::<Vec<(u16, u16)>>();
+
+        assert_eq!(
+            quantities,
+            vec![
+                (stone.id(), 10),
+                (stone.id(), 8),
+                (dirt.id(), 10),
+                (dirt.id(), 10),
+                (dirt.id(), 5)
+            ]
+        );
+    }
 }
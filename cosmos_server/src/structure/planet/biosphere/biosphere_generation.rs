@@ -145,7 +145,7 @@ fn do_face<T: Component + Clone>(
 
                 if actual_height <= top_height {
                     let block = block_ranges.face_block(top_height - actual_height);
-                    chunk.set_block_at(x, y, z, block, up);
+                    chunk.set_block_at(x, y, z, block, up.into());
                 }
             }
         }
@@ -298,7 +298,7 @@ fn do_edge<T: Component + Clone>(
                         block_up = k_up;
                     }
                     let block = block_ranges.edge_block(j_top - j_height, k_top - k_height);
-                    chunk.set_block_at(x, y, z, block, block_up);
+                    chunk.set_block_at(x, y, z, block, block_up.into());
                 }
             }
         }
@@ -459,7 +459,7 @@ fn do_corner<T: Component + Clone>(
                         y_top - y_height,
                         z_top - z_height,
                     );
-                    chunk.set_block_at(i, j, k, block, block_up);
+                    chunk.set_block_at(i, j, k, block, block_up.into());
                 }
             }
         }
@@ -511,16 +511,12 @@ impl<T: Component + Clone> BlockRanges<T> {
     }
 }
 
-/// Calls do_face, do_edge, and do_corner to generate the chunks of a planet.
-pub fn generate_planet<T: Component + Clone, E: TGenerateChunkEvent + Send + Sync + 'static>(
-    mut query: Query<(&mut Structure, &Location)>,
-    mut generating: ResMut<GeneratingChunks<T>>,
-    mut events: EventReader<E>,
-    noise_generator: Res<ResourceWrapper<noise::OpenSimplex>>,
-    block_ranges: Res<BlockRanges<T>>,
-) {
-    let chunks = events
-        .iter()
+/// Takes every chunk these events want generated out of their structures, so they can be generated.
+pub fn take_chunks_to_generate<'a, E: TGenerateChunkEvent + 'a>(
+    events: impl Iterator<Item = &'a E>,
+    query: &mut Query<(&mut Structure, &Location)>,
+) -> Vec<(Entity, Chunk)> {
+    events
         .filter_map(|ev| {
             let structure_entity = ev.get_structure_entity();
             let (x, y, z) = ev.get_chunk_coordinates();
@@ -533,153 +529,158 @@ pub fn generate_planet<T: Component + Clone, E: TGenerateChunkEvent + Send + Syn
                 None
             }
         })
-        .collect::<Vec<(Entity, Chunk)>>();
+        .collect::<Vec<(Entity, Chunk)>>()
+}
 
-    let thread_pool = AsyncComputeTaskPool::get();
+/// Calls do_face, do_edge, and do_corner on another thread to generate this chunk of a planet.
+///
+/// Add the returned [`GeneratingChunk`] to its [`GeneratingChunks`] so it's put in the structure once it's done.
+pub fn start_generating_chunk<T: Component + Clone>(
+    mut chunk: Chunk,
+    structure: &Structure,
+    location: Location,
+    structure_entity: Entity,
+    block_ranges: BlockRanges<T>,
+    // Not super expensive, only copies about 256 8 bit values.
+    // Still not ideal though.
+    noise_generator: noise::OpenSimplex,
+) -> GeneratingChunk<T> {
+    let s_width = structure.blocks_width();
+    let s_height = structure.blocks_height();
+    let s_length = structure.blocks_length();
+
+    let task = AsyncComputeTaskPool::get().spawn(async move {
+        let timer = UtilsTimer::start();
+
+        let middle_air_start = s_height - CHUNK_DIMENSIONS * 5;
+
+        let actual_pos = location.absolute_coords_f64();
+
+        let structure_z = actual_pos.z;
+        let structure_y = actual_pos.y;
+        let structure_x = actual_pos.x;
+
+        // To save multiplication operations later.
+        let sz = chunk.structure_z() * CHUNK_DIMENSIONS;
+        let sy = chunk.structure_y() * CHUNK_DIMENSIONS;
+        let sx = chunk.structure_x() * CHUNK_DIMENSIONS;
+
+        // Get all possible planet faces from the chunk corners.
+        let mut planet_faces = HashSet::new();
+        for z in 0..=1 {
+            for y in 0..=1 {
+                for x in 0..=1 {
+                    planet_faces.insert(Planet::get_planet_face_without_structure(
+                        sx + x * CHUNK_DIMENSIONS,
+                        sy + y * CHUNK_DIMENSIONS,
+                        sz + z * CHUNK_DIMENSIONS,
+                        s_width,
+                        s_height,
+                        s_length,
+                    ));
+                }
+            }
+        }
 
-    let chunks = chunks
-        .into_iter()
-        .flat_map(|(structure_entity, chunk)| {
-            let Ok((structure, location)) = query.get(structure_entity) else {
-                return None;
+        // Support for the middle of the planet.
+        if planet_faces.contains(&BlockFace::Top) {
+            planet_faces.remove(&BlockFace::Bottom);
+        }
+        if planet_faces.contains(&BlockFace::Right) {
+            planet_faces.remove(&BlockFace::Left);
+        }
+        if planet_faces.contains(&BlockFace::Front) {
+            planet_faces.remove(&BlockFace::Back);
+        }
+
+        if planet_faces.len() == 1 {
+            // Chunks on only one face.
+            do_face(
+                (sx, sy, sz),
+                (structure_x, structure_y, structure_z),
+                s_height,
+                &noise_generator,
+                middle_air_start,
+                &block_ranges,
+                &mut chunk,
+                *planet_faces.iter().next().unwrap(),
+            );
+        } else if planet_faces.len() == 2 {
+            // Chunks on an edge.
+            let mut face_iter = planet_faces.iter();
+            do_edge(
+                (sx, sy, sz),
+                (structure_x, structure_y, structure_z),
+                s_height,
+                &noise_generator,
+                middle_air_start,
+                &block_ranges,
+                &mut chunk,
+                *face_iter.next().unwrap(),
+                *face_iter.next().unwrap(),
+            );
+        } else {
+            let x_face = if planet_faces.contains(&BlockFace::Right) {
+                BlockFace::Right
+            } else {
+                BlockFace::Left
+            };
+            let y_face = if planet_faces.contains(&BlockFace::Top) {
+                BlockFace::Top
+            } else {
+                BlockFace::Bottom
             };
+            let z_face = if planet_faces.contains(&BlockFace::Front) {
+                BlockFace::Front
+            } else {
+                BlockFace::Back
+            };
+            do_corner(
+                (sx, sy, sz),
+                (structure_x, structure_y, structure_z),
+                s_height,
+                &noise_generator,
+                middle_air_start,
+                &block_ranges,
+                &mut chunk,
+                x_face,
+                y_face,
+                z_face,
+            );
+        }
+        timer.log_duration("Chunk: ");
+        (chunk, structure_entity)
+    });
 
-            let s_width = structure.blocks_width();
-            let s_height = structure.blocks_height();
-            let s_length = structure.blocks_length();
-            let location = *location;
+    GeneratingChunk::new(task)
+}
 
-            Some((
-                chunk,
-                s_width,
-                s_height,
-                s_length,
-                location,
-                structure_entity,
-            ))
-        })
-        .collect::<Vec<(Chunk, usize, usize, usize, Location, Entity)>>();
+/// Generates the chunks of a planet, using the block ranges of its biosphere.
+pub fn generate_planet<T: Component + Clone, E: TGenerateChunkEvent + Send + Sync + 'static>(
+    mut query: Query<(&mut Structure, &Location)>,
+    mut generating: ResMut<GeneratingChunks<T>>,
+    mut events: EventReader<E>,
+    noise_generator: Res<ResourceWrapper<noise::OpenSimplex>>,
+    block_ranges: Res<BlockRanges<T>>,
+) {
+    let chunks = take_chunks_to_generate(events.iter(), &mut query);
 
     if !chunks.is_empty() {
         println!("Doing {} chunks!", chunks.len());
 
-        for (mut chunk, s_width, s_height, s_length, location, structure_entity) in chunks {
-            let block_ranges = block_ranges.clone();
-            // let grass = grass.clone();
-            // let dirt = dirt.clone();
-            // let stone = stone.clone();
-            // Not super expensive, only copies about 256 8 bit values.
-            // Still not ideal though.
-            let noise_generator = **noise_generator;
-
-            let task = thread_pool.spawn(async move {
-                let timer = UtilsTimer::start();
-
-                // let grass = &grass;
-                // let dirt = &dirt;
-                // let stone = &stone;
-
-                let middle_air_start = s_height - CHUNK_DIMENSIONS * 5;
-
-                let actual_pos = location.absolute_coords_f64();
-
-                let structure_z = actual_pos.z;
-                let structure_y = actual_pos.y;
-                let structure_x = actual_pos.x;
-
-                // To save multiplication operations later.
-                let sz = chunk.structure_z() * CHUNK_DIMENSIONS;
-                let sy = chunk.structure_y() * CHUNK_DIMENSIONS;
-                let sx = chunk.structure_x() * CHUNK_DIMENSIONS;
-
-                // Get all possible planet faces from the chunk corners.
-                let mut planet_faces = HashSet::new();
-                for z in 0..=1 {
-                    for y in 0..=1 {
-                        for x in 0..=1 {
-                            planet_faces.insert(Planet::get_planet_face_without_structure(
-                                sx + x * CHUNK_DIMENSIONS,
-                                sy + y * CHUNK_DIMENSIONS,
-                                sz + z * CHUNK_DIMENSIONS,
-                                s_width,
-                                s_height,
-                                s_length,
-                            ));
-                        }
-                    }
-                }
-
-                // Support for the middle of the planet.
-                if planet_faces.contains(&BlockFace::Top) {
-                    planet_faces.remove(&BlockFace::Bottom);
-                }
-                if planet_faces.contains(&BlockFace::Right) {
-                    planet_faces.remove(&BlockFace::Left);
-                }
-                if planet_faces.contains(&BlockFace::Front) {
-                    planet_faces.remove(&BlockFace::Back);
-                }
-
-                if planet_faces.len() == 1 {
-                    // Chunks on only one face.
-                    do_face(
-                        (sx, sy, sz),
-                        (structure_x, structure_y, structure_z),
-                        s_height,
-                        &noise_generator,
-                        middle_air_start,
-                        &block_ranges,
-                        &mut chunk,
-                        *planet_faces.iter().next().unwrap(),
-                    );
-                } else if planet_faces.len() == 2 {
-                    // Chunks on an edge.
-                    let mut face_iter = planet_faces.iter();
-                    do_edge(
-                        (sx, sy, sz),
-                        (structure_x, structure_y, structure_z),
-                        s_height,
-                        &noise_generator,
-                        middle_air_start,
-                        &block_ranges,
-                        &mut chunk,
-                        *face_iter.next().unwrap(),
-                        *face_iter.next().unwrap(),
-                    );
-                } else {
-                    let x_face = if planet_faces.contains(&BlockFace::Right) {
-                        BlockFace::Right
-                    } else {
-                        BlockFace::Left
-                    };
-                    let y_face = if planet_faces.contains(&BlockFace::Top) {
-                        BlockFace::Top
-                    } else {
-                        BlockFace::Bottom
-                    };
-                    let z_face = if planet_faces.contains(&BlockFace::Front) {
-                        BlockFace::Front
-                    } else {
-                        BlockFace::Back
-                    };
-                    do_corner(
-                        (sx, sy, sz),
-                        (structure_x, structure_y, structure_z),
-                        s_height,
-                        &noise_generator,
-                        middle_air_start,
-                        &block_ranges,
-                        &mut chunk,
-                        x_face,
-                        y_face,
-                        z_face,
-                    );
-                }
-                timer.log_duration("Chunk: ");
-                (chunk, structure_entity)
-            });
+        for (structure_entity, chunk) in chunks {
+            let Ok((structure, location)) = query.get(structure_entity) else {
+                continue;
+            };
 
-            generating.generating.push(GeneratingChunk::new(task));
+            generating.generating.push(start_generating_chunk(
+                chunk,
+                structure,
+                *location,
+                structure_entity,
+                block_ranges.clone(),
+                **noise_generator,
+            ));
         }
     }
 }
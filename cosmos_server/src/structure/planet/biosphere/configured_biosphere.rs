@@ -0,0 +1,318 @@
+//! Biospheres defined in the `content/biospheres` files of a content pack.
+//!
+//! These generate the same terrain as the grass biosphere, but use the layers of blocks given in their definition.
+//!
+//! An example definition:
+//!
+//! ```json
+//! {
+//!     "unlocalized_name": "example:biosphere_sand",
+//!     "temperature_range": [500.0, 1000.0],
+//!     "layers": [
+//!         { "block": "cosmos:stone", "depth": 4 },
+//!         { "block": "example:sand", "depth": 0 }
+//!     ]
+//! }
+//! ```
+//!
+//! Each layer starts `depth` blocks below the surface, so layers must be in descending order of depth & the last one must start at 0.
+
+use bevy::prelude::{
+    App, Commands, Component, Entity, EventReader, EventWriter, IntoSystemAppConfig,
+    IntoSystemConfig, IntoSystemConfigs, OnEnter, OnUpdate, Query, Res, ResMut, With,
+};
+use cosmos_core::{
+    block::Block,
+    content::{self, ContentError, ContentObject, ContentPacks},
+    loader::{AddLoadingEvent, DoneLoadingEvent, LoadingManager},
+    physics::location::Location,
+    registry::{self, identifiable::Identifiable, Registry},
+    structure::{planet::biosphere::BiosphereMarker, Structure},
+    utils::resource_wrapper::ResourceWrapper,
+};
+
+use crate::{
+    persistence::{
+        loading::{begin_loading, done_loading, NeedsLoaded},
+        saving::{begin_saving, done_saving, NeedsSaved},
+        SerializedData,
+    },
+    state::GameState,
+    structure::planet::generation::planet_generator::check_needs_generated_system,
+};
+
+use super::{
+    biosphere_generation::{
+        notify_when_done_generating, start_generating_chunk, take_chunks_to_generate, BlockRanges,
+    },
+    BiosphereTemperatureRegistry, GeneratingChunks, NeedsBiosphereEvent, TGenerateChunkEvent,
+    TemperatureRange,
+};
+
+/// The kind of content biosphere definitions are
+const BIOSPHERES_CONTENT: &str = "biospheres";
+
+/// Every field a biosphere definition can have
+const FIELDS: [&str; 3] = ["unlocalized_name", "temperature_range", "layers"];
+
+/// Every field a layer of a biosphere definition can have
+const LAYER_FIELDS: [&str; 2] = ["block", "depth"];
+
+#[derive(Component, Debug, Default, Clone)]
+/// Marks that this planet's biosphere comes from a content file.
+///
+/// Use its [`BiosphereMarker`] to find which one.
+pub struct ConfiguredBiosphereMarker;
+
+/// Marks that a chunk of a configured biosphere needs generated
+pub struct ConfiguredChunkNeedsGeneratedEvent {
+    x: usize,
+    y: usize,
+    z: usize,
+    structure_entity: Entity,
+}
+
+impl TGenerateChunkEvent for ConfiguredChunkNeedsGeneratedEvent {
+    fn new(x: usize, y: usize, z: usize, structure_entity: Entity) -> Self {
+        Self {
+            x,
+            y,
+            z,
+            structure_entity,
+        }
+    }
+
+    fn get_structure_entity(&self) -> Entity {
+        self.structure_entity
+    }
+
+    fn get_chunk_coordinates(&self) -> (usize, usize, usize) {
+        (self.x, self.y, self.z)
+    }
+}
+
+/// A biosphere, as read from its content file
+pub struct BiosphereConfig {
+    id: u16,
+    unlocalized_name: String,
+
+    /// The temperatures planets with this biosphere can have
+    pub temperature_range: TemperatureRange,
+    /// The blocks this biosphere is made of
+    pub block_ranges: BlockRanges<ConfiguredBiosphereMarker>,
+}
+
+impl Identifiable for BiosphereConfig {
+    fn id(&self) -> u16 {
+        self.id
+    }
+
+    fn set_numeric_id(&mut self, id: u16) {
+        self.id = id;
+    }
+
+    fn unlocalized_name(&self) -> &str {
+        &self.unlocalized_name
+    }
+}
+
+fn parse_layer(
+    layer: &ContentObject,
+    blocks: &Registry<Block>,
+) -> Result<(Block, usize), ContentError> {
+    layer.deny_unknown_fields(&LAYER_FIELDS)?;
+
+    let block_name = layer.required::<String>("block")?;
+    let depth = layer.required::<usize>("depth")?;
+
+    let Some(block) = blocks.from_id(&block_name) else {
+        return Err(layer.error("block", format!("No block named {block_name} exists")));
+    };
+
+    Ok((block.clone(), depth))
+}
+
+fn parse_biosphere_config(
+    object: &ContentObject,
+    blocks: &Registry<Block>,
+) -> Result<BiosphereConfig, ContentError> {
+    object.deny_unknown_fields(&FIELDS)?;
+
+    let unlocalized_name = object.required::<String>("unlocalized_name")?;
+
+    let [low, high] = object.required::<[f32; 2]>("temperature_range")?;
+
+    if !low.is_finite() || !high.is_finite() {
+        return Err(object.error("temperature_range", "Temperatures must be finite"));
+    }
+
+    let layers = object.objects("layers")?;
+
+    if layers.is_empty() {
+        return Err(object.error("layers", "Must have at least one layer"));
+    }
+
+    let mut ranges = Vec::with_capacity(layers.len());
+
+    for layer in layers.iter() {
+        let (block, depth) = parse_layer(layer, blocks)?;
+
+        if let Some((_, above)) = ranges.last() {
+            if depth >= *above {
+                return Err(layer.error(
+                    "depth",
+                    format!("Must be less than the depth of the layer before it ({above})"),
+                ));
+            }
+        }
+
+        ranges.push((block, depth));
+    }
+
+    if let Some((_, depth)) = ranges.last() {
+        if *depth != 0 {
+            return Err(
+                layers[layers.len() - 1].error("depth", "The last layer must have a depth of 0")
+            );
+        }
+    }
+
+    Ok(BiosphereConfig {
+        id: 0,
+        unlocalized_name,
+        temperature_range: TemperatureRange::new(low, high),
+        block_ranges: BlockRanges::new(ranges),
+    })
+}
+
+fn load_biosphere_configs(
+    blocks: Res<Registry<Block>>,
+    packs: Res<ContentPacks>,
+    mut configs: ResMut<Registry<BiosphereConfig>>,
+    mut temperature_registry: ResMut<BiosphereTemperatureRegistry>,
+    mut loading: ResMut<LoadingManager>,
+    mut end_writer: EventWriter<DoneLoadingEvent>,
+    mut start_writer: EventWriter<AddLoadingEvent>,
+) {
+    let id = loading.register_loader(&mut start_writer);
+
+    match content::load_definitions(
+        &packs,
+        BIOSPHERES_CONTENT,
+        |name| temperature_registry.is_registered(name),
+        |object| parse_biosphere_config(object, &blocks),
+    ) {
+        Ok(loaded) => {
+            for config in loaded {
+                temperature_registry.register(config.unlocalized_name(), config.temperature_range);
+
+                configs.register(config);
+            }
+        }
+        Err(errors) => content::panic_with_errors("biosphere definitions", &errors),
+    }
+
+    loading.finish_loading(id, &mut end_writer);
+}
+
+fn add_configured_biosphere(
+    mut event_reader: EventReader<NeedsBiosphereEvent>,
+    configs: Res<Registry<BiosphereConfig>>,
+    mut commands: Commands,
+) {
+    for ev in event_reader.iter() {
+        if configs.from_id(&ev.biosphere_id).is_some() {
+            commands.entity(ev.entity).insert(ConfiguredBiosphereMarker);
+        }
+    }
+}
+
+fn save_configured_biosphere(
+    mut query: Query<
+        (&mut SerializedData, &BiosphereMarker),
+        (With<NeedsSaved>, With<ConfiguredBiosphereMarker>),
+    >,
+) {
+    for (mut sd, biosphere) in query.iter_mut() {
+        sd.serialize_data(biosphere.biosphere_name().to_owned(), &true);
+    }
+}
+
+fn load_configured_biosphere(
+    query: Query<(Entity, &SerializedData), With<NeedsLoaded>>,
+    configs: Res<Registry<BiosphereConfig>>,
+    mut commands: Commands,
+) {
+    for (entity, sd) in query.iter() {
+        if let Some(config) = configs.iter().find(|config| {
+            sd.deserialize_data::<bool>(config.unlocalized_name())
+                .unwrap_or(false)
+        }) {
+            commands.entity(entity).insert((
+                ConfiguredBiosphereMarker,
+                BiosphereMarker::new(config.unlocalized_name()),
+            ));
+        }
+    }
+}
+
+fn generate_configured_planet(
+    mut query: Query<(&mut Structure, &Location)>,
+    biosphere_query: Query<&BiosphereMarker>,
+    mut generating: ResMut<GeneratingChunks<ConfiguredBiosphereMarker>>,
+    mut events: EventReader<ConfiguredChunkNeedsGeneratedEvent>,
+    noise_generator: Res<ResourceWrapper<noise::OpenSimplex>>,
+    configs: Res<Registry<BiosphereConfig>>,
+) {
+    for (structure_entity, chunk) in take_chunks_to_generate(events.iter(), &mut query) {
+        let Some(config) = biosphere_query
+            .get(structure_entity)
+            .ok()
+            .and_then(|biosphere| configs.from_id(biosphere.biosphere_name()))
+        else {
+            continue;
+        };
+
+        let Ok((structure, location)) = query.get(structure_entity) else {
+            continue;
+        };
+
+        generating.generating.push(start_generating_chunk(
+            chunk,
+            structure,
+            *location,
+            structure_entity,
+            config.block_ranges.clone(),
+            **noise_generator,
+        ));
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    registry::create_registry::<BiosphereConfig>(app);
+
+    app.add_event::<ConfiguredChunkNeedsGeneratedEvent>()
+        .insert_resource(GeneratingChunks::<ConfiguredBiosphereMarker>::default())
+        // Blocks are registered while loading, so they all exist by now
+        .add_system(load_biosphere_configs.in_schedule(OnEnter(GameState::PostLoading)))
+        .add_systems((
+            add_configured_biosphere,
+            save_configured_biosphere
+                .after(begin_saving)
+                .before(done_saving),
+            load_configured_biosphere
+                .after(begin_loading)
+                .before(done_loading),
+        ))
+        .add_systems(
+            (
+                check_needs_generated_system::<
+                    ConfiguredChunkNeedsGeneratedEvent,
+                    ConfiguredBiosphereMarker,
+                >,
+                generate_configured_planet,
+                notify_when_done_generating::<ConfiguredBiosphereMarker>,
+            )
+                .in_set(OnUpdate(GameState::Playing)),
+        );
+}
@@ -33,6 +33,7 @@ use crate::{
 use super::generation::planet_generator::check_needs_generated_system;
 
 pub mod biosphere_generation;
+pub mod configured_biosphere;
 pub mod grass_biosphere;
 pub mod test_all_stone_biosphere;
 
@@ -213,6 +214,11 @@ impl BiosphereTemperatureRegistry {
             .collect::<Vec<&str>>()
     }
 
+    /// Returns true if a biosphere with this unlocalized name has been registered
+    pub fn is_registered(&self, biosphere: &str) -> bool {
+        self.ranges.iter().any(|(_, x)| x == biosphere)
+    }
+
     /// Adds a biosphere based on its temperature range
     pub fn register(&mut self, biosphere: impl Into<String>, temperature_range: TemperatureRange) {
         self.ranges.push((temperature_range, biosphere.into()));
@@ -226,4 +232,5 @@ pub(super) fn register(app: &mut App) {
 
     grass_biosphere::register(app);
     test_all_stone_biosphere::register(app);
+    configured_biosphere::register(app);
 }
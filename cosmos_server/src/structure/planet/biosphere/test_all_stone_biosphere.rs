@@ -4,7 +4,7 @@ use bevy::prelude::{
     App, Component, Entity, EventReader, EventWriter, IntoSystemConfig, OnUpdate, Query, Res,
 };
 use cosmos_core::{
-    block::{Block, BlockFace},
+    block::{Block, BlockRotation},
     registry::Registry,
     structure::{
         chunk::{Chunk, CHUNK_DIMENSIONS},
@@ -98,7 +98,7 @@ fn generate_planet(
         for z in 0..CHUNK_DIMENSIONS {
             for y in 0..CHUNK_DIMENSIONS {
                 for x in 0..CHUNK_DIMENSIONS {
-                    chunk.set_block_at(x, y, z, stone, BlockFace::Top);
+                    chunk.set_block_at(x, y, z, stone, BlockRotation::IDENTITY);
                 }
             }
         }
@@ -1,7 +1,14 @@
-use bevy::prelude::{App, Component, IntoSystemConfig, Query, With};
-use cosmos_core::structure::chunk::Chunk;
+use bevy::prelude::{App, Component, IntoSystemConfig, Query, Res, With};
+use cosmos_core::{
+    block::Block,
+    item::Item,
+    registry::{id_map::IdMap, Registry},
+    structure::chunk::{codec, Chunk},
+};
 
 use crate::persistence::{
+    block_ids::save_block_ids,
+    item_ids::save_item_ids,
     saving::{begin_saving, done_saving, NeedsSaved},
     SerializedData,
 };
@@ -9,9 +16,22 @@ use crate::persistence::{
 #[derive(Component, Debug)]
 pub struct SaveChunk(pub Chunk);
 
-fn save_chunks(mut query: Query<(&mut SerializedData, &SaveChunk), With<NeedsSaved>>) {
+fn save_chunks(
+    mut query: Query<(&mut SerializedData, &SaveChunk), With<NeedsSaved>>,
+    blocks: Res<Registry<Block>>,
+    items: Res<Registry<Item>>,
+) {
+    if query.is_empty() {
+        return;
+    }
+
+    let block_ids = IdMap::from_registry(&blocks);
+    let item_ids = IdMap::from_registry(&items);
+
     for (mut data, save_chunk) in query.iter_mut() {
-        data.serialize_data("cosmos:chunk", &save_chunk.0);
+        data.save("cosmos:chunk", codec::encode(&save_chunk.0));
+        save_block_ids(&mut data, &block_ids);
+        save_item_ids(&mut data, &item_ids);
     }
 }
 
@@ -19,7 +19,10 @@ use cosmos_core::{
     },
     physics::location::Location,
     structure::{
-        chunk::CHUNK_DIMENSIONSF, planet::Planet, structure_iterator::ChunkIteratorResult,
+        block_data::PrivateBlockData,
+        chunk::{codec, CHUNK_DIMENSIONSF},
+        planet::Planet,
+        structure_iterator::ChunkIteratorResult,
         ChunkState, Structure,
     },
     utils::timer::UtilsTimer,
@@ -105,6 +108,7 @@ fn get_requested_chunk(
     mut event_writer: EventWriter<RequestChunkBouncer>,
     mut server: ResMut<RenetServer>,
     mut commands: Commands,
+    private_data: Res<PrivateBlockData>,
 ) {
     let todo = Mutex::new(Some(Vec::new()));
     let serialized = Mutex::new(Some(Vec::new()));
@@ -153,7 +157,10 @@ fn get_requested_chunk(
                                 ev.requester_id,
                                 cosmos_encoder::serialize(&ServerReliableMessages::ChunkData {
                                     structure_entity: ev.structure_entity,
-                                    serialized_chunk: cosmos_encoder::serialize(chunk),
+                                    serialized_chunk: codec::encode_for_clients(
+                                        chunk,
+                                        &private_data,
+                                    ),
                                 }),
                             ));
 
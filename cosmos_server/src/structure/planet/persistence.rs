@@ -3,10 +3,13 @@ use std::fs;
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::PhysicsWorld;
 use cosmos_core::{
+    block::Block,
+    item::Item,
     netty::{cosmos_encoder, NoSendEntity},
     physics::location::Location,
+    registry::Registry,
     structure::{
-        chunk::{Chunk, ChunkEntity},
+        chunk::{codec, ChunkEntity},
         planet::{planet_builder::TPlanetBuilder, Planet},
         ChunkInitEvent, Structure,
     },
@@ -14,6 +17,8 @@ use cosmos_core::{
 use serde::{Deserialize, Serialize};
 
 use crate::persistence::{
+    block_ids::load_block_id_remap,
+    item_ids::load_item_id_remap,
     loading::{begin_loading, done_loading, NeedsLoaded},
     saving::{begin_saving, done_saving, NeedsSaved},
     EntityId, SaveFileIdentifier, SerializedData,
@@ -173,35 +178,55 @@ fn load_chunk(
     query: Query<(Entity, &SerializedData, &ChunkEntity), With<NeedsLoaded>>,
     mut structure_query: Query<&mut Structure>,
     mut chunk_init_event: EventWriter<ChunkInitEvent>,
+    blocks: Res<Registry<Block>>,
+    items: Res<Registry<Item>>,
     mut commands: Commands,
 ) {
     for (entity, sd, ce) in query.iter() {
-        if let Some(chunk) = sd.deserialize_data::<Chunk>("cosmos:chunk") {
-            if let Ok(mut structure) = structure_query.get_mut(ce.structure_entity) {
-                let (cx, cy, cz) = (
-                    chunk.structure_x(),
-                    chunk.structure_y(),
-                    chunk.structure_z(),
-                );
-
-                commands.entity(entity).insert(PbrBundle {
-                    transform: Transform::from_translation(
-                        structure.chunk_relative_position(cx, cy, cz),
-                    ),
-                    ..Default::default()
-                });
-
-                structure.set_chunk_entity(cx, cy, cz, entity);
-
-                structure.set_chunk(chunk);
-
-                chunk_init_event.send(ChunkInitEvent {
-                    structure_entity: ce.structure_entity,
-                    x: cx,
-                    y: cy,
-                    z: cz,
-                });
+        let Some(chunk_data) = sd.read_data("cosmos:chunk") else {
+            continue;
+        };
+
+        let mut chunk = match codec::decode(chunk_data) {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                eprintln!("Error loading chunk - {e}");
+                continue;
             }
+        };
+
+        if let Some(remap) = load_block_id_remap(sd, &blocks, "Planet chunk") {
+            chunk.remap_block_ids(&remap);
+        }
+
+        if let Some(remap) = load_item_id_remap(sd, &items, "Planet chunk") {
+            chunk.remap_item_ids(&remap);
+        }
+
+        if let Ok(mut structure) = structure_query.get_mut(ce.structure_entity) {
+            let (cx, cy, cz) = (
+                chunk.structure_x(),
+                chunk.structure_y(),
+                chunk.structure_z(),
+            );
+
+            commands.entity(entity).insert(PbrBundle {
+                transform: Transform::from_translation(
+                    structure.chunk_relative_position(cx, cy, cz),
+                ),
+                ..Default::default()
+            });
+
+            structure.set_chunk_entity(cx, cy, cz, entity);
+
+            structure.set_chunk(chunk);
+
+            chunk_init_event.send(ChunkInitEvent {
+                structure_entity: ce.structure_entity,
+                x: cx,
+                y: cy,
+                z: cz,
+            });
         }
     }
 }
@@ -4,17 +4,22 @@
 
 use std::{fs, io::ErrorKind};
 
-use bevy::prelude::{App, Commands, Component, Entity, EventReader, EventWriter, Query};
+use bevy::prelude::{App, Commands, Component, Entity, EventReader, EventWriter, Query, Res};
 use bevy_rapier3d::prelude::Velocity;
 use cosmos_core::{
+    block::Block,
+    item::Item,
     netty::cosmos_encoder,
     physics::location::Location,
+    registry::{id_map::IdMap, Registry},
     structure::{
-        ship::ship_builder::TShipBuilder, structure_iterator::ChunkIteratorResult, ChunkInitEvent,
-        Structure,
+        chunk::codec, ship::ship_builder::TShipBuilder, structure_iterator::ChunkIteratorResult,
+        ChunkInitEvent, Structure,
     },
 };
 
+use crate::persistence::{block_ids::block_id_remap, item_ids::item_id_remap};
+
 use super::ship::server_ship_builder::ServerShipBuilder;
 
 /// Loading loads the structure instantly + creates the events at the same time
@@ -66,11 +71,51 @@ fn send_actual_loaded_events(
     }
 }
 
+/// Reads a structure file, remapping its block & item ids if it was saved with different registries.
+///
+/// Files saved before the block or item id tables were stored in them are assumed to use the current ids.
+fn read_structure_file(
+    structure_bin: &[u8],
+    structure_name: &str,
+    blocks: &Registry<Block>,
+    items: &Registry<Item>,
+) -> Option<Structure> {
+    if let Ok((block_ids, item_ids, mut structure)) =
+        cosmos_encoder::deserialize::<(IdMap, IdMap, Structure)>(structure_bin)
+    {
+        if let Some(remap) = block_id_remap(&block_ids, blocks, structure_name) {
+            structure.remap_block_ids(&remap);
+        }
+
+        if let Some(remap) = item_id_remap(&item_ids, items, structure_name) {
+            structure.remap_item_ids(&remap);
+        }
+
+        return Some(structure);
+    }
+
+    if let Ok((block_ids, mut structure)) =
+        cosmos_encoder::deserialize::<(IdMap, Structure)>(structure_bin)
+    {
+        if let Some(remap) = block_id_remap(&block_ids, blocks, structure_name) {
+            structure.remap_block_ids(&remap);
+        }
+
+        return Some(structure);
+    }
+
+    cosmos_encoder::deserialize::<Structure>(structure_bin)
+        .or_else(|_| codec::decode_legacy_structure(structure_bin))
+        .ok()
+}
+
 /// TODO: Eventually turn this into event
 pub fn load_structure(
     structure_name: &str,
     structure_type: StructureType,
     spawn_at: Location,
+    blocks: &Registry<Block>,
+    items: &Registry<Item>,
     commands: &mut Commands,
     structure_loaded: &mut EventWriter<SendDelayedStructureLoadEvent>,
 ) {
@@ -81,7 +126,9 @@ pub fn load_structure(
     )) {
         println!("Loading structure {structure_name}...");
 
-        if let Ok(mut structure) = cosmos_encoder::deserialize::<Structure>(&structure_bin) {
+        if let Some(mut structure) =
+            read_structure_file(&structure_bin, structure_name, blocks, items)
+        {
             let mut entity_cmd = commands.spawn_empty();
 
             match structure_type {
@@ -118,7 +165,7 @@ pub fn load_structure(
     }
 }
 
-/// Saves the given structure.
+/// Saves the given structure, along with the block & item id tables needed to load it if those registries change.
 ///
 /// This is NOT how the structures are saved in the world, but rather used to get structure
 /// files that can be loaded through commands.
@@ -126,6 +173,8 @@ pub fn save_structure(
     structure: &Structure,
     file_name: &str,
     structure_type: StructureType,
+    blocks: &Registry<Block>,
+    items: &Registry<Item>,
 ) -> std::io::Result<()> {
     if let Err(e) = fs::create_dir("saves") {
         match e.kind() {
@@ -148,7 +197,11 @@ pub fn save_structure(
         }
     }
 
-    let serialized = cosmos_encoder::serialize(structure);
+    let serialized = cosmos_encoder::serialize(&(
+        IdMap::from_registry(blocks),
+        IdMap::from_registry(items),
+        structure,
+    ));
 
     fs::write(
         format!("saves/{}/{file_name}.cstr", structure_type.name()),
@@ -186,12 +239,19 @@ pub struct SaveStructure {
     pub structure_type: StructureType,
 }
 
-fn monitor_needs_saved(mut commands: Commands, query: Query<(Entity, &Structure, &SaveStructure)>) {
+fn monitor_needs_saved(
+    mut commands: Commands,
+    query: Query<(Entity, &Structure, &SaveStructure)>,
+    blocks: Res<Registry<Block>>,
+    items: Res<Registry<Item>>,
+) {
     for (entity, structure, save_structure_component) in query.iter() {
         match save_structure(
             structure,
             &save_structure_component.name,
             save_structure_component.structure_type,
+            &blocks,
+            &items,
         ) {
             Ok(_) => println!("Saved structure {}", save_structure_component.name),
             Err(e) => eprintln!(
@@ -0,0 +1,299 @@
+//! Splits pieces of a ship that were cut off from the rest of it into their own ships
+
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use bevy_rapier3d::prelude::{ReadMassProperties, Velocity};
+use cosmos_core::{
+    block::{blocks::AIR_BLOCK_ID, hardness::BlockHardness, Block},
+    events::block_events::{BlockChangedBatchEvent, BlockChangedEvent},
+    physics::location::Location,
+    registry::{identifiable::Identifiable, Registry},
+    structure::{
+        block_edit_batch::BlockEditBatch,
+        connectivity::disconnected_fragments,
+        loading::ChunksNeedLoaded,
+        ship::{
+            core::{MeltingDown, ShipCoreSet},
+            ship_builder::TShipBuilder,
+            Ship,
+        },
+        structure_block::StructureBlock,
+        structure_iterator::ChunkIteratorResult,
+        ChunkInitEvent, Structure,
+    },
+};
+
+use crate::{persistence::saving::NeedsSaved, state::GameState};
+
+use super::server_ship_builder::ServerShipBuilder;
+
+/// Sent when blocks are removed from a ship, so it can be checked for pieces that are no longer attached
+struct ShipBlocksRemovedEvent {
+    structure_entity: Entity,
+    removed: Vec<StructureBlock>,
+}
+
+/// A ship that was just split off of another ship & still needs its chunks initialized
+#[derive(Component)]
+struct FragmentNeedsLoaded;
+
+fn collect_removed_blocks(
+    mut block_event: EventReader<BlockChangedEvent>,
+    mut batch_event: EventReader<BlockChangedBatchEvent>,
+    // Pieces that break off a melting down ship go down with it
+    ship_query: Query<(), (With<Ship>, Without<MeltingDown>)>,
+    blocks: Res<Registry<Block>>,
+    mut event_writer: EventWriter<ShipBlocksRemovedEvent>,
+) {
+    let ship_core = blocks.from_id("cosmos:ship_core").map(|block| block.id());
+
+    let mut removed = HashMap::<Entity, Vec<StructureBlock>>::default();
+    // The core monitor may not have seen these yet, so these ships aren't marked as melting down
+    let mut lost_core = HashSet::<Entity>::default();
+
+    let removals = block_event
+        .iter()
+        .filter(|ev| ev.old_block != AIR_BLOCK_ID && ev.new_block == AIR_BLOCK_ID)
+        .map(|ev| (ev.structure_entity, ev.block, ev.old_block))
+        .chain(batch_event.iter().flat_map(|ev| {
+            ev.changes
+                .iter()
+                .filter(|change| {
+                    change.old_block != AIR_BLOCK_ID && change.new_block == AIR_BLOCK_ID
+                })
+                .map(move |change| (ev.structure_entity, change.block, change.old_block))
+        }));
+
+    for (structure_entity, block, old_block) in removals {
+        if ship_query.contains(structure_entity) {
+            removed.entry(structure_entity).or_default().push(block);
+
+            if Some(old_block) == ship_core {
+                lost_core.insert(structure_entity);
+            }
+        }
+    }
+
+    for (structure_entity, removed) in removed
+        .into_iter()
+        .filter(|(structure_entity, _)| !lost_core.contains(structure_entity))
+    {
+        event_writer.send(ShipBlocksRemovedEvent {
+            structure_entity,
+            removed,
+        });
+    }
+}
+
+/// Creates a structure holding just these blocks of the ship, along with their rotation, data & health.
+///
+/// The fragment uses the same dimensions + location as the ship, so its blocks can keep their coordinates.
+fn copy_fragment(
+    structure: &mut Structure,
+    fragment: &[StructureBlock],
+    blocks: &Registry<Block>,
+    hardness: &Registry<BlockHardness>,
+) -> Structure {
+    let mut fragment_structure = Structure::new(
+        structure.chunks_width(),
+        structure.chunks_height(),
+        structure.chunks_length(),
+    );
+
+    for block in fragment.iter() {
+        let (x, y, z) = (block.x, block.y, block.z);
+        let block_type = structure.block_at(x, y, z, blocks);
+
+        fragment_structure.set_block_at(
+            x,
+            y,
+            z,
+            block_type,
+            structure.block_rotation(x, y, z),
+            blocks,
+            None,
+        );
+
+        for (data_id, data) in structure.all_raw_block_data(x, y, z) {
+            fragment_structure.set_raw_block_data(x, y, z, data_id, Some(data.to_vec()), None);
+        }
+
+        if let Some(block_hardness) = hardness.from_id(block_type.unlocalized_name()) {
+            let health = structure.get_block_health(x, y, z, block_hardness);
+
+            fragment_structure.set_block_health(x, y, z, block_hardness, health, None);
+        }
+    }
+
+    fragment_structure
+}
+
+fn split_fragments(
+    mut event_reader: EventReader<ShipBlocksRemovedEvent>,
+    mut query: Query<
+        (
+            &mut Structure,
+            &Location,
+            &Transform,
+            &Velocity,
+            Option<&ReadMassProperties>,
+        ),
+        With<Ship>,
+    >,
+    blocks: Res<Registry<Block>>,
+    hardness: Res<Registry<BlockHardness>>,
+    mut batch_event_writer: EventWriter<BlockChangedBatchEvent>,
+    mut commands: Commands,
+) {
+    let ship_core = blocks.from_id("cosmos:ship_core").map(|block| block.id());
+
+    for ev in event_reader.iter() {
+        let Ok((mut structure, location, transform, velocity, mass_properties)) = query.get_mut(ev.structure_entity) else {
+            continue;
+        };
+
+        let center_of_mass = mass_properties
+            .map(|x| x.0.local_center_of_mass)
+            .unwrap_or(Vec3::ZERO);
+
+        // Whichever piece has the core stays this ship, so the ship & its pilot are never separated from their core
+        let fragments = disconnected_fragments(&structure, ev.removed.iter().copied(), |block| {
+            Some(block.block_id(&structure)) == ship_core
+        });
+
+        for fragment in fragments {
+            let mut fragment_structure =
+                copy_fragment(&mut structure, &fragment, &blocks, &hardness);
+
+            let mut removals = BlockEditBatch::new();
+            let mut center = Vec3::ZERO;
+
+            for block in fragment.iter() {
+                let (x, y, z) = (block.x, block.y, block.z);
+
+                removals.remove_block_at(x, y, z);
+                center += structure.block_relative_position(x, y, z);
+            }
+
+            center /= fragment.len() as f32;
+
+            structure.apply_block_edits(removals, &blocks, Some(&mut batch_event_writer));
+
+            // The fragment keeps moving the way its part of the ship was moving
+            let arm = transform.rotation * (center - center_of_mass);
+            let fragment_velocity = Velocity {
+                linvel: velocity.linvel + velocity.angvel.cross(arm),
+                angvel: velocity.angvel,
+            };
+
+            let mut entity_cmds = commands.spawn_empty();
+
+            ServerShipBuilder::default().insert_ship(
+                &mut entity_cmds,
+                *location,
+                fragment_velocity,
+                &mut fragment_structure,
+            );
+
+            entity_cmds.insert((
+                fragment_structure,
+                Transform::from_rotation(transform.rotation),
+                FragmentNeedsLoaded,
+                NeedsSaved,
+            ));
+        }
+    }
+}
+
+fn load_fragments(
+    query: Query<(Entity, &Structure), With<FragmentNeedsLoaded>>,
+    mut chunk_init_event_writer: EventWriter<ChunkInitEvent>,
+    mut commands: Commands,
+) {
+    for (entity, structure) in query.iter() {
+        let itr = structure.all_chunks_iter(false);
+
+        commands
+            .entity(entity)
+            .remove::<FragmentNeedsLoaded>()
+            .insert(ChunksNeedLoaded {
+                amount_needed: itr.len(),
+            });
+
+        for res in itr {
+            // This will always be true because include_empty is false
+            if let ChunkIteratorResult::FilledChunk {
+                position: (x, y, z),
+                chunk: _,
+            } = res
+            {
+                chunk_init_event_writer.send(ChunkInitEvent {
+                    structure_entity: entity,
+                    x,
+                    y,
+                    z,
+                });
+            }
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_event::<ShipBlocksRemovedEvent>().add_systems((
+        // Ships that just lost their core need to be marked as melting down before their removed blocks are collected
+        apply_system_buffers
+            .in_set(OnUpdate(GameState::Playing))
+            .after(ShipCoreSet::MonitorCores)
+            .before(collect_removed_blocks),
+        collect_removed_blocks
+            .in_set(OnUpdate(GameState::Playing))
+            .after(ShipCoreSet::MonitorCores),
+        split_fragments
+            .in_set(OnUpdate(GameState::Playing))
+            .after(collect_removed_blocks),
+        load_fragments.in_set(OnUpdate(GameState::Playing)),
+    ));
+}
+
+#[cfg(test)]
+mod test {
+    use cosmos_core::block::BlockRotation;
+
+    use super::*;
+
+    #[test]
+    fn fragments_keep_block_health() {
+        let mut blocks = Registry::<Block>::new();
+        blocks.register(Block::new(&vec![], 0, "cosmos:air".into(), 0.0));
+        blocks.register(Block::new(&vec![], 0, "cosmos:test".into(), 1.0));
+
+        let block = blocks.from_id("cosmos:test").unwrap();
+
+        let mut hardness = Registry::<BlockHardness>::new();
+        hardness.register(BlockHardness::new(block, 10.0, 0.0));
+        let block_hardness = hardness.from_id("cosmos:test").unwrap();
+
+        let mut structure = Structure::new(1, 1, 1);
+
+        for x in 0..3 {
+            structure.set_block_at(x, 0, 0, block, BlockRotation::IDENTITY, &blocks, None);
+        }
+
+        structure.set_block_health(1, 0, 0, block_hardness, 4.0, None);
+
+        let fragment = [StructureBlock::new(1, 0, 0), StructureBlock::new(2, 0, 0)];
+        let mut fragment_structure = copy_fragment(&mut structure, &fragment, &blocks, &hardness);
+
+        assert_eq!(
+            fragment_structure.get_block_health(1, 0, 0, block_hardness),
+            4.0
+        );
+        assert_eq!(
+            fragment_structure.get_block_health(2, 0, 0, block_hardness),
+            10.0
+        );
+        assert!(!fragment_structure.has_block_at(0, 0, 0));
+    }
+}
@@ -0,0 +1,241 @@
+//! Ships grow as they're built on.
+//!
+//! Growing can shift every block in a ship, so it only happens once every block event for the frame has been handled.
+//! That way every event in a frame uses the same coordinates. Clients may still send coordinates from before a resize
+//! they haven't heard about yet, so each ship remembers its recent resizes to move those coordinates to where they are now.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use cosmos_core::{
+    block::blocks::AIR_BLOCK_ID,
+    events::block_events::BlockChangedEvent,
+    structure::{
+        chunk::CHUNK_DIMENSIONS,
+        events::StructureResizedEvent,
+        ship::{Ship, SHIP_CHUNK_PADDING},
+        structure_block::StructureBlock,
+        Structure,
+    },
+};
+
+use crate::state::GameState;
+
+/// How many resizes a ship remembers. Coordinates from before the oldest one can't be fixed anymore.
+const MAX_REMEMBERED_RESIZES: usize = 16;
+
+#[derive(Component, Debug, Default)]
+/// The most recent resizes a ship went through, oldest first
+pub struct ResizeHistory {
+    /// (the ship's size in chunks before the resize, the chunks added in the negative directions)
+    resizes: VecDeque<((usize, usize, usize), (usize, usize, usize))>,
+}
+
+impl ResizeHistory {
+    fn record(&mut self, size_before: (usize, usize, usize), negative: (usize, usize, usize)) {
+        if self.resizes.len() == MAX_REMEMBERED_RESIZES {
+            self.resizes.pop_front();
+        }
+
+        self.resizes.push_back((size_before, negative));
+    }
+
+    /// Gets where this block is now, given its coordinates from when the ship was `size` chunks big.
+    ///
+    /// Ships only ever grow, so each size they've been is unique. If the ship hasn't been resized since it was that size
+    /// (or it was too long ago to remember), the coordinates are returned unchanged.
+    pub fn current_block(
+        &self,
+        size: (usize, usize, usize),
+        block: StructureBlock,
+    ) -> StructureBlock {
+        let Some(start) = self.resizes.iter().position(|(before, _)| *before == size) else {
+            return block;
+        };
+
+        self.resizes
+            .iter()
+            .skip(start)
+            .fold(block, |block, (_, (nx, ny, nz))| {
+                StructureBlock::new(
+                    block.x + nx * CHUNK_DIMENSIONS,
+                    block.y + ny * CHUNK_DIMENSIONS,
+                    block.z + nz * CHUNK_DIMENSIONS,
+                )
+            })
+    }
+}
+
+/// Gets where a block a client sent is now, in case the ship grew before the client heard about it
+///
+/// * `history` The structure's resize history, if it has ever been resized
+/// * `structure_size` The size in chunks the client thought the structure was
+pub fn block_from_client(
+    history: Option<&ResizeHistory>,
+    structure_size: (u32, u32, u32),
+    (x, y, z): (u32, u32, u32),
+) -> StructureBlock {
+    let block = StructureBlock::new(x as usize, y as usize, z as usize);
+
+    let Some(history) = history else {
+        return block;
+    };
+
+    let (w, h, l) = structure_size;
+
+    history.current_block((w as usize, h as usize, l as usize), block)
+}
+
+fn size(structure: &Structure) -> (usize, usize, usize) {
+    (
+        structure.chunks_width(),
+        structure.chunks_height(),
+        structure.chunks_length(),
+    )
+}
+
+/// Grows the ship so every one of these blocks has enough room around it, remembering every resize that happens.
+///
+/// The blocks' coordinates are all from before any growing.
+fn grow_to_fit_blocks(
+    structure: &mut Structure,
+    history: &mut ResizeHistory,
+    placed: &[StructureBlock],
+    mut event_writer: Option<&mut EventWriter<StructureResizedEvent>>,
+) {
+    let mut shift = (0, 0, 0);
+
+    for block in placed {
+        let block = StructureBlock::new(block.x + shift.0, block.y + shift.1, block.z + shift.2);
+        let size_before = size(structure);
+
+        let moved = structure.grow_to_fit(block, SHIP_CHUNK_PADDING, event_writer.as_deref_mut());
+
+        let moved_by = (moved.x - block.x, moved.y - block.y, moved.z - block.z);
+
+        if size(structure) != size_before {
+            history.record(
+                size_before,
+                (
+                    moved_by.0 / CHUNK_DIMENSIONS,
+                    moved_by.1 / CHUNK_DIMENSIONS,
+                    moved_by.2 / CHUNK_DIMENSIONS,
+                ),
+            );
+        }
+
+        shift = (
+            shift.0 + moved_by.0,
+            shift.1 + moved_by.1,
+            shift.2 + moved_by.2,
+        );
+    }
+}
+
+fn grow_ships(
+    mut event_reader: EventReader<BlockChangedEvent>,
+    mut query: Query<(&mut Structure, Option<&mut ResizeHistory>), With<Ship>>,
+    mut resized_event_writer: EventWriter<StructureResizedEvent>,
+    mut commands: Commands,
+) {
+    let mut placed = Vec::<(Entity, Vec<StructureBlock>)>::new();
+
+    for ev in event_reader
+        .iter()
+        .filter(|ev| ev.new_block != AIR_BLOCK_ID)
+    {
+        match placed
+            .iter_mut()
+            .find(|(entity, _)| *entity == ev.structure_entity)
+        {
+            Some((_, blocks)) => blocks.push(ev.block),
+            None => placed.push((ev.structure_entity, vec![ev.block])),
+        }
+    }
+
+    for (structure_entity, blocks) in placed {
+        let Ok((mut structure, history)) = query.get_mut(structure_entity) else {
+            continue;
+        };
+
+        match history {
+            Some(mut history) => grow_to_fit_blocks(
+                &mut structure,
+                &mut history,
+                &blocks,
+                Some(&mut resized_event_writer),
+            ),
+            None => {
+                let mut history = ResizeHistory::default();
+
+                grow_to_fit_blocks(
+                    &mut structure,
+                    &mut history,
+                    &blocks,
+                    Some(&mut resized_event_writer),
+                );
+
+                commands.entity(structure_entity).insert(history);
+            }
+        }
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    // Every block event for the frame has been handled by post update, so growing won't move any blocks they still need
+    app.add_system(
+        grow_ships
+            .in_base_set(CoreSet::PostUpdate)
+            .run_if(in_state(GameState::Playing)),
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use cosmos_core::{
+        block::{Block, BlockRotation},
+        registry::Registry,
+    };
+
+    use super::*;
+
+    #[test]
+    fn blocks_placed_in_one_frame_stay_put() {
+        let mut blocks = Registry::<Block>::new();
+        blocks.register(Block::new(&vec![], 0, "cosmos:air".into(), 0.0));
+        blocks.register(Block::new(&vec![], 0, "cosmos:test".into(), 1.0));
+
+        let block = blocks.from_id("cosmos:test").unwrap();
+
+        let mut structure = Structure::new(3, 3, 3);
+
+        // Both blocks are in the first chunk, so each one needs the ship to grow in the negative x direction
+        let placed = [
+            StructureBlock::new(0, 40, 40),
+            StructureBlock::new(1, 40, 40),
+        ];
+
+        for b in placed.iter() {
+            structure.set_block_at(b.x, b.y, b.z, block, BlockRotation::IDENTITY, &blocks, None);
+        }
+
+        let mut history = ResizeHistory::default();
+        grow_to_fit_blocks(&mut structure, &mut history, &placed, None);
+
+        assert_eq!(size(&structure), (4, 3, 3));
+
+        for b in placed.iter() {
+            let moved = history.current_block((3, 3, 3), *b);
+
+            assert_eq!(moved, StructureBlock::new(b.x + CHUNK_DIMENSIONS, b.y, b.z));
+            assert!(structure.has_block_at(moved.x, moved.y, moved.z));
+            assert!(!structure.has_block_at(b.x, b.y, b.z));
+        }
+
+        // Coordinates from after the resize are already right
+        assert_eq!(
+            history.current_block((4, 3, 3), StructureBlock::new(5, 6, 7)),
+            StructureBlock::new(5, 6, 7)
+        );
+    }
+}
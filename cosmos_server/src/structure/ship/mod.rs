@@ -3,6 +3,8 @@
 use bevy::prelude::App;
 
 mod change_pilot_event_listener;
+mod fragments;
+pub mod growth;
 pub mod loading;
 mod persistence;
 pub mod server_ship_builder;
@@ -10,6 +12,8 @@ mod sync;
 
 pub(super) fn register(app: &mut App) {
     change_pilot_event_listener::register(app);
+    fragments::register(app);
+    growth::register(app);
     loading::register(app);
     persistence::register(app);
     sync::register(app);
@@ -1,13 +1,23 @@
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::Velocity;
-use cosmos_core::structure::{
-    events::StructureLoadedEvent,
-    ship::{ship_builder::TShipBuilder, Ship},
-    structure_iterator::ChunkIteratorResult,
-    ChunkInitEvent, Structure,
+use cosmos_core::{
+    block::Block,
+    item::Item,
+    netty::cosmos_encoder,
+    registry::{id_map::IdMap, Registry},
+    structure::{
+        chunk::codec,
+        events::StructureLoadedEvent,
+        ship::{core::MeltingDown, ship_builder::TShipBuilder, Ship},
+        structure_iterator::ChunkIteratorResult,
+        systems::SystemsOffline,
+        ChunkInitEvent, Structure,
+    },
 };
 
 use crate::persistence::{
+    block_ids::{load_block_id_remap, save_block_ids},
+    item_ids::{load_item_id_remap, save_item_ids},
     loading::{begin_loading, done_loading, NeedsLoaded},
     saving::{begin_saving, done_saving, NeedsSaved},
     SerializedData,
@@ -16,17 +26,37 @@ use crate::persistence::{
 use super::server_ship_builder::ServerShipBuilder;
 
 fn on_save_structure(
-    mut query: Query<(&mut SerializedData, &Structure), (With<NeedsSaved>, With<Ship>)>,
+    mut query: Query<
+        (&mut SerializedData, &Structure, Option<&MeltingDown>),
+        (With<NeedsSaved>, With<Ship>),
+    >,
+    blocks: Res<Registry<Block>>,
+    items: Res<Registry<Item>>,
 ) {
-    for (mut s_data, structure) in query.iter_mut() {
+    if query.is_empty() {
+        return;
+    }
+
+    let block_ids = IdMap::from_registry(&blocks);
+    let item_ids = IdMap::from_registry(&items);
+
+    for (mut s_data, structure, melting_down) in query.iter_mut() {
         s_data.serialize_data("cosmos:structure", structure);
+        save_block_ids(&mut s_data, &block_ids);
+        save_item_ids(&mut s_data, &item_ids);
         s_data.serialize_data("cosmos:is_ship", &true);
+
+        if let Some(melting_down) = melting_down {
+            s_data.serialize_data("cosmos:melting_down", melting_down);
+        }
     }
 }
 
 fn on_load_structure(
     query: Query<(Entity, &SerializedData), With<NeedsLoaded>>,
     mut event_writer: EventWriter<DelayedStructureLoadEvent>,
+    blocks: Res<Registry<Block>>,
+    items: Res<Registry<Item>>,
     mut commands: Commands,
 ) {
     for (entity, s_data) in query.iter() {
@@ -34,7 +64,19 @@ fn on_load_structure(
             .deserialize_data::<bool>("cosmos:is_ship")
             .unwrap_or(false)
         {
-            if let Some(mut structure) = s_data.deserialize_data::<Structure>("cosmos:structure") {
+            if let Some(mut structure) = s_data.read_data("cosmos:structure").and_then(|data| {
+                cosmos_encoder::deserialize::<Structure>(data)
+                    .ok()
+                    .or_else(|| codec::decode_legacy_structure(data).ok())
+            }) {
+                if let Some(remap) = load_block_id_remap(s_data, &blocks, "Ship") {
+                    structure.remap_block_ids(&remap);
+                }
+
+                if let Some(remap) = load_item_id_remap(s_data, &items, "Ship") {
+                    structure.remap_item_ids(&remap);
+                }
+
                 let loc = s_data
                     .deserialize_data("cosmos:location")
                     .expect("Every ship should have a location when saved!");
@@ -49,6 +91,12 @@ fn on_load_structure(
 
                 builder.insert_ship(&mut entity_cmd, loc, vel, &mut structure);
 
+                if let Some(melting_down) =
+                    s_data.deserialize_data::<MeltingDown>("cosmos:melting_down")
+                {
+                    entity_cmd.insert((melting_down, SystemsOffline));
+                }
+
                 let entity = entity_cmd.id();
 
                 event_writer.send(DelayedStructureLoadEvent(entity));
@@ -11,7 +11,7 @@ use cosmos_core::{
     structure::{
         systems::{
             energy_storage_system::EnergyStorageSystem, laser_cannon_system::LaserCannonSystem,
-            StructureSystem, SystemActive, Systems,
+            StructureSystem, SystemActive, Systems, SystemsOffline,
         },
         Structure,
     },
@@ -25,14 +25,17 @@ const LASER_SHOOT_SECONDS: f32 = 0.2;
 fn update_system(
     mut query: Query<(&mut LaserCannonSystem, &StructureSystem), With<SystemActive>>,
     mut es_query: Query<&mut EnergyStorageSystem>,
-    systems: Query<(
-        &Systems,
-        &Structure,
-        &Location,
-        &GlobalTransform,
-        &Velocity,
-        Option<&PhysicsWorld>,
-    )>,
+    systems: Query<
+        (
+            &Systems,
+            &Structure,
+            &Location,
+            &GlobalTransform,
+            &Velocity,
+            Option<&PhysicsWorld>,
+        ),
+        Without<SystemsOffline>,
+    >,
     time: Res<Time>,
     mut commands: Commands,
     mut server: ResMut<RenetServer>,
@@ -63,14 +66,10 @@ fn update_system(
                                 location,
                             );
 
-                            // AT SOME POINT, THE NEGATIVE SIGN HAS TO BE REMOVED HERE!!!!!
-                            // I SHOULD NOT HAVE TO NEGATE THE DIRECTION
-                            // SINCE THERE IS NO WAY TO ROTATE THE CANNONS, FOR NOW THIS HAS
-                            // TO BE HERE, BUT ONCE CANNONS CAN BE ROTATED, REMOVE THIS!
                             let laser_velocity = global_transform
                                 .affine()
                                 .matrix3
-                                .mul_vec3(-line.direction.direction_vec3())
+                                .mul_vec3(line.direction.direction_vec3())
                                 * LASER_BASE_VELOCITY;
 
                             let strength = (5.0 * line.len as f32).powf(1.2);
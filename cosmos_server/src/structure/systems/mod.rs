@@ -3,7 +3,9 @@
 use bevy::prelude::App;
 
 mod laser_cannon_system;
+mod repair_system;
 
 pub(super) fn register(app: &mut App) {
     laser_cannon_system::register(app);
+    repair_system::register(app);
 }
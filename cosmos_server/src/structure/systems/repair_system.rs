@@ -0,0 +1,103 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use cosmos_core::{
+    block::{hardness::BlockHardness, Block},
+    registry::{identifiable::Identifiable, Registry},
+    structure::{
+        block_health::block_health_changed_event::BlockHealthChangedEvent,
+        structure_block::StructureBlock,
+        systems::{
+            energy_storage_system::EnergyStorageSystem, repair_system::RepairSystem,
+            StructureSystem, Systems, SystemsOffline,
+        },
+        Structure,
+    },
+};
+
+use crate::state::GameState;
+
+/// How often repair modules repair blocks
+const REPAIR_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Repairs the damaged blocks of every structure with repair modules, paying for it with energy.
+///
+/// A structure can only repair as much as its energy allows, and stops once every block is back to full health.
+fn update_system(
+    query: Query<(&RepairSystem, &StructureSystem)>,
+    mut es_query: Query<&mut EnergyStorageSystem>,
+    mut structures: Query<(&Systems, &mut Structure), Without<SystemsOffline>>,
+    blocks: Res<Registry<Block>>,
+    hardness: Res<Registry<BlockHardness>>,
+    mut event_writer: EventWriter<BlockHealthChangedEvent>,
+) {
+    for (repair_system, system) in query.iter() {
+        if repair_system.repair_rate() <= 0.0 {
+            continue;
+        }
+
+        let Ok((systems, mut structure)) = structures.get_mut(system.structure_entity) else {
+            continue;
+        };
+
+        let Ok(mut energy_storage_system) = systems.query_mut(&mut es_query) else {
+            continue;
+        };
+
+        let damaged = structure
+            .all_damaged_blocks()
+            .collect::<Vec<(StructureBlock, f32)>>();
+
+        if damaged.is_empty() {
+            continue;
+        }
+
+        let mut budget = repair_system.repair_rate() * REPAIR_INTERVAL.as_secs_f32();
+
+        let energy_cost = repair_system.energy_cost(budget);
+        if energy_cost > energy_storage_system.get_energy() {
+            budget *= energy_storage_system.get_energy() / energy_cost;
+        }
+
+        let mut repaired = 0.0;
+
+        for (block, health) in damaged {
+            if budget <= 0.0 {
+                break;
+            }
+
+            let block_type = block.block(&structure, &blocks);
+            let Some(block_hardness) = hardness.from_id(block_type.unlocalized_name()) else {
+                continue;
+            };
+
+            let amount = (block_hardness.hardness() - health).min(budget);
+
+            if amount <= 0.0 {
+                continue;
+            }
+
+            budget -= amount;
+            repaired += amount;
+
+            structure.set_block_health(
+                block.x,
+                block.y,
+                block.z,
+                block_hardness,
+                health + amount,
+                Some(&mut event_writer),
+            );
+        }
+
+        energy_storage_system.decrease_energy(repair_system.energy_cost(repaired));
+    }
+}
+
+pub(super) fn register(app: &mut App) {
+    app.add_system(
+        update_system
+            .run_if(on_timer(REPAIR_INTERVAL))
+            .in_set(OnUpdate(GameState::Playing)),
+    );
+}